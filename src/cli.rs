@@ -1,23 +1,53 @@
-use crate::clock::SystemClock;
+use crate::clock::{Clock, SystemClock};
 use crate::command_handler::CommandHandler;
-use crate::repository::FileRepository;
-use crate::session_service::SessionService;
-use crate::validators::ProjectValidator;
-use anyhow::Result;
-use std::io::{self, Write};
+use crate::exporters::ExportFormat;
+use crate::git;
+use crate::importers::ImportFormat;
+use crate::parsers::{DateParser, DurationParser, TimeParser, TimeSpec};
+use crate::render::bar::BarFormat;
+use crate::render::{prompt, OutputFormat, ReportOptions};
+use crate::repository::history::History;
+use crate::repository::{self, StorageBackend};
+use crate::session_service::{ReportPeriod, SessionService};
+use crate::validators::{NoteValidator, ProjectValidator, TagValidator};
+use anyhow::{anyhow, Context, Result};
+use std::io::{self, Read, Write};
+use std::path::PathBuf;
 
 pub struct CLI {
     handler: CommandHandler,
 }
 
 impl CLI {
-    pub fn new() -> Self {
-        let repository = Box::new(FileRepository::new());
+    /// `output` selects how results are printed, see [`OutputFormat`]
+    pub fn new(output: &str) -> Result<Self> {
+        let output: OutputFormat = output.parse()?;
+        let data_dir = repository::find_data_dir();
+        let repository = repository::open_repository()?;
+        let history = History::new(&data_dir);
         let clock = Box::new(SystemClock);
-        let service = SessionService::new(repository, clock);
-        let handler = CommandHandler::new(service);
+        let service = SessionService::new(repository, clock).with_history(history);
+        let handler = CommandHandler::new(service).with_output(output);
 
-        Self { handler }
+        Ok(Self { handler })
+    }
+
+    /// Prints the prompt line for the enclosing project. Nothing is printed
+    /// outside a project or when its data cannot be read: a prompt is no place
+    /// for error messages.
+    pub fn print_prompt(format: &str) {
+        let Some(data_dir) = repository::enclosing_data_dir() else {
+            return;
+        };
+        let Ok(project) = repository::read_project(&data_dir) else {
+            return;
+        };
+
+        let status = SessionService::status_at(&project, SystemClock.now());
+        let line = prompt::render(format, &status);
+        if !line.is_empty() {
+            println!("{}", line);
+        }
     }
 
     pub fn handle_init(&self, name: Option<String>) -> Result<()> {
@@ -37,19 +67,261 @@ impl CLI {
         self.handler.handle_init(project_name)
     }
 
-    pub fn handle_now(&self) -> Result<()> {
-        self.handler.handle_clock_in()
+    pub fn handle_now(
+        &self,
+        at: Option<String>,
+        ago: Option<String>,
+        message: Option<String>,
+        tags: Vec<String>,
+    ) -> Result<()> {
+        let at = Self::parse_time_spec(at, ago)?;
+        let message = Self::validate_note(message)?;
+        Self::validate_tags(&tags)?;
+        self.handler.handle_clock_in(at, message, tags)
+    }
+
+    pub fn handle_out(
+        &self,
+        at: Option<String>,
+        ago: Option<String>,
+        message: Option<String>,
+    ) -> Result<()> {
+        let at = Self::parse_time_spec(at, ago)?;
+        let message = Self::validate_note(message)?;
+        self.handler.handle_clock_out(at, message)
+    }
+
+    pub fn handle_break(
+        &self,
+        at: Option<String>,
+        ago: Option<String>,
+        message: Option<String>,
+    ) -> Result<()> {
+        let at = Self::parse_time_spec(at, ago)?;
+        let message = Self::validate_note(message)?;
+        self.handler.handle_break(at, message)
+    }
+
+    pub fn handle_note(&self, note: String, append: bool) -> Result<()> {
+        NoteValidator::validate(&note)?;
+        self.handler.handle_note(note.trim().to_string(), append)
+    }
+
+    pub fn handle_add(
+        &self,
+        duration: String,
+        date: Option<String>,
+        at: Option<String>,
+        note: Option<String>,
+        tags: Vec<String>,
+    ) -> Result<()> {
+        let duration = DurationParser::parse(&duration)?;
+        let date = date.as_deref().map(DateParser::parse).transpose()?;
+        let at = at.as_deref().map(TimeParser::parse).transpose()?;
+        let note = Self::validate_note(note)?;
+        Self::validate_tags(&tags)?;
+
+        self.handler.handle_add(duration, date, at, note, tags)
+    }
+
+    pub fn handle_status(&self, all: bool) -> Result<()> {
+        self.handler.handle_status(all)
+    }
+
+    pub fn handle_bar(
+        &self,
+        format: String,
+        goal: String,
+        text: String,
+        watch: Option<u64>,
+    ) -> Result<()> {
+        let format: BarFormat = format.parse()?;
+        let goal = DurationParser::parse(&goal)?;
+        if watch == Some(0) {
+            return Err(anyhow!("--watch needs an interval of at least 1 second"));
+        }
+
+        self.handler.handle_bar(format, goal, &text, watch)
+    }
+
+    pub fn handle_git_install_hooks(&self, auto_start: bool) -> Result<()> {
+        self.handler.handle_git_install_hooks(auto_start)
+    }
+
+    pub fn handle_git_post_commit(&self, auto_start: bool) -> Result<()> {
+        self.handler.handle_git_post_commit(auto_start)
+    }
+
+    /// git passes the previous HEAD, the new HEAD and 1 for a branch checkout
+    /// (0 for checking out files). A clone reports a null previous HEAD.
+    pub fn handle_git_post_checkout(
+        &self,
+        previous: String,
+        branch_checkout: String,
+    ) -> Result<()> {
+        let is_clone = previous.chars().all(|c| c == '0');
+        if branch_checkout != "1" || is_clone {
+            return Ok(());
+        }
+        self.handler.handle_git_post_checkout()
+    }
+
+    /// git writes the rewritten commits to stdin
+    pub fn handle_git_post_rewrite(&self) -> Result<()> {
+        let mut input = String::new();
+        io::stdin()
+            .read_to_string(&mut input)
+            .context("Failed to read rewritten commits")?;
+        self.handler
+            .handle_git_post_rewrite(&git::parse_rewrites(&input))
+    }
+
+    pub fn handle_projects(&self, prune: bool, add: bool) -> Result<()> {
+        self.handler.handle_projects(prune, add)
+    }
+
+    pub fn handle_report(
+        &self,
+        by: String,
+        from: Option<String>,
+        to: Option<String>,
+        tags: bool,
+        commits: bool,
+    ) -> Result<()> {
+        let period: ReportPeriod = by.parse()?;
+        let from = from.as_deref().map(DateParser::parse).transpose()?;
+        let to = to.as_deref().map(DateParser::parse).transpose()?;
+
+        let options = ReportOptions { tags, commits };
+        self.handler.handle_report(period, from, to, options)
+    }
+
+    pub fn handle_tag(
+        &self,
+        tags: Vec<String>,
+        session: Option<String>,
+        remove: bool,
+    ) -> Result<()> {
+        Self::validate_tags(&tags)?;
+        self.handler.handle_tag(tags, session, remove)
+    }
+
+    pub fn handle_log(
+        &self,
+        from: Option<String>,
+        to: Option<String>,
+        limit: usize,
+        breaks: bool,
+    ) -> Result<()> {
+        let from = from.as_deref().map(DateParser::parse).transpose()?;
+        let to = to.as_deref().map(DateParser::parse).transpose()?;
+
+        self.handler.handle_log(from, to, limit, breaks)
+    }
+
+    pub fn handle_edit(
+        &self,
+        id: String,
+        start: Option<String>,
+        end: Option<String>,
+        break_id: Option<String>,
+    ) -> Result<()> {
+        if start.is_none() && end.is_none() {
+            return Err(anyhow!("Nothing to change. Pass --start and/or --end."));
+        }
+        let start = start.as_deref().map(TimeSpec::parse_at).transpose()?;
+        let end = end.as_deref().map(TimeSpec::parse_at).transpose()?;
+
+        self.handler.handle_edit(id, start, end, break_id)
+    }
+
+    pub fn handle_delete(&self, id: String, break_id: Option<String>) -> Result<()> {
+        self.handler.handle_delete(id, break_id)
+    }
+
+    pub fn handle_split(&self, id: String, at: String) -> Result<()> {
+        let at = TimeSpec::parse_at(&at)?;
+        self.handler.handle_split(id, at)
+    }
+
+    pub fn handle_merge(&self, first: String, second: String, force: bool) -> Result<()> {
+        self.handler.handle_merge(first, second, force)
     }
 
-    pub fn handle_out(&self) -> Result<()> {
-        self.handler.handle_clock_out()
+    pub fn handle_export(
+        &self,
+        format: String,
+        from: Option<String>,
+        to: Option<String>,
+        output: Option<PathBuf>,
+        breaks: bool,
+    ) -> Result<()> {
+        let format: ExportFormat = format.parse()?;
+        let from = from.as_deref().map(DateParser::parse).transpose()?;
+        let to = to.as_deref().map(DateParser::parse).transpose()?;
+
+        self.handler.handle_export(format, from, to, output, breaks)
     }
 
-    pub fn handle_break(&self) -> Result<()> {
-        self.handler.handle_break()
+    pub fn handle_import(
+        &self,
+        format: String,
+        file: PathBuf,
+        account: Option<String>,
+        breaks: bool,
+    ) -> Result<()> {
+        let format: ImportFormat = format.parse()?;
+        self.handler.handle_import(format, file, account, breaks)
+    }
+
+    pub fn handle_migrate_storage(&self, to: String) -> Result<()> {
+        let to: StorageBackend = to.parse()?;
+        self.handler.handle_migrate_storage(to)
+    }
+
+    pub fn handle_doctor(&self, fix: bool) -> Result<()> {
+        self.handler.handle_doctor(fix)
+    }
+
+    pub fn handle_undo(&self, steps: usize) -> Result<()> {
+        Self::validate_steps(steps)?;
+        self.handler.handle_undo(steps)
+    }
+
+    pub fn handle_redo(&self, steps: usize) -> Result<()> {
+        Self::validate_steps(steps)?;
+        self.handler.handle_redo(steps)
+    }
+
+    fn validate_steps(steps: usize) -> Result<()> {
+        if steps == 0 {
+            return Err(anyhow!("Number of steps must be at least 1"));
+        }
+        Ok(())
+    }
+
+    fn validate_tags(tags: &[String]) -> Result<()> {
+        for tag in tags {
+            TagValidator::validate_name(tag)?;
+        }
+        Ok(())
+    }
+
+    fn validate_note(note: Option<String>) -> Result<Option<String>> {
+        match note {
+            Some(note) => {
+                NoteValidator::validate(&note)?;
+                Ok(Some(note.trim().to_string()))
+            }
+            None => Ok(None),
+        }
     }
 
-    pub fn handle_status(&self) -> Result<()> {
-        self.handler.handle_status()
+    fn parse_time_spec(at: Option<String>, ago: Option<String>) -> Result<Option<TimeSpec>> {
+        match (at, ago) {
+            (Some(at), _) => TimeSpec::parse_at(&at).map(Some),
+            (None, Some(ago)) => TimeSpec::parse_ago(&ago).map(Some),
+            (None, None) => Ok(None),
+        }
     }
 }
@@ -1,205 +1,705 @@
-use crate::parsers::DurationParser;
-use crate::session_service::SessionService;
-use anyhow::Result;
+use crate::exporters::csv::CsvExporter;
+use crate::exporters::ics::IcsExporter;
+use crate::exporters::timeclock::TimeclockExporter;
+use crate::exporters::{ExportFormat, Exporter};
+use crate::git::{self, HookChange};
+use crate::importers::timeclock::TimeclockImporter;
+use crate::importers::timewarrior::TimewarriorImporter;
+use crate::importers::{ImportFormat, Importer};
+use crate::models::integrity::Issue;
+use crate::models::r#break::Break;
+use crate::models::session::Session;
+use crate::parsers::{DurationParser, TimeSpec};
+use crate::render::bar::{BarFormat, BarStatus};
+use crate::render::{OutputFormat, Renderer, ReportOptions};
+use crate::repository::registry::{RegisteredProject, Registry};
+use crate::repository::{self, StorageBackend};
+use crate::session_service::{CommitOutcome, ReportPeriod, SessionService, StatusInfo};
+use anyhow::{anyhow, Context, Result};
+use chrono::{Duration, NaiveDate, NaiveTime};
+use std::fs;
+use std::io::{self, Write};
+use std::path::PathBuf;
+use std::thread;
 
 pub struct CommandHandler {
     session_service: SessionService,
+    renderer: Box<dyn Renderer>,
 }
 
 impl CommandHandler {
     pub fn new(session_service: SessionService) -> Self {
-        Self { session_service }
+        Self {
+            session_service,
+            renderer: OutputFormat::Text.renderer(),
+        }
+    }
+
+    pub fn with_output(mut self, output: OutputFormat) -> Self {
+        self.renderer = output.renderer();
+        self
     }
 
     pub fn handle_init(&self, project_name: String) -> Result<()> {
-        self.session_service.init_project(project_name)?;
-        println!("✓ Project initialized successfully!");
-        println!("You can now use 'clock-me now' to start tracking time.");
+        self.session_service.init_project(project_name.clone())?;
+        if let Err(e) = Registry::open().and_then(|r| r.register(&repository::find_data_dir())) {
+            eprintln!("⚠ Could not add the project to the registry: {}", e);
+        }
+        print!("{}", self.renderer.init(&project_name)?);
         Ok(())
     }
 
-    pub fn handle_clock_in(&self) -> Result<()> {
-        let project = self.session_service.start_session()?;
+    pub fn handle_clock_in(
+        &self,
+        at: Option<TimeSpec>,
+        note: Option<String>,
+        tags: Vec<String>,
+    ) -> Result<()> {
+        let clock_in = self.session_service.start_session(at, note, tags)?;
+        print!("{}", self.renderer.clock_in(&clock_in)?);
+        Ok(())
+    }
 
-        // Check if we just ended a break
-        if project.current_session.is_some()
-            && project.current_session.as_ref().unwrap().breaks.len() > 0
-            && !project.is_on_break()
-        {
-            println!("✓ Break ended, continuing work on: {}", project.name);
+    pub fn handle_clock_out(&self, at: Option<TimeSpec>, note: Option<String>) -> Result<()> {
+        let clock_out = self.session_service.end_session(at, note)?;
+        print!("{}", self.renderer.clock_out(&clock_out)?);
+        Ok(())
+    }
 
-            if let Some(ref session) = project.current_session {
-                if let Some(last_break) = session.breaks.last() {
-                    if let Some(duration) = last_break.duration() {
-                        println!("Break duration: {}", DurationParser::format(duration));
-                    }
-                }
+    pub fn handle_break(&self, at: Option<TimeSpec>, note: Option<String>) -> Result<()> {
+        let started = self.session_service.start_break(at, note)?;
+        print!("{}", self.renderer.break_started(&started)?);
+        Ok(())
+    }
 
-                // Show accumulated break time in this session
-                let total_break_time = session.total_break_time();
-                if total_break_time.num_minutes() > 0 {
-                    println!(
-                        "Total break time this session: {}",
-                        DurationParser::format(total_break_time)
-                    );
-                }
-            }
+    pub fn handle_add(
+        &self,
+        duration: Duration,
+        date: Option<NaiveDate>,
+        at: Option<NaiveTime>,
+        note: Option<String>,
+        tags: Vec<String>,
+    ) -> Result<()> {
+        let (project, session) = self
+            .session_service
+            .add_session(duration, date, at, note, tags)?;
+        println!(
+            "✓ Added {} to project: {}",
+            DurationParser::format(duration),
+            project.name
+        );
+        println!(
+            "  {} – {}",
+            session.start.format("%Y-%m-%d %H:%M"),
+            session.end.unwrap().format("%H:%M")
+        );
+        if let Some(ref note) = session.note {
+            println!("  Note: {}", note);
+        }
+        if !session.tags.is_empty() {
+            println!("  Tags: {}", session.tags.join(", "));
+        }
+        Ok(())
+    }
+
+    pub fn handle_tag(
+        &self,
+        tags: Vec<String>,
+        session: Option<String>,
+        remove: bool,
+    ) -> Result<()> {
+        let session = self.session_service.tag_session(tags, session, remove)?;
+        println!(
+            "✓ Tags updated for session started {}",
+            session.start.format("%Y-%m-%d %H:%M")
+        );
+        if session.tags.is_empty() {
+            println!("Tags: (none)");
         } else {
-            println!("✓ Clocked in to project: {}", project.name);
-            println!(
-                "Started tracking time at {}",
-                project
-                    .current_session
-                    .as_ref()
-                    .unwrap()
-                    .start
-                    .format("%H:%M:%S")
-            );
+            println!("Tags: {}", session.tags.join(", "));
+        }
+        Ok(())
+    }
+
+    pub fn handle_log(
+        &self,
+        from: Option<NaiveDate>,
+        to: Option<NaiveDate>,
+        limit: usize,
+        show_breaks: bool,
+    ) -> Result<()> {
+        let (project_name, sessions) = self.session_service.get_log(from, to, limit)?;
+
+        if sessions.is_empty() {
+            println!("No finished sessions in {}", project_name);
+            return Ok(());
+        }
+
+        for session in &sessions {
+            println!("{}", Self::describe_session(session));
+            if show_breaks {
+                Self::print_breaks(session);
+            }
         }
         Ok(())
     }
 
-    pub fn handle_clock_out(&self) -> Result<()> {
-        let (project, duration) = self.session_service.end_session()?;
-        println!("✓ Clocked out from project: {}", project.name);
+    pub fn handle_edit(
+        &self,
+        id: String,
+        start: Option<TimeSpec>,
+        end: Option<TimeSpec>,
+        break_id: Option<String>,
+    ) -> Result<()> {
+        let session = match break_id {
+            Some(break_id) => self
+                .session_service
+                .edit_break(&id, &break_id, start, end)?,
+            None => self.session_service.edit_session(&id, start, end)?,
+        };
 
-        println!("Session work time: {}", DurationParser::format(duration));
-        println!("  ({:.2} hours)", duration.num_minutes() as f64 / 60.0);
+        println!("✓ Session updated");
+        println!("{}", Self::describe_session(&session));
+        Self::print_breaks(&session);
+        Ok(())
+    }
 
-        // Show break info if there were breaks
-        if let Some(last_session) = project.sessions.last() {
-            let break_time = last_session.total_break_time();
-            if break_time.num_minutes() > 0 {
-                println!("Break time: {}", DurationParser::format(break_time));
-                println!("  (Breaks taken: {})", last_session.breaks.len());
+    pub fn handle_delete(&self, id: String, break_id: Option<String>) -> Result<()> {
+        match break_id {
+            Some(break_id) => {
+                let break_period = self.session_service.delete_break(&id, &break_id)?;
+                println!("✓ Deleted break {}", Self::describe_break(&break_period));
+            }
+            None => {
+                let session = self.session_service.delete_session(&id)?;
+                println!("✓ Deleted session");
+                println!("{}", Self::describe_session(&session));
             }
         }
+        println!("Use 'clock-me undo' to restore it.");
+        Ok(())
+    }
+
+    pub fn handle_split(&self, id: String, at: TimeSpec) -> Result<()> {
+        let (first, second) = self.session_service.split_session(&id, at)?;
+        println!("✓ Split session in two");
+        println!("{}", Self::describe_session(&first));
+        println!("{}", Self::describe_session(&second));
+        Ok(())
+    }
 
+    pub fn handle_merge(&self, first: String, second: String, force: bool) -> Result<()> {
+        let session = self
+            .session_service
+            .merge_sessions(&first, &second, force)?;
+        println!("✓ Merged sessions");
+        println!("{}", Self::describe_session(&session));
         Ok(())
     }
 
-    pub fn handle_break(&self) -> Result<()> {
-        let (project, work_time_before_break) = self.session_service.start_break()?;
-        println!("✓ Break started for project: {}", project.name);
+    /// One `log` line: ID, date, times, work time, breaks, tags and note
+    fn describe_session(session: &Session) -> String {
+        let end = session
+            .end
+            .map(|e| e.format("%H:%M").to_string())
+            .unwrap_or_default();
+        let mut line = format!(
+            "{}  {}  {}–{}  {:>8}",
+            session.short_id(),
+            session.start.format("%Y-%m-%d"),
+            session.start.format("%H:%M"),
+            end,
+            DurationParser::format(session.work_time().unwrap_or_else(Duration::zero))
+        );
+
+        match session.breaks.len() {
+            0 => {}
+            1 => line.push_str("  (1 break)"),
+            n => line.push_str(&format!("  ({} breaks)", n)),
+        }
+        if !session.tags.is_empty() {
+            line.push_str(&format!("  [{}]", session.tags.join(", ")));
+        }
+        if let Some(ref note) = session.note {
+            line.push_str(&format!("  {}", note));
+        }
+        line
+    }
+
+    fn print_breaks(session: &Session) {
+        for (index, break_period) in session.breaks.iter().enumerate() {
+            println!("    {}. {}", index + 1, Self::describe_break(break_period));
+        }
+    }
+
+    fn describe_break(break_period: &Break) -> String {
+        let end = break_period
+            .end
+            .map(|e| e.format("%H:%M").to_string())
+            .unwrap_or_default();
+        let mut line = format!(
+            "{}  {}–{}",
+            break_period.short_id(),
+            break_period.start.format("%H:%M"),
+            end
+        );
+        if let Some(ref note) = break_period.note {
+            line.push_str(&format!("  {}", note));
+        }
+        line
+    }
+
+    pub fn handle_note(&self, note: String, append: bool) -> Result<()> {
+        let session = self.session_service.set_note(note, append)?;
+        let target = if session.is_active() {
+            "active session"
+        } else {
+            "last session"
+        };
         println!(
-            "Work time before break: {}",
-            DurationParser::format(work_time_before_break)
+            "✓ Note updated for {} (started {})",
+            target,
+            session.start.format("%Y-%m-%d %H:%M")
         );
+        if let Some(ref note) = session.note {
+            println!("Note: {}", note);
+        }
+        Ok(())
+    }
+
+    pub fn handle_status(&self, all: bool) -> Result<()> {
+        if all {
+            return self.handle_status_all();
+        }
+        let status = self.session_service.get_status()?;
+        print!("{}", self.renderer.status(&status)?);
+        Ok(())
+    }
+
+    /// Prints one line for a status bar, or one every `watch` seconds until
+    /// the bar closes the pipe
+    pub fn handle_bar(
+        &self,
+        format: BarFormat,
+        goal: Duration,
+        text: &str,
+        watch: Option<u64>,
+    ) -> Result<()> {
+        let Some(interval) = watch else {
+            let status = self.session_service.get_status()?;
+            println!("{}", BarStatus::new(&status, goal, text).render(format));
+            return Ok(());
+        };
+
+        let mut stdout = io::stdout();
+        loop {
+            // A failed read is reported but does not end the module; the next
+            // one usually succeeds
+            match self.session_service.get_status() {
+                Ok(status) => {
+                    let line = BarStatus::new(&status, goal, text).render(format);
+                    match writeln!(stdout, "{}", line).and_then(|_| stdout.flush()) {
+                        Err(e) if e.kind() == io::ErrorKind::BrokenPipe => return Ok(()),
+                        result => result?,
+                    }
+                }
+                Err(e) => eprintln!("Error: {}", e),
+            }
+            thread::sleep(std::time::Duration::from_secs(interval));
+        }
+    }
+
+    pub fn handle_report(
+        &self,
+        period: ReportPeriod,
+        from: Option<NaiveDate>,
+        to: Option<NaiveDate>,
+        options: ReportOptions,
+    ) -> Result<()> {
+        let report = self.session_service.get_report(period, from, to)?;
+        print!("{}", self.renderer.report(&report, options)?);
+        Ok(())
+    }
 
-        if let Some(ref session) = project.current_session {
-            let accumulated_breaks = session.total_break_time();
-            if accumulated_breaks.num_minutes() > 0 {
+    pub fn handle_export(
+        &self,
+        format: ExportFormat,
+        from: Option<NaiveDate>,
+        to: Option<NaiveDate>,
+        output: Option<PathBuf>,
+        breaks: bool,
+    ) -> Result<()> {
+        let (project_name, sessions) = self.session_service.get_sessions(from, to)?;
+
+        let exporter: Box<dyn Exporter> = match format {
+            ExportFormat::Csv if breaks => Box::new(CsvExporter::breaks()),
+            ExportFormat::Csv => Box::new(CsvExporter::sessions()),
+            ExportFormat::Ics => Box::new(IcsExporter::new(breaks, self.session_service.now())),
+            ExportFormat::Timeclock => Box::new(TimeclockExporter),
+        };
+        let document = exporter.export(&project_name, &sessions);
+
+        match output {
+            Some(path) => {
+                fs::write(&path, document)
+                    .with_context(|| format!("Failed to write {}", path.display()))?;
                 println!(
-                    "Previous breaks this session: {}",
-                    DurationParser::format(accumulated_breaks)
+                    "✓ Exported {} sessions to {}",
+                    sessions.len(),
+                    path.display()
                 );
             }
+            None => {
+                io::stdout()
+                    .write_all(document.as_bytes())
+                    .context("Failed to write export")?;
+            }
         }
 
-        println!("\nUse 'clock-me start' to continue working");
         Ok(())
     }
 
-    pub fn handle_status(&self) -> Result<()> {
-        let status = self.session_service.get_status()?;
+    pub fn handle_import(
+        &self,
+        format: ImportFormat,
+        file: PathBuf,
+        account: Option<String>,
+        breaks: bool,
+    ) -> Result<()> {
+        let content = Self::read_import_source(&file, format)?;
 
-        println!("━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━");
-        println!("Project: {}", status.project_name);
+        let importer: Box<dyn Importer> = match format {
+            ImportFormat::Timeclock => Box::new(TimeclockImporter::new(account)),
+            ImportFormat::Timewarrior => Box::new(TimewarriorImporter::new(breaks)),
+        };
+        let sessions = importer.import(&content)?;
+        let summary = self.session_service.import_sessions(sessions)?;
 
-        if let Some(session) = status.current_session {
-            if let Some(break_start) = status.current_break_start {
-                println!("Status: On BREAK 🔴");
-                println!("Break started at: {}", break_start.format("%H:%M:%S"));
+        println!(
+            "✓ Imported {} sessions from {}",
+            summary.imported,
+            file.display()
+        );
+        if summary.skipped > 0 {
+            println!("  Skipped {} already recorded", summary.skipped);
+        }
+        Ok(())
+    }
 
-                let break_duration = status.current_time.signed_duration_since(break_start);
-                println!(
-                    "Current break duration: {}",
-                    DurationParser::format(break_duration)
-                );
+    pub fn handle_migrate_storage(&self, to: StorageBackend) -> Result<()> {
+        let project = repository::migrate_storage(&repository::find_data_dir(), to)?;
+        let sessions = project.sessions.len() + project.current_session.iter().count();
 
-                // Show accumulated break time
-                let accumulated_breaks = session.total_break_time();
-                if accumulated_breaks.num_minutes() > 0 {
+        println!("✓ Moved {} sessions to {} storage", sessions, to.as_str());
+        Ok(())
+    }
+
+    pub fn handle_projects(&self, prune: bool, add: bool) -> Result<()> {
+        let registry = Registry::open()?;
+        if add {
+            let Some(data_dir) = repository::enclosing_data_dir() else {
+                return Err(anyhow!("No project found. Run 'clock-me init' first."));
+            };
+            let path = data_dir.parent().unwrap_or(&data_dir).display().to_string();
+            if registry.register(&data_dir)? {
+                println!("✓ Added {}", path);
+            } else {
+                println!("✓ {} is already in the list", path);
+            }
+            return Ok(());
+        }
+        if prune {
+            let stale = registry.prune()?;
+            if stale.is_empty() {
+                println!("✓ No missing projects");
+            }
+            for project in stale {
+                println!("✓ Forgot {}", project.path.display());
+            }
+            return Ok(());
+        }
+
+        let projects = registry.load()?;
+        if projects.is_empty() {
+            println!(
+                "No projects yet. Run 'clock-me init' in a project directory, or \
+                 'clock-me projects --add' in an existing one."
+            );
+            return Ok(());
+        }
+
+        let mut rows = Vec::new();
+        let mut missing = false;
+        for project in &projects {
+            let path = project.path.display().to_string();
+            if project.is_stale() {
+                missing = true;
+                rows.push([
+                    "?".to_string(),
+                    String::new(),
+                    String::new(),
+                    "missing".to_string(),
+                    path,
+                ]);
+                continue;
+            }
+            match self.status_of(project) {
+                Ok(status) => rows.push([
+                    status.project_name.clone(),
+                    DurationParser::format(status.today_work_time),
+                    DurationParser::format(status.total_work_time),
+                    Self::activity(&status),
+                    path,
+                ]),
+                Err(_) => rows.push([
+                    "?".to_string(),
+                    String::new(),
+                    String::new(),
+                    "unreadable".to_string(),
+                    path,
+                ]),
+            }
+        }
+
+        let name_width = rows
+            .iter()
+            .map(|r| r[0].chars().count())
+            .max()
+            .unwrap_or(0)
+            .max(7);
+        let state_width = rows.iter().map(|r| r[3].chars().count()).max().unwrap_or(0);
+        println!(
+            "{:<name_width$}  {:>8}  {:>8}  {:<state_width$}  Path",
+            "Project", "Today", "Total", ""
+        );
+        for [name, today, total, state, path] in rows {
+            println!("{name:<name_width$}  {today:>8}  {total:>8}  {state:<state_width$}  {path}");
+        }
+        if missing {
+            println!("Run 'clock-me projects --prune' to forget missing projects.");
+        }
+        Ok(())
+    }
+
+    fn handle_status_all(&self) -> Result<()> {
+        let mut active = 0;
+        for project in Registry::open()?.load()? {
+            if project.is_stale() {
+                continue;
+            }
+            let Ok(status) = self.status_of(&project) else {
+                continue;
+            };
+            let Some(ref session) = status.current_session else {
+                continue;
+            };
+
+            active += 1;
+            match status.current_break_start {
+                Some(break_start) => println!(
+                    "🔴 {}: on break since {} ({})",
+                    status.project_name,
+                    break_start.format("%H:%M"),
+                    project.path.display()
+                ),
+                None => {
+                    let work_time = status.current_time.signed_duration_since(session.start)
+                        - session.total_break_time();
                     println!(
-                        "Previous breaks this session: {}",
-                        DurationParser::format(accumulated_breaks)
+                        "✓ {}: clocked in since {}, working for {} ({})",
+                        status.project_name,
+                        session.start.format("%H:%M"),
+                        DurationParser::format(work_time),
+                        project.path.display()
                     );
                 }
-            } else {
-                println!("Status: Clocked IN ✓");
-                println!("Started at: {}", session.start.format("%Y-%m-%d %H:%M:%S"));
+            }
+        }
+
+        if active == 0 {
+            println!("Not clocked in to any project.");
+        }
+        Ok(())
+    }
+
+    /// Only reads the other project: no lock, upgrade or backup in its
+    /// `.clockme`
+    fn status_of(&self, project: &RegisteredProject) -> Result<StatusInfo> {
+        let project = repository::read_project(&project.data_dir())?;
+        Ok(self.session_service.status_of(&project))
+    }
 
-                let elapsed = status.current_time.signed_duration_since(session.start);
-                let break_time = session.total_break_time();
-                let work_time = elapsed - break_time;
+    /// Short state for the projects list
+    fn activity(status: &StatusInfo) -> String {
+        match (&status.current_session, status.current_break_start) {
+            (Some(_), Some(_)) => "on break".to_string(),
+            (Some(_), None) => "clocked in".to_string(),
+            (None, _) => String::new(),
+        }
+    }
 
-                println!("Working for: {}", DurationParser::format(work_time));
+    pub fn handle_git_install_hooks(&self, auto_start: bool) -> Result<()> {
+        let repo_root = Self::repo_root()?;
+        let hooks_dir = git::hooks_dir(&repo_root)?;
 
-                if break_time.num_minutes() > 0 {
+        for change in git::install_hooks(&hooks_dir, auto_start)? {
+            match change {
+                HookChange::Installed(hook) => println!(
+                    "✓ Installed the {} hook in {}",
+                    hook.name(),
+                    hooks_dir.display()
+                ),
+                HookChange::Kept(hook) => {
                     println!(
-                        "Break time this session: {} ({} breaks)",
-                        DurationParser::format(break_time),
-                        session.breaks.len()
+                        "⚠ {} already has a {} hook, so it was left alone. Add this line to it:",
+                        hooks_dir.display(),
+                        hook.name()
                     );
+                    println!("  {}", hook.command(auto_start));
                 }
             }
-        } else {
-            println!("Status: Clocked OUT");
+        }
+        if auto_start {
+            println!("Committing while clocked out will start a session.");
+        }
+        Ok(())
+    }
+
+    pub fn handle_git_post_commit(&self, auto_start: bool) -> Result<()> {
+        let repo_root = Self::repo_root()?;
+        let (hash, subject) = git::head_commit(&repo_root)?;
+        let branch = git::current_branch(&repo_root);
 
-            if let Some(last_session) = status.last_session {
-                println!("\nLast session:");
+        let outcome = self
+            .session_service
+            .record_commit(hash, subject, branch, auto_start)?;
+        if outcome == CommitOutcome::Started {
+            println!("clock-me: started a session with this commit");
+        }
+        Ok(())
+    }
+
+    /// A detached HEAD, as during a rebase, is not a branch switch
+    pub fn handle_git_post_checkout(&self) -> Result<()> {
+        let repo_root = Self::repo_root()?;
+        if let Some(branch) = git::current_branch(&repo_root) {
+            self.session_service.record_checkout(branch)?;
+        }
+        Ok(())
+    }
+
+    pub fn handle_git_post_rewrite(&self, rewrites: &[(String, String)]) -> Result<()> {
+        self.session_service.rewrite_commits(rewrites)?;
+        Ok(())
+    }
+
+    /// The directory holding `.clockme`, which the hooks expect to be the
+    /// root of the git repository
+    fn repo_root() -> Result<PathBuf> {
+        let data_dir = repository::enclosing_data_dir()
+            .ok_or_else(|| anyhow!("No project found. Run 'clock-me init' first."))?;
+        let data_dir = data_dir
+            .canonicalize()
+            .with_context(|| format!("Failed to resolve {}", data_dir.display()))?;
+        Ok(data_dir.parent().unwrap_or(&data_dir).to_path_buf())
+    }
+
+    pub fn handle_doctor(&self, fix: bool) -> Result<()> {
+        if repository::enclosing_data_dir().is_none() {
+            return Err(anyhow!("No project found. Run 'clock-me init' first."));
+        }
+        if !fix {
+            let issues = self.session_service.check_integrity()?;
+            if issues.is_empty() {
+                println!("✓ No problems found");
+                return Ok(());
+            }
+
+            println!("⚠ Found {} problem(s):", issues.len());
+            Self::print_issues(&issues);
+            let fixable = issues.iter().filter(|i| i.fixable).count();
+            if fixable > 0 {
                 println!(
-                    "  Started: {}",
-                    last_session.start.format("%Y-%m-%d %H:%M:%S")
+                    "Run 'clock-me doctor --fix' to repair the {} marked (fixable).",
+                    fixable
                 );
-                if let Some(end) = last_session.end {
-                    println!("  Ended: {}", end.format("%Y-%m-%d %H:%M:%S"));
-                    if let Some(work_time) = last_session.work_time() {
-                        println!("  Work time: {}", DurationParser::format(work_time));
-                        let break_time = last_session.total_break_time();
-                        if break_time.num_minutes() > 0 {
-                            println!(
-                                "  Break time: {} ({} breaks)",
-                                DurationParser::format(break_time),
-                                last_session.breaks.len()
-                            );
-                        }
-                    }
-                }
             }
+            return Ok(());
         }
 
-        println!("\nToday's Summary:");
-        println!(
-            "  Work time: {}",
-            DurationParser::format(status.today_work_time)
-        );
-        if status.today_break_time.num_minutes() > 0 {
+        let summary = self
+            .session_service
+            .repair_integrity(&repository::find_data_dir())?;
+        if let Some(backup) = summary.backup {
+            println!("Backed up the project to {}", backup.display());
+        }
+        for repair in &summary.repairs {
+            println!("✓ {}", repair);
+        }
+
+        if summary.remaining.is_empty() {
+            if summary.repairs.is_empty() {
+                println!("✓ No problems found");
+            }
+        } else {
             println!(
-                "  Break time: {}",
-                DurationParser::format(status.today_break_time)
+                "⚠ {} problem(s) need to be resolved by hand:",
+                summary.remaining.len()
             );
+            Self::print_issues(&summary.remaining);
+            if summary.remaining.iter().any(Issue::is_unreadable) {
+                println!("Repair the file by hand or restore a copy from .clockme/backups.");
+            } else {
+                println!("Use 'clock-me edit', 'split', 'merge' or 'delete' to resolve them.");
+            }
         }
-        println!("  Sessions: {}", status.today_sessions);
-        if status.today_breaks > 0 {
-            println!("  Breaks: {}", status.today_breaks);
+        Ok(())
+    }
+
+    fn print_issues(issues: &[Issue]) {
+        for issue in issues {
+            let fixable = if issue.fixable { " (fixable)" } else { "" };
+            println!("  {}: {}{}", issue.record, issue.problem, fixable);
         }
+    }
 
-        println!("\n Total (all time):");
-        println!(
-            "  Work time: {}",
-            DurationParser::format(status.total_work_time)
-        );
-        if status.total_break_time.num_minutes() > 0 {
+    pub fn handle_undo(&self, steps: usize) -> Result<()> {
+        for entry in self.session_service.undo(steps)? {
             println!(
-                "  Break time: {}",
-                DurationParser::format(status.total_break_time)
+                "↶ Undid {} from {}",
+                entry.action,
+                entry.at.format("%Y-%m-%d %H:%M")
             );
         }
-        println!("  Sessions: {}", status.total_sessions);
-
-        println!("━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━");
+        println!("Use 'clock-me redo' to reapply.");
+        Ok(())
+    }
 
+    pub fn handle_redo(&self, steps: usize) -> Result<()> {
+        for entry in self.session_service.redo(steps)? {
+            println!(
+                "↷ Redid {} from {}",
+                entry.action,
+                entry.at.format("%Y-%m-%d %H:%M")
+            );
+        }
         Ok(())
     }
+
+    /// Reads an import file, or the interval files of Timewarrior's `data/`
+    /// directory
+    fn read_import_source(path: &PathBuf, format: ImportFormat) -> Result<String> {
+        if !path.is_dir() {
+            return fs::read_to_string(path)
+                .with_context(|| format!("Failed to read {}", path.display()));
+        }
+
+        match format {
+            ImportFormat::Timewarrior => TimewarriorImporter::read_data_dir(path),
+            ImportFormat::Timeclock => Err(anyhow!(
+                "{} is a directory; timeclock imports read a single file",
+                path.display()
+            )),
+        }
+    }
 }
@@ -0,0 +1,180 @@
+use crate::exporters::Exporter;
+use crate::models::session::Session;
+use chrono::{DateTime, Duration, Local};
+
+const SESSION_HEADER: [&str; 10] = [
+    "id",
+    "start",
+    "end",
+    "duration_minutes",
+    "break_minutes",
+    "work_minutes",
+    "breaks",
+    "origin",
+    "note",
+    "tags",
+];
+
+const BREAK_HEADER: [&str; 7] = [
+    "session_id",
+    "id",
+    "session_start",
+    "start",
+    "end",
+    "duration_minutes",
+    "note",
+];
+
+/// Exports sessions as RFC 4180 CSV, one row per session or one row per break
+pub struct CsvExporter {
+    pub breaks: bool,
+}
+
+impl CsvExporter {
+    pub fn sessions() -> Self {
+        Self { breaks: false }
+    }
+
+    pub fn breaks() -> Self {
+        Self { breaks: true }
+    }
+
+    /// Quotes a field if it contains a delimiter, quote or line break,
+    /// doubling any embedded quotes
+    pub fn escape(field: &str) -> String {
+        if field.contains([',', '"', '\n', '\r']) {
+            format!("\"{}\"", field.replace('"', "\"\""))
+        } else {
+            field.to_string()
+        }
+    }
+
+    fn write_row<S: AsRef<str>>(out: &mut String, fields: &[S]) {
+        let row: Vec<String> = fields.iter().map(|f| Self::escape(f.as_ref())).collect();
+        out.push_str(&row.join(","));
+        out.push_str("\r\n");
+    }
+
+    fn timestamp(time: DateTime<Local>) -> String {
+        time.format("%Y-%m-%d %H:%M:%S").to_string()
+    }
+
+    fn minutes(duration: Option<Duration>) -> String {
+        duration
+            .map(|d| d.num_minutes().to_string())
+            .unwrap_or_default()
+    }
+
+    fn session_row(session: &Session) -> Vec<String> {
+        vec![
+            session.id.to_string(),
+            Self::timestamp(session.start),
+            session.end.map(Self::timestamp).unwrap_or_default(),
+            Self::minutes(session.duration()),
+            Self::minutes(Some(session.total_break_time())),
+            Self::minutes(session.work_time()),
+            session.breaks.len().to_string(),
+            session.origin.as_str().to_string(),
+            session.note.clone().unwrap_or_default(),
+            session.tags.join(";"),
+        ]
+    }
+}
+
+impl Exporter for CsvExporter {
+    fn export(&self, _project_name: &str, sessions: &[Session]) -> String {
+        let mut out = String::new();
+
+        if self.breaks {
+            Self::write_row(&mut out, &BREAK_HEADER);
+            for session in sessions {
+                for break_period in &session.breaks {
+                    Self::write_row(
+                        &mut out,
+                        &[
+                            session.id.to_string(),
+                            break_period.id.to_string(),
+                            Self::timestamp(session.start),
+                            Self::timestamp(break_period.start),
+                            break_period.end.map(Self::timestamp).unwrap_or_default(),
+                            Self::minutes(break_period.duration()),
+                            break_period.note.clone().unwrap_or_default(),
+                        ],
+                    );
+                }
+            }
+        } else {
+            Self::write_row(&mut out, &SESSION_HEADER);
+            for session in sessions {
+                Self::write_row(&mut out, &Self::session_row(session));
+            }
+        }
+
+        out
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::r#break::Break;
+    use chrono::TimeZone;
+    use uuid::Uuid;
+
+    fn sample_session() -> Session {
+        let start = Local.with_ymd_and_hms(2025, 10, 13, 9, 0, 0).unwrap();
+        let mut session = Session::new(start);
+        session.id = Uuid::from_u128(1);
+
+        let mut break_period = Break::new(Local.with_ymd_and_hms(2025, 10, 13, 12, 0, 0).unwrap());
+        break_period.id = Uuid::from_u128(2);
+        break_period.finish(Local.with_ymd_and_hms(2025, 10, 13, 12, 30, 0).unwrap());
+        break_period.note = Some("lunch".to_string());
+        session.add_break(break_period);
+
+        session.finish(Local.with_ymd_and_hms(2025, 10, 13, 17, 0, 0).unwrap());
+        session.note = Some("API, \"v2\" rollout".to_string());
+        session.tags = vec!["backend".to_string(), "review".to_string()];
+        session
+    }
+
+    #[test]
+    fn test_escape() {
+        assert_eq!(CsvExporter::escape("plain"), "plain");
+        assert_eq!(CsvExporter::escape("a,b"), "\"a,b\"");
+        assert_eq!(CsvExporter::escape("say \"hi\""), "\"say \"\"hi\"\"\"");
+        assert_eq!(CsvExporter::escape("two\nlines"), "\"two\nlines\"");
+    }
+
+    #[test]
+    fn test_export_sessions() {
+        let csv = CsvExporter::sessions().export("test", &[sample_session()]);
+        let lines: Vec<&str> = csv.split("\r\n").collect();
+
+        assert_eq!(
+            lines[0],
+            "id,start,end,duration_minutes,break_minutes,work_minutes,breaks,origin,note,tags"
+        );
+        assert_eq!(
+            lines[1],
+            "00000000-0000-0000-0000-000000000001,2025-10-13 09:00:00,2025-10-13 17:00:00,480,30,450,1,clocked,\"API, \"\"v2\"\" rollout\",backend;review"
+        );
+        assert_eq!(lines[2], "");
+    }
+
+    #[test]
+    fn test_export_breaks() {
+        let csv = CsvExporter::breaks().export("test", &[sample_session()]);
+        let lines: Vec<&str> = csv.split("\r\n").collect();
+
+        assert_eq!(
+            lines[0],
+            "session_id,id,session_start,start,end,duration_minutes,note"
+        );
+        assert_eq!(
+            lines[1],
+            "00000000-0000-0000-0000-000000000001,00000000-0000-0000-0000-000000000002,\
+             2025-10-13 09:00:00,2025-10-13 12:00:00,2025-10-13 12:30:00,30,lunch"
+        );
+    }
+}
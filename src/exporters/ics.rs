@@ -0,0 +1,279 @@
+use crate::exporters::Exporter;
+use crate::models::r#break::Break;
+use crate::models::session::Session;
+use crate::parsers::DurationParser;
+use chrono::{DateTime, Local, Utc};
+use uuid::Uuid;
+
+/// Exports finished sessions as an iCalendar (RFC 5545) document.
+///
+/// Times are written in UTC so every calendar places them at the same instant
+/// regardless of the offset they were recorded with.
+pub struct IcsExporter {
+    /// Emit each break as its own event instead of listing it in the description
+    pub break_events: bool,
+    /// When the document is created, written as every event's DTSTAMP
+    pub exported_at: DateTime<Local>,
+}
+
+impl IcsExporter {
+    pub fn new(break_events: bool, exported_at: DateTime<Local>) -> Self {
+        Self {
+            break_events,
+            exported_at,
+        }
+    }
+
+    /// Escapes TEXT values: backslash, semicolon, comma and line breaks
+    pub fn escape(text: &str) -> String {
+        text.replace('\\', "\\\\")
+            .replace(';', "\\;")
+            .replace(',', "\\,")
+            .replace("\r\n", "\\n")
+            .replace('\n', "\\n")
+    }
+
+    /// Folds a content line so no physical line exceeds 75 octets
+    fn fold(line: &str) -> String {
+        let mut folded = String::new();
+        let mut width = 0;
+
+        for c in line.chars() {
+            let len = c.len_utf8();
+            if width + len > 75 {
+                folded.push_str("\r\n ");
+                width = 1;
+            }
+            folded.push(c);
+            width += len;
+        }
+
+        folded
+    }
+
+    fn push_line(out: &mut String, line: &str) {
+        out.push_str(&Self::fold(line));
+        out.push_str("\r\n");
+    }
+
+    fn timestamp(time: DateTime<Local>) -> String {
+        time.with_timezone(&Utc)
+            .format("%Y%m%dT%H%M%SZ")
+            .to_string()
+    }
+
+    /// The record's own ID, so re-importing an export updates events
+    /// (even after their times were edited) instead of duplicating them
+    fn uid(id: &Uuid, kind: &str) -> String {
+        format!("{}-{}@clock-me", kind, id)
+    }
+
+    fn describe_break(break_period: &Break) -> String {
+        let end = break_period
+            .end
+            .map(|e| e.format("%H:%M").to_string())
+            .unwrap_or_default();
+        match break_period.note {
+            Some(ref note) => format!(
+                "Break {}–{} ({})",
+                break_period.start.format("%H:%M"),
+                end,
+                note
+            ),
+            None => format!("Break {}–{}", break_period.start.format("%H:%M"), end),
+        }
+    }
+
+    /// One VEVENT, stamped with the export time
+    fn event(
+        &self,
+        uid: &str,
+        start: DateTime<Local>,
+        end: DateTime<Local>,
+        summary: &str,
+        description: &str,
+        categories: &[String],
+    ) -> String {
+        let mut event = String::new();
+        Self::push_line(&mut event, "BEGIN:VEVENT");
+        Self::push_line(&mut event, &format!("UID:{}", uid));
+        Self::push_line(
+            &mut event,
+            &format!("DTSTAMP:{}", Self::timestamp(self.exported_at)),
+        );
+        Self::push_line(&mut event, &format!("DTSTART:{}", Self::timestamp(start)));
+        Self::push_line(&mut event, &format!("DTEND:{}", Self::timestamp(end)));
+        Self::push_line(&mut event, &format!("SUMMARY:{}", Self::escape(summary)));
+        if !description.is_empty() {
+            Self::push_line(
+                &mut event,
+                &format!("DESCRIPTION:{}", Self::escape(description)),
+            );
+        }
+        if !categories.is_empty() {
+            let escaped: Vec<String> = categories.iter().map(|c| Self::escape(c)).collect();
+            Self::push_line(&mut event, &format!("CATEGORIES:{}", escaped.join(",")));
+        }
+        Self::push_line(&mut event, "END:VEVENT");
+        event
+    }
+}
+
+impl Exporter for IcsExporter {
+    fn export(&self, project_name: &str, sessions: &[Session]) -> String {
+        let mut out = String::new();
+        Self::push_line(&mut out, "BEGIN:VCALENDAR");
+        Self::push_line(&mut out, "VERSION:2.0");
+        Self::push_line(&mut out, "PRODID:-//clock-me//clock-me//EN");
+        Self::push_line(&mut out, "CALSCALE:GREGORIAN");
+        Self::push_line(
+            &mut out,
+            &format!("X-WR-CALNAME:{}", Self::escape(project_name)),
+        );
+
+        for session in sessions {
+            let Some(end) = session.end else {
+                continue;
+            };
+
+            let mut description = Vec::new();
+            if let Some(ref note) = session.note {
+                description.push(note.clone());
+            }
+            if let Some(work_time) = session.work_time() {
+                description.push(format!("Work time: {}", DurationParser::format(work_time)));
+            }
+            if !self.break_events {
+                description.extend(session.breaks.iter().map(Self::describe_break));
+            }
+
+            out.push_str(&self.event(
+                &Self::uid(&session.id, "session"),
+                session.start,
+                end,
+                project_name,
+                &description.join("\n"),
+                &session.tags,
+            ));
+
+            if self.break_events {
+                for break_period in &session.breaks {
+                    let Some(break_end) = break_period.end else {
+                        continue;
+                    };
+                    out.push_str(&self.event(
+                        &Self::uid(&break_period.id, "break"),
+                        break_period.start,
+                        break_end,
+                        &format!("Break ({})", project_name),
+                        break_period.note.as_deref().unwrap_or_default(),
+                        &[],
+                    ));
+                }
+            }
+        }
+
+        Self::push_line(&mut out, "END:VCALENDAR");
+        out
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::{FixedOffset, TimeZone};
+
+    fn exported_at() -> DateTime<Local> {
+        Local.with_ymd_and_hms(2025, 11, 1, 8, 0, 0).unwrap()
+    }
+
+    fn sample_session() -> Session {
+        let start = Local.with_ymd_and_hms(2025, 10, 13, 9, 0, 0).unwrap();
+        let mut session = Session::new(start);
+
+        let mut break_period = Break::new(Local.with_ymd_and_hms(2025, 10, 13, 12, 0, 0).unwrap());
+        break_period.finish(Local.with_ymd_and_hms(2025, 10, 13, 12, 30, 0).unwrap());
+        break_period.note = Some("lunch".to_string());
+        session.add_break(break_period);
+
+        session.finish(Local.with_ymd_and_hms(2025, 10, 13, 17, 0, 0).unwrap());
+        session.note = Some("API, v2; rollout".to_string());
+        session.tags = vec!["backend".to_string()];
+        session
+    }
+
+    #[test]
+    fn test_escape() {
+        assert_eq!(IcsExporter::escape("a, b; c"), "a\\, b\\; c");
+        assert_eq!(IcsExporter::escape("one\ntwo"), "one\\ntwo");
+        assert_eq!(IcsExporter::escape("back\\slash"), "back\\\\slash");
+    }
+
+    #[test]
+    fn test_fold_long_lines() {
+        let line = format!("DESCRIPTION:{}", "x".repeat(100));
+        let folded = IcsExporter::fold(&line);
+        let parts: Vec<&str> = folded.split("\r\n").collect();
+
+        assert_eq!(parts.len(), 2);
+        assert_eq!(parts[0].len(), 75);
+        assert!(parts[1].starts_with(' '));
+        assert_eq!(parts.concat().replace(' ', ""), line);
+    }
+
+    #[test]
+    fn test_times_are_utc() {
+        let offset = FixedOffset::east_opt(2 * 3600).unwrap();
+        let time = offset
+            .with_ymd_and_hms(2025, 10, 13, 9, 0, 0)
+            .unwrap()
+            .with_timezone(&Local);
+
+        assert_eq!(IcsExporter::timestamp(time), "20251013T070000Z");
+    }
+
+    #[test]
+    fn test_export_session_with_breaks_in_description() {
+        let session = sample_session();
+        let ics =
+            IcsExporter::new(false, exported_at()).export("my-app", std::slice::from_ref(&session));
+        let unfolded = ics.replace("\r\n ", "");
+
+        assert!(ics.starts_with("BEGIN:VCALENDAR\r\n"));
+        assert!(ics.ends_with("END:VCALENDAR\r\n"));
+        assert_eq!(ics.matches("BEGIN:VEVENT").count(), 1);
+        assert!(ics.contains("SUMMARY:my-app\r\n"));
+        assert!(ics.contains(&format!(
+            "DTSTART:{}\r\n",
+            IcsExporter::timestamp(session.start)
+        )));
+        assert!(unfolded.contains(
+            "DESCRIPTION:API\\, v2\\; rollout\\nWork time: 7h 30m\\nBreak 12:00–12:30 (lunch)"
+        ));
+        assert!(ics.contains("CATEGORIES:backend\r\n"));
+        assert!(ics.contains(&format!(
+            "DTSTAMP:{}\r\n",
+            IcsExporter::timestamp(exported_at())
+        )));
+    }
+
+    #[test]
+    fn test_export_breaks_as_events_with_stable_uids() {
+        let sessions = [sample_session()];
+        let first = IcsExporter::new(true, exported_at()).export("my-app", &sessions);
+        let second = IcsExporter::new(true, exported_at()).export("my-app", &sessions);
+
+        assert_eq!(first.matches("BEGIN:VEVENT").count(), 2);
+        assert!(first.contains("SUMMARY:Break (my-app)\r\n"));
+        assert!(!first.contains("Break 12:00"));
+
+        let uids = |ics: &str| -> Vec<String> {
+            ics.lines()
+                .filter(|l| l.starts_with("UID:"))
+                .map(String::from)
+                .collect()
+        };
+        assert_eq!(uids(&first), uids(&second));
+        assert_ne!(uids(&first)[0], uids(&first)[1]);
+    }
+}
@@ -0,0 +1,34 @@
+pub mod csv;
+pub mod ics;
+pub mod timeclock;
+
+use crate::models::session::Session;
+use anyhow::{anyhow, Result};
+
+/// Converts finished sessions into a text document
+pub trait Exporter {
+    fn export(&self, project_name: &str, sessions: &[Session]) -> String;
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ExportFormat {
+    Csv,
+    Ics,
+    Timeclock,
+}
+
+impl std::str::FromStr for ExportFormat {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self> {
+        match s.trim().to_lowercase().as_str() {
+            "csv" => Ok(ExportFormat::Csv),
+            "ics" | "ical" => Ok(ExportFormat::Ics),
+            "timeclock" => Ok(ExportFormat::Timeclock),
+            _ => Err(anyhow!(
+                "Unsupported export format '{}'. Use 'csv', 'ics' or 'timeclock'",
+                s
+            )),
+        }
+    }
+}
@@ -0,0 +1,128 @@
+use crate::exporters::Exporter;
+use crate::models::session::Session;
+use chrono::{DateTime, Local};
+
+/// Exports sessions in hledger/ledger timeclock format.
+///
+/// Timeclock has no notion of breaks, so each session is written as one
+/// clock-in/clock-out pair per stretch of work between breaks. Every stretch
+/// after the first carries the [`RESUMED_TAG`] comment, which hledger ignores
+/// and the importer uses to join the stretches again. The project name is
+/// used as the account and the session note as the description; tags and
+/// break notes are not exported.
+///
+/// `;` starts a comment in timeclock files, so it is shown as `,` in the
+/// description and the exact note follows [`NOTE_TAG`] at the end of the
+/// comment.
+pub struct TimeclockExporter;
+
+/// Comment tag marking a clock-in that resumes the previous session after a
+/// break
+pub const RESUMED_TAG: &str = "clock-me:resumed";
+
+/// Comment tag followed by the note, up to the end of the line, for notes
+/// that contain `;`
+pub const NOTE_TAG: &str = "clock-me-note:";
+
+impl TimeclockExporter {
+    fn timestamp(time: DateTime<Local>) -> String {
+        time.format("%Y/%m/%d %H:%M:%S").to_string()
+    }
+
+    /// Splits a finished session into the periods actually worked
+    pub fn work_periods(session: &Session) -> Vec<(DateTime<Local>, DateTime<Local>)> {
+        let Some(end) = session.end else {
+            return Vec::new();
+        };
+
+        let mut breaks: Vec<(DateTime<Local>, DateTime<Local>)> = session
+            .breaks
+            .iter()
+            .filter_map(|b| b.end.map(|e| (b.start, e)))
+            .collect();
+        breaks.sort();
+
+        let mut periods = Vec::new();
+        let mut cursor = session.start;
+        for (break_start, break_end) in breaks {
+            if break_start > cursor {
+                periods.push((cursor, break_start.min(end)));
+            }
+            cursor = cursor.max(break_end);
+        }
+        if end > cursor {
+            periods.push((cursor, end));
+        }
+
+        periods
+    }
+}
+
+impl Exporter for TimeclockExporter {
+    fn export(&self, project_name: &str, sessions: &[Session]) -> String {
+        let mut out = String::new();
+
+        for session in sessions {
+            for (index, (start, end)) in Self::work_periods(session).into_iter().enumerate() {
+                let mut clock_in = format!("i {} {}", Self::timestamp(start), project_name);
+                let mut comment = Vec::new();
+                if index > 0 {
+                    comment.push(RESUMED_TAG.to_string());
+                }
+                if let Some(ref note) = session.note {
+                    // Two spaces separate the account from the description
+                    clock_in.push_str("  ");
+                    clock_in.push_str(&note.replace(';', ","));
+                    if note.contains(';') {
+                        comment.push(format!("{}{}", NOTE_TAG, note));
+                    }
+                }
+                if !comment.is_empty() {
+                    clock_in.push_str("  ; ");
+                    clock_in.push_str(&comment.join(", "));
+                }
+                out.push_str(&clock_in);
+                out.push('\n');
+                out.push_str(&format!("o {}\n", Self::timestamp(end)));
+            }
+        }
+
+        out
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::r#break::Break;
+    use chrono::TimeZone;
+
+    #[test]
+    fn test_export_splits_breaks() {
+        let at = |h, m| Local.with_ymd_and_hms(2025, 10, 13, h, m, 0).unwrap();
+        let mut session = Session::new(at(9, 0));
+        let mut break_period = Break::new(at(12, 0));
+        break_period.finish(at(12, 30));
+        session.add_break(break_period);
+        session.finish(at(17, 0));
+        session.note = Some("API work".to_string());
+
+        let output = TimeclockExporter.export("my-app", &[session]);
+
+        assert_eq!(
+            output,
+            "i 2025/10/13 09:00:00 my-app  API work\n\
+             o 2025/10/13 12:00:00\n\
+             i 2025/10/13 12:30:00 my-app  API work  ; clock-me:resumed\n\
+             o 2025/10/13 17:00:00\n"
+        );
+    }
+
+    #[test]
+    fn test_export_skips_active_session() {
+        let start = Local.with_ymd_and_hms(2025, 10, 13, 9, 0, 0).unwrap();
+        let output = TimeclockExporter.export("my-app", &[Session::new(start)]);
+
+        assert!(output.is_empty());
+    }
+}
@@ -0,0 +1,225 @@
+use anyhow::{anyhow, Context, Result};
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::process::Command;
+
+/// Marks the hook scripts written by clock-me, so it can tell its own hooks
+/// from the user's
+const MARKER: &str = "# Installed by clock-me";
+
+/// A git hook clock-me can install
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Hook {
+    /// Records each commit on the active session
+    PostCommit,
+    /// Records branch switches on the active session
+    PostCheckout,
+    /// Replaces commits rewritten by `commit --amend` and `rebase`
+    PostRewrite,
+}
+
+impl Hook {
+    pub const ALL: [Hook; 3] = [Hook::PostCommit, Hook::PostCheckout, Hook::PostRewrite];
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            Hook::PostCommit => "post-commit",
+            Hook::PostCheckout => "post-checkout",
+            Hook::PostRewrite => "post-rewrite",
+        }
+    }
+
+    /// The clock-me command the hook runs
+    pub fn command(&self, auto_start: bool) -> String {
+        match self {
+            Hook::PostCommit if auto_start => "clock-me git post-commit --auto-start".to_string(),
+            Hook::PostCommit => "clock-me git post-commit".to_string(),
+            Hook::PostCheckout => "clock-me git post-checkout \"$@\"".to_string(),
+            Hook::PostRewrite => "clock-me git post-rewrite \"$@\"".to_string(),
+        }
+    }
+
+    /// Never fails: a missing clock-me or a busy project must not get in the
+    /// way of git
+    fn script(&self, auto_start: bool) -> String {
+        format!(
+            "#!/bin/sh\n{} (clock-me git install-hooks)\ncommand -v clock-me >/dev/null 2>&1 || exit 0\n{} || true\n",
+            MARKER,
+            self.command(auto_start)
+        )
+    }
+}
+
+/// What `install_hooks` did with one hook
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum HookChange {
+    Installed(Hook),
+    /// The user already has a hook of this name; it was left alone
+    Kept(Hook),
+}
+
+/// Writes the post-commit, post-checkout and post-rewrite hooks into
+/// `hooks_dir`. Hooks written by someone else are never replaced.
+pub fn install_hooks(hooks_dir: &Path, auto_start: bool) -> Result<Vec<HookChange>> {
+    fs::create_dir_all(hooks_dir)
+        .with_context(|| format!("Failed to create {}", hooks_dir.display()))?;
+
+    let mut changes = Vec::new();
+    for hook in Hook::ALL {
+        let path = hooks_dir.join(hook.name());
+        let existing = fs::read_to_string(&path).ok();
+        if existing.is_some_and(|s| !s.contains(MARKER)) {
+            changes.push(HookChange::Kept(hook));
+            continue;
+        }
+
+        fs::write(&path, hook.script(auto_start))
+            .with_context(|| format!("Failed to write {}", path.display()))?;
+        make_executable(&path)?;
+        changes.push(HookChange::Installed(hook));
+    }
+    Ok(changes)
+}
+
+#[cfg(unix)]
+fn make_executable(path: &Path) -> Result<()> {
+    use std::os::unix::fs::PermissionsExt;
+    fs::set_permissions(path, fs::Permissions::from_mode(0o755))
+        .with_context(|| format!("Failed to make {} executable", path.display()))
+}
+
+#[cfg(not(unix))]
+fn make_executable(_path: &Path) -> Result<()> {
+    Ok(())
+}
+
+/// Runs git in `dir` and returns its trimmed output
+fn git(dir: &Path, args: &[&str]) -> Result<String> {
+    let output = Command::new("git")
+        .args(args)
+        .current_dir(dir)
+        .output()
+        .context("Failed to run git. Is it installed?")?;
+    if !output.status.success() {
+        return Err(anyhow!(
+            "git {} failed: {}",
+            args.join(" "),
+            String::from_utf8_lossy(&output.stderr).trim()
+        ));
+    }
+    Ok(String::from_utf8_lossy(&output.stdout).trim().to_string())
+}
+
+/// The hooks directory of the repository at `repo_root`, honoring
+/// `core.hooksPath` and worktrees
+pub fn hooks_dir(repo_root: &Path) -> Result<PathBuf> {
+    let path = git(repo_root, &["rev-parse", "--git-path", "hooks"]).map_err(|_| {
+        anyhow!(
+            "{} is not a git repository. Run this where .clockme sits next to .git",
+            repo_root.display()
+        )
+    })?;
+    Ok(repo_root.join(path))
+}
+
+/// Hash and subject of HEAD
+pub fn head_commit(dir: &Path) -> Result<(String, String)> {
+    let output = git(dir, &["log", "-1", "--format=%H%n%s"])?;
+    let (hash, subject) = output.split_once('\n').unwrap_or((&output, ""));
+    Ok((hash.to_string(), subject.to_string()))
+}
+
+/// The `(old, new)` hash pairs git writes to the post-rewrite hook's stdin,
+/// one `<old> <new> [<extra>]` line per rewritten commit
+pub fn parse_rewrites(input: &str) -> Vec<(String, String)> {
+    input
+        .lines()
+        .filter_map(|line| {
+            let mut fields = line.split_whitespace();
+            Some((fields.next()?.to_string(), fields.next()?.to_string()))
+        })
+        .collect()
+}
+
+/// The checked out branch, or `None` on a detached HEAD
+pub fn current_branch(dir: &Path) -> Option<String> {
+    git(dir, &["symbolic-ref", "--short", "-q", "HEAD"])
+        .ok()
+        .filter(|branch| !branch.is_empty())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    #[test]
+    fn test_install_hooks() {
+        let temp_dir = TempDir::new().unwrap();
+        let hooks = temp_dir.path().join("hooks");
+
+        let changes = install_hooks(&hooks, true).unwrap();
+        assert_eq!(
+            changes,
+            vec![
+                HookChange::Installed(Hook::PostCommit),
+                HookChange::Installed(Hook::PostCheckout),
+                HookChange::Installed(Hook::PostRewrite)
+            ]
+        );
+        let post_commit = fs::read_to_string(hooks.join("post-commit")).unwrap();
+        assert!(post_commit.starts_with("#!/bin/sh\n"));
+        assert!(post_commit.contains("clock-me git post-commit --auto-start || true"));
+
+        #[cfg(unix)]
+        {
+            use std::os::unix::fs::PermissionsExt;
+            let mode = fs::metadata(hooks.join("post-commit"))
+                .unwrap()
+                .permissions()
+                .mode();
+            assert_eq!(mode & 0o111, 0o111);
+        }
+
+        // Turning auto-start off rewrites our own hooks
+        install_hooks(&hooks, false).unwrap();
+        let post_commit = fs::read_to_string(hooks.join("post-commit")).unwrap();
+        assert!(!post_commit.contains("--auto-start"));
+        let post_checkout = fs::read_to_string(hooks.join("post-checkout")).unwrap();
+        assert!(post_checkout.contains("clock-me git post-checkout \"$@\" || true"));
+    }
+
+    #[test]
+    fn test_parse_rewrites() {
+        let input = "aaa111 bbb222\nccc333 ddd444 extra\n\nbroken\n";
+        assert_eq!(
+            parse_rewrites(input),
+            vec![
+                ("aaa111".to_string(), "bbb222".to_string()),
+                ("ccc333".to_string(), "ddd444".to_string())
+            ]
+        );
+    }
+
+    #[test]
+    fn test_install_hooks_keeps_foreign_hooks() {
+        let temp_dir = TempDir::new().unwrap();
+        let hooks = temp_dir.path().join("hooks");
+        fs::create_dir_all(&hooks).unwrap();
+        fs::write(hooks.join("post-commit"), "#!/bin/sh\nmake lint\n").unwrap();
+
+        let changes = install_hooks(&hooks, false).unwrap();
+        assert_eq!(
+            changes,
+            vec![
+                HookChange::Kept(Hook::PostCommit),
+                HookChange::Installed(Hook::PostCheckout),
+                HookChange::Installed(Hook::PostRewrite)
+            ]
+        );
+        assert_eq!(
+            fs::read_to_string(hooks.join("post-commit")).unwrap(),
+            "#!/bin/sh\nmake lint\n"
+        );
+    }
+}
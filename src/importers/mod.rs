@@ -0,0 +1,31 @@
+pub mod timeclock;
+pub mod timewarrior;
+
+use crate::models::session::Session;
+use anyhow::{anyhow, Result};
+
+/// Converts another time tracker's data into finished sessions
+pub trait Importer {
+    fn import(&self, content: &str) -> Result<Vec<Session>>;
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ImportFormat {
+    Timeclock,
+    Timewarrior,
+}
+
+impl std::str::FromStr for ImportFormat {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self> {
+        match s.trim().to_lowercase().as_str() {
+            "timeclock" => Ok(ImportFormat::Timeclock),
+            "timewarrior" | "timew" => Ok(ImportFormat::Timewarrior),
+            _ => Err(anyhow!(
+                "Unsupported import format '{}'. Use 'timeclock' or 'timewarrior'",
+                s
+            )),
+        }
+    }
+}
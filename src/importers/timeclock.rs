@@ -0,0 +1,316 @@
+use crate::exporters::timeclock::{NOTE_TAG, RESUMED_TAG};
+use crate::importers::Importer;
+use crate::models::r#break::Break;
+use crate::models::session::Session;
+use anyhow::{anyhow, Result};
+use chrono::{DateTime, Local, NaiveDateTime};
+
+/// Reads hledger/ledger timeclock files.
+///
+/// Each `i DATE TIME [ACCOUNT[  DESCRIPTION]][  ; COMMENT]` line must be
+/// followed by an `o DATE TIME` (or `O`) line; the pair becomes one imported
+/// session with the description as its note. A clock-in tagged
+/// [`RESUMED_TAG`], as written by the exporter after a break, continues the
+/// session imported just before it instead, with the gap as a break. A note
+/// after [`NOTE_TAG`] in the comment replaces the description. Blank lines
+/// and `;`, `#` or `*` comments are ignored.
+pub struct TimeclockImporter {
+    /// Only import entries for this account
+    pub account: Option<String>,
+}
+
+struct ClockIn {
+    line: usize,
+    time: DateTime<Local>,
+    account: String,
+    description: Option<String>,
+    resumed: bool,
+}
+
+impl TimeclockImporter {
+    pub fn new(account: Option<String>) -> Self {
+        Self { account }
+    }
+
+    fn parse_timestamp(date: &str, time: &str, line: usize) -> Result<DateTime<Local>> {
+        let text = format!("{} {}", date.replace('-', "/"), time);
+        let naive = NaiveDateTime::parse_from_str(&text, "%Y/%m/%d %H:%M:%S")
+            .or_else(|_| NaiveDateTime::parse_from_str(&text, "%Y/%m/%d %H:%M"))
+            .map_err(|_| anyhow!("Line {}: invalid date/time '{}'", line, text))?;
+
+        naive.and_local_timezone(Local).earliest().ok_or_else(|| {
+            anyhow!(
+                "Line {}: {} does not exist in the local timezone",
+                line,
+                text
+            )
+        })
+    }
+
+    /// Splits off the first whitespace-separated field
+    fn next_field(text: &str) -> (&str, &str) {
+        let text = text.trim_start();
+        let end = text.find(char::is_whitespace).unwrap_or(text.len());
+        text.split_at(end)
+    }
+
+    /// Splits "account  description" on the first run of two spaces or a tab
+    fn split_account(rest: &str) -> (String, Option<String>) {
+        let rest = rest.trim();
+        let split = rest.find("  ").into_iter().chain(rest.find('\t')).min();
+
+        match split {
+            Some(index) => {
+                let description = rest[index..].trim();
+                (
+                    rest[..index].trim().to_string(),
+                    (!description.is_empty()).then(|| description.to_string()),
+                )
+            }
+            None => (rest.to_string(), None),
+        }
+    }
+}
+
+impl Importer for TimeclockImporter {
+    fn import(&self, content: &str) -> Result<Vec<Session>> {
+        let mut sessions: Vec<Session> = Vec::new();
+        let mut open: Option<ClockIn> = None;
+        // Whether the last pair was imported, so a resumed clock-in can join it
+        let mut joinable = false;
+
+        for (index, raw) in content.lines().enumerate() {
+            let line = index + 1;
+            let trimmed = raw.trim();
+            if trimmed.is_empty() || trimmed.starts_with([';', '#', '*']) {
+                continue;
+            }
+
+            let (code, rest) = Self::next_field(trimmed);
+            let (date, rest) = Self::next_field(rest);
+            let (time, rest) = Self::next_field(rest);
+            let (rest, comment) = rest.split_once(';').unwrap_or((rest, ""));
+
+            match code {
+                "i" => {
+                    if let Some(previous) = open {
+                        return Err(anyhow!(
+                            "Line {}: clock-in while the clock-in from line {} is still open",
+                            line,
+                            previous.line
+                        ));
+                    }
+                    let (account, mut description) = Self::split_account(rest);
+                    // The note tag comes last and runs to the end of the line
+                    let (tags, note) = comment.split_once(NOTE_TAG).unwrap_or((comment, ""));
+                    if !note.trim().is_empty() {
+                        description = Some(note.trim().to_string());
+                    }
+                    open = Some(ClockIn {
+                        line,
+                        time: Self::parse_timestamp(date, time, line)?,
+                        account,
+                        description,
+                        resumed: tags.contains(RESUMED_TAG),
+                    });
+                }
+                "o" | "O" => {
+                    let clock_in = open
+                        .take()
+                        .ok_or_else(|| anyhow!("Line {}: clock-out without clock-in", line))?;
+                    let end = Self::parse_timestamp(date, time, line)?;
+                    if end <= clock_in.time {
+                        return Err(anyhow!(
+                            "Line {}: clock-out is not after the clock-in on line {}",
+                            line,
+                            clock_in.line
+                        ));
+                    }
+
+                    if self
+                        .account
+                        .as_ref()
+                        .is_some_and(|wanted| *wanted != clock_in.account)
+                    {
+                        joinable = false;
+                        continue;
+                    }
+
+                    let previous = sessions.last_mut().filter(|previous| {
+                        joinable && clock_in.resumed && previous.end <= Some(clock_in.time)
+                    });
+                    if let Some(previous) = previous {
+                        let mut break_period = Break::new(previous.end.unwrap_or(clock_in.time));
+                        break_period.finish(clock_in.time);
+                        previous.add_break(break_period);
+                        previous.finish(end);
+                    } else {
+                        let mut session = Session::imported(clock_in.time, end);
+                        session.note = clock_in.description;
+                        sessions.push(session);
+                    }
+                    joinable = true;
+                }
+                _ => {
+                    return Err(anyhow!(
+                        "Line {}: expected an 'i' or 'o' entry, found '{}'",
+                        line,
+                        trimmed
+                    ))
+                }
+            }
+        }
+
+        if let Some(clock_in) = open {
+            return Err(anyhow!(
+                "Line {}: clock-in has no matching clock-out",
+                clock_in.line
+            ));
+        }
+
+        Ok(sessions)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::exporters::timeclock::TimeclockExporter;
+    use crate::exporters::Exporter;
+    use crate::models::session::SessionOrigin;
+    use chrono::TimeZone;
+
+    const SAMPLE: &str = "\
+; hledger timeclock
+i 2025/10/13 09:00:00 work:my-app  API design
+o 2025/10/13 12:00:00
+
+i 2025/10/13 13:00 work:other
+O 2025/10/13 14:30
+";
+
+    #[test]
+    fn test_import_pairs() {
+        let sessions = TimeclockImporter::new(None).import(SAMPLE).unwrap();
+
+        assert_eq!(sessions.len(), 2);
+        assert_eq!(
+            sessions[0].start,
+            Local.with_ymd_and_hms(2025, 10, 13, 9, 0, 0).unwrap()
+        );
+        assert_eq!(sessions[0].work_time().unwrap().num_minutes(), 180);
+        assert_eq!(sessions[0].note.as_deref(), Some("API design"));
+        assert_eq!(sessions[0].origin, SessionOrigin::Imported);
+        assert_eq!(sessions[1].work_time().unwrap().num_minutes(), 90);
+        assert!(sessions[1].note.is_none());
+    }
+
+    #[test]
+    fn test_import_account_filter() {
+        let importer = TimeclockImporter::new(Some("work:other".to_string()));
+        let sessions = importer.import(SAMPLE).unwrap();
+
+        assert_eq!(sessions.len(), 1);
+        assert_eq!(
+            sessions[0].start,
+            Local.with_ymd_and_hms(2025, 10, 13, 13, 0, 0).unwrap()
+        );
+    }
+
+    #[test]
+    fn test_import_errors() {
+        let importer = TimeclockImporter::new(None);
+
+        assert!(importer.import("o 2025/10/13 12:00:00\n").is_err());
+        assert!(importer.import("i 2025/10/13 09:00:00 a\n").is_err());
+        assert!(importer
+            .import("i 2025/10/13 09:00:00 a\ni 2025/10/13 10:00:00 a\n")
+            .is_err());
+        assert!(importer
+            .import("i 2025/10/13 09:00:00 a\no 2025/10/13 08:00:00\n")
+            .is_err());
+
+        let result = importer.import("i 2025/13/45 09:00:00 a\n");
+        assert!(result.unwrap_err().to_string().contains("Line 1"));
+    }
+
+    #[test]
+    fn test_import_repeated_spaces() {
+        let content = "i  2025/10/13   09:00:00   work:my-app  API design\no\t2025/10/13 12:00\n";
+        let sessions = TimeclockImporter::new(Some("work:my-app".to_string()))
+            .import(content)
+            .unwrap();
+
+        assert_eq!(sessions.len(), 1);
+        assert_eq!(sessions[0].work_time().unwrap().num_minutes(), 180);
+        assert_eq!(sessions[0].note.as_deref(), Some("API design"));
+    }
+
+    #[test]
+    fn test_round_trip_keeps_breaks() {
+        let at = |h, m| Local.with_ymd_and_hms(2025, 10, 13, h, m, 0).unwrap();
+        let mut session = Session::imported(at(9, 0), at(17, 0));
+        session.note = Some("API work".to_string());
+        for (start, end) in [(at(12, 0), at(12, 30)), (at(15, 0), at(15, 15))] {
+            let mut break_period = Break::new(start);
+            break_period.finish(end);
+            session.add_break(break_period);
+        }
+        let next = Session::imported(at(18, 0), at(19, 0));
+
+        let exported = TimeclockExporter.export("my-app", &[session, next]);
+        let reimported = TimeclockImporter::new(None).import(&exported).unwrap();
+
+        assert_eq!(reimported.len(), 2);
+        assert_eq!(
+            (reimported[0].start, reimported[0].end),
+            (at(9, 0), Some(at(17, 0)))
+        );
+        assert_eq!(reimported[0].note.as_deref(), Some("API work"));
+        assert_eq!(reimported[0].total_break_time().num_minutes(), 45);
+        assert_eq!(reimported[0].breaks.len(), 2);
+        assert!(reimported[1].breaks.is_empty());
+
+        // Without the previous stretch, a resumed clock-in starts a session
+        let filtered = TimeclockImporter::new(Some("other".to_string()))
+            .import(&exported.replace("12:30:00 my-app", "12:30:00 other"))
+            .unwrap();
+        assert_eq!(filtered.len(), 1);
+        assert_eq!(filtered[0].start, at(12, 30));
+    }
+
+    #[test]
+    fn test_round_trip_keeps_semicolons_in_notes() {
+        let at = |h| Local.with_ymd_and_hms(2025, 10, 13, h, 0, 0).unwrap();
+        let mut session = Session::imported(at(9), at(17));
+        session.append_note("fix parser");
+        session.append_note("add tests");
+        let mut lunch = Break::new(at(12));
+        lunch.finish(at(13));
+        session.add_break(lunch);
+
+        let exported = TimeclockExporter.export("my-app", std::slice::from_ref(&session));
+        // hledger sees a clean description and the tags in the comment
+        assert!(exported.starts_with("i 2025/10/13 09:00:00 my-app  fix parser, add tests  ; clock-me-note:fix parser; add tests\n"));
+        assert!(exported.contains("  ; clock-me:resumed, clock-me-note:"));
+
+        let reimported = TimeclockImporter::new(None).import(&exported).unwrap();
+        assert_eq!(reimported.len(), 1);
+        assert_eq!(reimported[0].note, session.note);
+        assert_eq!(reimported[0].breaks.len(), 1);
+    }
+
+    #[test]
+    fn test_round_trip_with_exporter() {
+        let sessions = TimeclockImporter::new(None).import(SAMPLE).unwrap();
+        let exported = TimeclockExporter.export("my-app", &sessions);
+        let reimported = TimeclockImporter::new(None).import(&exported).unwrap();
+
+        assert_eq!(reimported.len(), sessions.len());
+        for (a, b) in sessions.iter().zip(&reimported) {
+            assert_eq!(a.start, b.start);
+            assert_eq!(a.end, b.end);
+            assert_eq!(a.note, b.note);
+        }
+    }
+}
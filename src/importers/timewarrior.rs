@@ -0,0 +1,424 @@
+use crate::importers::Importer;
+use crate::models::r#break::Break;
+use crate::models::session::Session;
+use crate::validators::{NoteValidator, TagValidator};
+use anyhow::{anyhow, Context, Result};
+use chrono::{DateTime, Local, NaiveDateTime};
+use serde::Deserialize;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+/// Reads Timewarrior data, either `data/YYYY-MM.data` interval lines such as
+/// `inc 20251013T080000Z - 20251013T120000Z # tag1 "tag two"` or the JSON
+/// array printed by `timew export`. Open intervals (still running) are skipped.
+pub struct TimewarriorImporter {
+    /// Merge intervals on the same day with the same tags into one session,
+    /// turning the gaps between them into breaks
+    pub reconstruct_breaks: bool,
+}
+
+#[derive(Debug, Deserialize)]
+struct ExportedInterval {
+    start: String,
+    end: Option<String>,
+    #[serde(default)]
+    tags: Vec<String>,
+    annotation: Option<String>,
+}
+
+struct Interval {
+    start: DateTime<Local>,
+    end: DateTime<Local>,
+    tags: Vec<String>,
+    annotation: Option<String>,
+}
+
+impl TimewarriorImporter {
+    pub fn new(reconstruct_breaks: bool) -> Self {
+        Self { reconstruct_breaks }
+    }
+
+    fn parse_timestamp(text: &str) -> Result<DateTime<Local>> {
+        NaiveDateTime::parse_from_str(text, "%Y%m%dT%H%M%SZ")
+            .map(|naive| naive.and_utc().with_timezone(&Local))
+            .map_err(|_| anyhow!("Invalid Timewarrior timestamp '{}'", text))
+    }
+
+    /// Reads the interval files of a Timewarrior `data/` directory, one per
+    /// month and named like `2025-10.data`, in order. `tags.data`, `undo.data`
+    /// and other files next to them are skipped.
+    pub fn read_data_dir(dir: &Path) -> Result<String> {
+        let mut files: Vec<PathBuf> = fs::read_dir(dir)
+            .with_context(|| format!("Failed to read {}", dir.display()))?
+            .filter_map(|entry| entry.ok().map(|e| e.path()))
+            .filter(|path| {
+                path.file_name()
+                    .and_then(|name| name.to_str())
+                    .is_some_and(Self::is_month_file)
+            })
+            .collect();
+        if files.is_empty() {
+            return Err(anyhow!(
+                "No Timewarrior interval files (YYYY-MM.data) in {}",
+                dir.display()
+            ));
+        }
+        files.sort();
+
+        let mut content = String::new();
+        for file in files {
+            content.push_str(
+                &fs::read_to_string(&file)
+                    .with_context(|| format!("Failed to read {}", file.display()))?,
+            );
+            content.push('\n');
+        }
+        Ok(content)
+    }
+
+    fn is_month_file(name: &str) -> bool {
+        let Some(month) = name.strip_suffix(".data") else {
+            return false;
+        };
+        let bytes = month.as_bytes();
+        bytes.len() == 7
+            && bytes[4] == b'-'
+            && bytes
+                .iter()
+                .enumerate()
+                .all(|(i, b)| i == 4 || b.is_ascii_digit())
+    }
+
+    /// Turns a Timewarrior tag into a valid clock-me tag: spaces become
+    /// hyphens, other unsupported characters are dropped and long tags are
+    /// cut to the length clock-me allows
+    pub fn sanitize_tag(tag: &str) -> Option<String> {
+        let cleaned: String = tag
+            .trim()
+            .chars()
+            .filter_map(|c| match c {
+                ' ' => Some('-'),
+                c if c.is_ascii_alphanumeric() || c == '-' || c == '_' => Some(c),
+                _ => None,
+            })
+            .collect();
+        let mut cleaned = cleaned.trim_start_matches(['-', '_']).to_string();
+        cleaned.truncate(TagValidator::MAX_LEN);
+
+        TagValidator::validate_name(&cleaned).ok().map(|_| cleaned)
+    }
+
+    /// Turns an annotation into a valid note: one line, cut to the length
+    /// clock-me allows
+    pub fn sanitize_note(text: &str) -> Option<String> {
+        let flattened = text.split_whitespace().collect::<Vec<_>>().join(" ");
+        let note = if flattened.chars().count() > NoteValidator::MAX_LEN {
+            let mut cut: String = flattened.chars().take(NoteValidator::MAX_LEN - 1).collect();
+            cut.push('…');
+            cut
+        } else {
+            flattened
+        };
+
+        NoteValidator::validate(&note).ok().map(|_| note)
+    }
+
+    /// Splits on whitespace, keeping double-quoted words together
+    fn split_words(text: &str) -> Vec<String> {
+        let mut words = Vec::new();
+        let mut current = String::new();
+        let mut quoted = false;
+        let mut chars = text.chars();
+
+        while let Some(c) = chars.next() {
+            match c {
+                '\\' if quoted => {
+                    if let Some(next) = chars.next() {
+                        current.push(next);
+                    }
+                }
+                '"' => quoted = !quoted,
+                c if c.is_whitespace() && !quoted => {
+                    if !current.is_empty() {
+                        words.push(std::mem::take(&mut current));
+                    }
+                }
+                c => current.push(c),
+            }
+        }
+        if !current.is_empty() {
+            words.push(current);
+        }
+
+        words
+    }
+
+    fn parse_data_line(line: &str, number: usize) -> Result<Option<Interval>> {
+        let mut sections = line.splitn(3, " # ");
+        let range = sections.next().unwrap_or_default().trim();
+        let tags = sections.next().map(Self::split_words).unwrap_or_default();
+        let annotation = sections
+            .next()
+            .and_then(|a| Self::sanitize_note(&Self::split_words(a).join(" ")));
+
+        let words: Vec<&str> = range.split_whitespace().collect();
+        match words.as_slice() {
+            ["inc", _start] => Ok(None),
+            ["inc", start, "-", end] => Ok(Some(Interval {
+                start: Self::parse_timestamp(start).with_context(|| format!("Line {}", number))?,
+                end: Self::parse_timestamp(end).with_context(|| format!("Line {}", number))?,
+                tags,
+                annotation,
+            })),
+            _ => Err(anyhow!(
+                "Line {}: expected 'inc START - END', found '{}'",
+                number,
+                line
+            )),
+        }
+    }
+
+    fn parse_json(content: &str) -> Result<Vec<Interval>> {
+        let exported: Vec<ExportedInterval> =
+            serde_json::from_str(content).context("Failed to parse Timewarrior export")?;
+
+        let mut intervals = Vec::new();
+        for entry in exported {
+            let Some(end) = entry.end else {
+                continue;
+            };
+            intervals.push(Interval {
+                start: Self::parse_timestamp(&entry.start)?,
+                end: Self::parse_timestamp(&end)?,
+                tags: entry.tags,
+                annotation: entry.annotation.as_deref().and_then(Self::sanitize_note),
+            });
+        }
+        Ok(intervals)
+    }
+
+    fn parse_data(content: &str) -> Result<Vec<Interval>> {
+        let mut intervals = Vec::new();
+        for (index, line) in content.lines().enumerate() {
+            let line = line.trim();
+            if line.is_empty() {
+                continue;
+            }
+            if let Some(interval) = Self::parse_data_line(line, index + 1)? {
+                intervals.push(interval);
+            }
+        }
+        Ok(intervals)
+    }
+
+    fn normalized_tags(tags: &[String]) -> Vec<String> {
+        let mut normalized: Vec<String> = Vec::new();
+        for tag in tags.iter().filter_map(|t| Self::sanitize_tag(t)) {
+            if !normalized.contains(&tag) {
+                normalized.push(tag);
+            }
+        }
+        normalized
+    }
+
+    fn same_tags(a: &[String], b: &[String]) -> bool {
+        let mut a = a.to_vec();
+        let mut b = b.to_vec();
+        a.sort();
+        b.sort();
+        a == b
+    }
+}
+
+impl Importer for TimewarriorImporter {
+    fn import(&self, content: &str) -> Result<Vec<Session>> {
+        let mut intervals = if content.trim_start().starts_with('[') {
+            Self::parse_json(content)?
+        } else {
+            Self::parse_data(content)?
+        };
+        intervals.sort_by_key(|i| i.start);
+
+        let mut sessions: Vec<Session> = Vec::new();
+        for interval in intervals {
+            if interval.end <= interval.start {
+                return Err(anyhow!(
+                    "Interval starting {} does not end after it starts",
+                    interval.start.format("%Y-%m-%d %H:%M")
+                ));
+            }
+            let tags = Self::normalized_tags(&interval.tags);
+
+            if self.reconstruct_breaks {
+                if let Some(previous) = sessions.last_mut() {
+                    let previous_end = previous.end.unwrap();
+                    if previous_end < interval.start
+                        && previous_end.date_naive() == interval.start.date_naive()
+                        && Self::same_tags(&previous.tags, &tags)
+                    {
+                        let mut gap = Break::new(previous_end);
+                        gap.finish(interval.start);
+                        previous.add_break(gap);
+                        previous.finish(interval.end);
+                        if let Some(ref annotation) = interval.annotation {
+                            if previous.note.as_deref() != Some(annotation.as_str()) {
+                                previous.append_note(annotation);
+                                previous.note =
+                                    previous.note.as_deref().and_then(Self::sanitize_note);
+                            }
+                        }
+                        continue;
+                    }
+                }
+            }
+
+            let mut session = Session::imported(interval.start, interval.end);
+            session.tags = tags;
+            session.note = interval.annotation;
+            sessions.push(session);
+        }
+
+        Ok(sessions)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::{TimeZone, Utc};
+
+    const DATA: &str = "\
+inc 20251013T080000Z - 20251013T100000Z # coding \"client work\"
+inc 20251013T103000Z - 20251013T120000Z # \"client work\" coding
+inc 20251013T130000Z - 20251013T140000Z # meeting # \"sprint planning\"
+inc 20251013T150000Z
+";
+
+    #[test]
+    fn test_import_data_lines() {
+        let sessions = TimewarriorImporter::new(false).import(DATA).unwrap();
+
+        assert_eq!(sessions.len(), 3);
+        assert_eq!(
+            sessions[0].start,
+            Utc.with_ymd_and_hms(2025, 10, 13, 8, 0, 0)
+                .unwrap()
+                .with_timezone(&Local)
+        );
+        assert_eq!(sessions[0].tags, vec!["coding", "client-work"]);
+        assert_eq!(sessions[2].note.as_deref(), Some("sprint planning"));
+        assert!(sessions.iter().all(|s| s.breaks.is_empty()));
+    }
+
+    #[test]
+    fn test_reconstruct_breaks() {
+        let sessions = TimewarriorImporter::new(true).import(DATA).unwrap();
+
+        // The first two intervals share tags and become one session with a break
+        assert_eq!(sessions.len(), 2);
+        assert_eq!(sessions[0].breaks.len(), 1);
+        assert_eq!(sessions[0].total_break_time().num_minutes(), 30);
+        assert_eq!(sessions[0].work_time().unwrap().num_minutes(), 120 + 90);
+        assert_eq!(sessions[1].tags, vec!["meeting"]);
+    }
+
+    #[test]
+    fn test_import_json_export() {
+        let json = r#"[
+            {"id":2,"start":"20251013T080000Z","end":"20251013T090000Z","tags":["review"],"annotation":"PR 42"},
+            {"id":1,"start":"20251013T100000Z","tags":["open"]}
+        ]"#;
+
+        let sessions = TimewarriorImporter::new(false).import(json).unwrap();
+
+        assert_eq!(sessions.len(), 1);
+        assert_eq!(sessions[0].tags, vec!["review"]);
+        assert_eq!(sessions[0].note.as_deref(), Some("PR 42"));
+        assert_eq!(sessions[0].work_time().unwrap().num_minutes(), 60);
+    }
+
+    #[test]
+    fn test_sanitize_tag() {
+        assert_eq!(
+            TimewarriorImporter::sanitize_tag("client work"),
+            Some("client-work".to_string())
+        );
+        assert_eq!(
+            TimewarriorImporter::sanitize_tag("+urgent!"),
+            Some("urgent".to_string())
+        );
+        assert_eq!(TimewarriorImporter::sanitize_tag("@@"), None);
+        assert_eq!(
+            TimewarriorImporter::sanitize_tag(&"a".repeat(40)),
+            Some("a".repeat(30))
+        );
+    }
+
+    #[test]
+    fn test_sanitize_note() {
+        assert_eq!(
+            TimewarriorImporter::sanitize_note("call with\n  client\r\n"),
+            Some("call with client".to_string())
+        );
+        assert_eq!(TimewarriorImporter::sanitize_note(" \n "), None);
+
+        let note = TimewarriorImporter::sanitize_note(&"x".repeat(600)).unwrap();
+        assert_eq!(note.chars().count(), 500);
+        assert!(note.ends_with('…'));
+    }
+
+    #[test]
+    fn test_import_json_annotation_becomes_valid_note() {
+        let content = r#"[
+            {"id":1,"start":"20251013T080000Z","end":"20251013T090000Z","tags":["a-very-long-tag-name-from-timewarrior-1"],"annotation":"first line\nsecond line"}
+        ]"#;
+        let sessions = TimewarriorImporter::new(false).import(content).unwrap();
+
+        assert_eq!(sessions[0].note.as_deref(), Some("first line second line"));
+        assert_eq!(sessions[0].tags, vec!["a-very-long-tag-name-from-time"]);
+    }
+
+    #[test]
+    fn test_read_data_dir_skips_other_files() {
+        let temp_dir = tempfile::TempDir::new().unwrap();
+        let dir = temp_dir.path();
+        fs::write(
+            dir.join("2025-10.data"),
+            "inc 20251013T080000Z - 20251013T100000Z # coding\n",
+        )
+        .unwrap();
+        fs::write(
+            dir.join("2025-09.data"),
+            "inc 20250915T080000Z - 20250915T090000Z # review\n",
+        )
+        .unwrap();
+        fs::write(dir.join("tags.data"), "{\"coding\":{\"count\":1}}\n").unwrap();
+        fs::write(
+            dir.join("undo.data"),
+            "txn:\n  type: interval\n  before: \n  after: inc 20251013T080000Z\n",
+        )
+        .unwrap();
+        fs::write(dir.join("backlog.data"), "garbage\n").unwrap();
+
+        let content = TimewarriorImporter::read_data_dir(dir).unwrap();
+        let sessions = TimewarriorImporter::new(false).import(&content).unwrap();
+
+        assert_eq!(sessions.len(), 2);
+        assert_eq!(sessions[0].tags, vec!["review"]);
+        assert_eq!(sessions[1].tags, vec!["coding"]);
+    }
+
+    #[test]
+    fn test_read_data_dir_without_interval_files() {
+        let temp_dir = tempfile::TempDir::new().unwrap();
+        fs::write(temp_dir.path().join("tags.data"), "{}\n").unwrap();
+
+        assert!(TimewarriorImporter::read_data_dir(temp_dir.path()).is_err());
+    }
+
+    #[test]
+    fn test_invalid_line() {
+        let result = TimewarriorImporter::new(false).import("exc 20251013T080000Z\n");
+        assert!(result.unwrap_err().to_string().contains("Line 1"));
+    }
+}
@@ -1,8 +1,12 @@
 pub mod cli;
 pub mod clock;
 pub mod command_handler;
+pub mod exporters;
+pub mod git;
+pub mod importers;
 pub mod models;
 pub mod parsers;
+pub mod render;
 pub mod repository;
 pub mod session_service;
 pub mod validators;
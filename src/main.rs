@@ -1,14 +1,43 @@
 use clap::{Parser, Subcommand};
 use clock_me::cli::CLI;
+use std::path::PathBuf;
 
 #[derive(Parser)]
 #[command(name = "clock-me")]
 #[command(about = "A simple CLI time tracker", long_about = None)]
 struct Args {
+    /// How to print results: auto (color on a terminal), text, color, json or quiet.
+    /// json and quiet apply to init, start, stop, break, status and report
+    #[arg(
+        long,
+        global = true,
+        default_value = "auto",
+        value_parser = ["auto", "text", "color", "json", "quiet"]
+    )]
+    output: String,
     #[command(subcommand)]
     command: Commands,
 }
 
+/// Record the action at an earlier time instead of now
+#[derive(clap::Args)]
+struct TimeArgs {
+    /// Time of the action: HH:MM (today) or "YYYY-MM-DD HH:MM"
+    #[arg(long, conflicts_with = "ago")]
+    at: Option<String>,
+    /// How long ago the action happened, e.g. "20m" or "1h 15m"
+    #[arg(long)]
+    ago: Option<String>,
+}
+
+/// Describe the session or break
+#[derive(clap::Args)]
+struct MessageArgs {
+    /// Note for the session (or reason for a break)
+    #[arg(short = 'm', long = "message")]
+    message: Option<String>,
+}
+
 #[derive(Subcommand)]
 enum Commands {
     /// Initialize a new project
@@ -17,25 +46,390 @@ enum Commands {
         name: Option<String>,
     },
     /// Clock in (or continue from break)
-    Start,
+    Start {
+        #[command(flatten)]
+        time: TimeArgs,
+        #[command(flatten)]
+        message: MessageArgs,
+        /// Tag the session (repeatable)
+        #[arg(short, long = "tag")]
+        tags: Vec<String>,
+    },
     /// Clock out
-    Stop,
+    Stop {
+        #[command(flatten)]
+        time: TimeArgs,
+        #[command(flatten)]
+        message: MessageArgs,
+    },
     /// Take a break
-    Break,
+    Break {
+        #[command(flatten)]
+        time: TimeArgs,
+        #[command(flatten)]
+        message: MessageArgs,
+    },
+    /// Set the note of the active session (or the last one when clocked out)
+    Note {
+        /// What the session was spent on
+        text: String,
+        /// Add to the existing note instead of replacing it
+        #[arg(short, long)]
+        append: bool,
+    },
     /// Show current tracking status
-    Status,
+    Status {
+        /// Show which registered projects are clocked in
+        #[arg(long)]
+        all: bool,
+    },
+    /// Print a one-line status for shell prompts and status bars; prints nothing outside a project
+    Prompt {
+        /// Template with {project}, {state}, {icon}, {session}, {break}, {elapsed} and {today}
+        #[arg(long, default_value = clock_me::render::prompt::DEFAULT_FORMAT)]
+        format: String,
+    },
+    /// Print the status as JSON for a waybar or i3blocks module
+    Bar {
+        /// JSON dialect of the bar
+        #[arg(long, default_value = "waybar", value_parser = ["waybar", "i3blocks"])]
+        format: String,
+        /// Daily work time the percentage is measured against (e.g. 8h, 7h30m)
+        #[arg(long, default_value = "8h")]
+        goal: String,
+        /// Template for the text, with the placeholders of `prompt --format`
+        #[arg(long, default_value = clock_me::render::prompt::DEFAULT_FORMAT)]
+        text: String,
+        /// Keep running and print a new line every N seconds
+        #[arg(long, value_name = "N")]
+        watch: Option<u64>,
+    },
+    /// List every project initialized on this machine with today's and total work time
+    Projects {
+        /// Forget projects whose directory no longer exists
+        #[arg(long)]
+        prune: bool,
+        /// Add the current project, e.g. one initialized before the registry existed
+        #[arg(long, conflicts_with = "prune")]
+        add: bool,
+    },
+    /// Add time you forgot to track
+    Add {
+        /// Duration, e.g. "2h 30m", "1.5h" or "45m"
+        duration: String,
+        /// Day of the entry (YYYY-MM-DD), defaults to today
+        #[arg(long)]
+        date: Option<String>,
+        /// Start time (HH:MM). Without it the entry ends now
+        #[arg(long)]
+        at: Option<String>,
+        /// What the time was spent on
+        #[arg(long)]
+        note: Option<String>,
+        /// Tag the entry (repeatable)
+        #[arg(short, long = "tag")]
+        tags: Vec<String>,
+    },
+    /// Add or remove tags on the active session (or a past one)
+    Tag {
+        /// Tags to add or remove
+        #[arg(required = true)]
+        tags: Vec<String>,
+        /// Finished session to retag: its ID, or a number counting back from the last one (1 = last)
+        #[arg(short, long)]
+        session: Option<String>,
+        /// Remove the tags instead of adding them
+        #[arg(short, long)]
+        remove: bool,
+    },
+    /// Show work and break time grouped by day, week or month
+    Report {
+        /// Group sessions by day, ISO week or calendar month
+        #[arg(short, long, default_value = "day", value_parser = ["day", "week", "month"])]
+        by: String,
+        /// First day to include (YYYY-MM-DD)
+        #[arg(long)]
+        from: Option<String>,
+        /// Last day to include (YYYY-MM-DD), defaults to today
+        #[arg(long)]
+        to: Option<String>,
+        /// Also show work time per tag
+        #[arg(short, long)]
+        tags: bool,
+        /// Also list the git commits of each session
+        #[arg(short, long)]
+        commits: bool,
+    },
+    /// List finished sessions with the IDs used by edit and delete
+    Log {
+        /// First day to include (YYYY-MM-DD)
+        #[arg(long)]
+        from: Option<String>,
+        /// Last day to include (YYYY-MM-DD)
+        #[arg(long)]
+        to: Option<String>,
+        /// Show at most this many of the most recent sessions
+        #[arg(short = 'n', long, default_value_t = 20)]
+        limit: usize,
+        /// List each session's breaks
+        #[arg(short, long)]
+        breaks: bool,
+    },
+    /// Change the start or end of a finished session or one of its breaks
+    Edit {
+        /// Session ID (or a unique prefix of at least 4 characters) from 'clock-me log'
+        id: String,
+        /// New start: HH:MM (on the session's date) or "YYYY-MM-DD HH:MM"
+        #[arg(long)]
+        start: Option<String>,
+        /// New end: HH:MM (on the session's date) or "YYYY-MM-DD HH:MM"
+        #[arg(long)]
+        end: Option<String>,
+        /// Edit this break (number from 1, or ID) instead of the session
+        #[arg(long = "break")]
+        break_id: Option<String>,
+    },
+    /// Remove a finished session or one of its breaks
+    Delete {
+        /// Session ID (or a unique prefix of at least 4 characters) from 'clock-me log'
+        id: String,
+        /// Remove only this break (number from 1, or ID)
+        #[arg(long = "break")]
+        break_id: Option<String>,
+    },
+    /// Split a finished session in two at a given time
+    Split {
+        /// Session ID (or a unique prefix of at least 4 characters) from 'clock-me log'
+        id: String,
+        /// Where to split: HH:MM (on the session's date) or "YYYY-MM-DD HH:MM"
+        #[arg(long)]
+        at: String,
+    },
+    /// Merge two consecutive finished sessions, keeping the gap as a break
+    Merge {
+        /// ID of the first session
+        first: String,
+        /// ID of the second session
+        second: String,
+        /// Merge sessions from different days or more than 4 hours apart
+        #[arg(long)]
+        force: bool,
+    },
+    /// Export finished sessions to another format
+    Export {
+        /// Output format
+        #[arg(short, long, default_value = "csv", value_parser = ["csv", "ics", "timeclock"])]
+        format: String,
+        /// First day to include (YYYY-MM-DD)
+        #[arg(long)]
+        from: Option<String>,
+        /// Last day to include (YYYY-MM-DD)
+        #[arg(long)]
+        to: Option<String>,
+        /// Write to a file instead of stdout
+        #[arg(short = 'o', long)]
+        file: Option<PathBuf>,
+        /// CSV: one row per break instead of per session. ICS: breaks as separate events
+        #[arg(long)]
+        breaks: bool,
+    },
+    /// Import sessions from another time tracker
+    Import {
+        /// Input format
+        #[arg(short, long, value_parser = ["timeclock", "timewarrior"])]
+        format: String,
+        /// File to read (for Timewarrior, a `timew export` file or the data/ directory)
+        file: PathBuf,
+        /// Only import entries for this timeclock account
+        #[arg(long)]
+        account: Option<String>,
+        /// Timewarrior: join same-day intervals with the same tags, turning gaps into breaks
+        #[arg(long)]
+        breaks: bool,
+    },
+    /// Check the project data for inconsistencies
+    Doctor {
+        /// Back up the project, then sort, close and trim records where that loses no time
+        #[arg(long)]
+        fix: bool,
+    },
+    /// Revert the last state-changing commands
+    Undo {
+        /// How many commands to revert
+        #[arg(default_value_t = 1)]
+        steps: usize,
+    },
+    /// Reapply commands reverted with undo
+    Redo {
+        /// How many commands to reapply
+        #[arg(default_value_t = 1)]
+        steps: usize,
+    },
+    /// Record git commits on sessions
+    Git {
+        #[command(subcommand)]
+        command: GitCommands,
+    },
+    /// Move project data to another storage backend
+    MigrateStorage {
+        /// Backend to move to
+        #[arg(long, value_parser = ["json", "sqlite", "events"])]
+        to: String,
+    },
+}
+
+#[derive(Subcommand)]
+enum GitCommands {
+    /// Install hooks in the repository that record commits and branch switches on the active session
+    InstallHooks {
+        /// Also start a session on a commit while clocked out
+        #[arg(long)]
+        auto_start: bool,
+    },
+    /// Run by the post-commit hook
+    #[command(hide = true)]
+    PostCommit {
+        #[arg(long)]
+        auto_start: bool,
+    },
+    /// Run by the post-checkout hook, with git's arguments
+    #[command(hide = true)]
+    PostCheckout {
+        previous: String,
+        new: String,
+        branch_checkout: String,
+    },
+    /// Run by the post-rewrite hook, with the rewritten commits on stdin
+    #[command(hide = true)]
+    PostRewrite {
+        /// `amend` or `rebase`
+        command: Option<String>,
+    },
+}
+
+impl Commands {
+    /// Whether the command prints its result through a renderer, and so
+    /// accepts every output format
+    fn uses_renderer(&self) -> bool {
+        matches!(
+            self,
+            Commands::Init { .. }
+                | Commands::Start { .. }
+                | Commands::Stop { .. }
+                | Commands::Break { .. }
+                | Commands::Status { all: false }
+                | Commands::Report { .. }
+        )
+    }
 }
 
 fn main() {
     let args = Args::parse();
-    let cli = CLI::new();
+    // Runs on every shell prompt, so it skips the usual setup
+    if let Commands::Prompt { format } = &args.command {
+        CLI::print_prompt(format);
+        return;
+    }
+    if matches!(args.output.as_str(), "json" | "quiet") && !args.command.uses_renderer() {
+        eprintln!(
+            "Error: --output {} is not supported by this command",
+            args.output
+        );
+        std::process::exit(1);
+    }
+
+    let cli = match CLI::new(&args.output) {
+        Ok(cli) => cli,
+        Err(e) => {
+            eprintln!("Error: {}", e);
+            std::process::exit(1);
+        }
+    };
 
     let result = match args.command {
         Commands::Init { name } => cli.handle_init(name),
-        Commands::Start => cli.handle_now(),
-        Commands::Stop => cli.handle_out(),
-        Commands::Break => cli.handle_break(),
-        Commands::Status => cli.handle_status(),
+        Commands::Start {
+            time,
+            message,
+            tags,
+        } => cli.handle_now(time.at, time.ago, message.message, tags),
+        Commands::Stop { time, message } => cli.handle_out(time.at, time.ago, message.message),
+        Commands::Break { time, message } => cli.handle_break(time.at, time.ago, message.message),
+        Commands::Note { text, append } => cli.handle_note(text, append),
+        Commands::Status { all } => cli.handle_status(all),
+        Commands::Prompt { .. } => Ok(()),
+        Commands::Git { command } => match command {
+            GitCommands::InstallHooks { auto_start } => cli.handle_git_install_hooks(auto_start),
+            GitCommands::PostCommit { auto_start } => cli.handle_git_post_commit(auto_start),
+            GitCommands::PostCheckout {
+                previous,
+                new: _,
+                branch_checkout,
+            } => cli.handle_git_post_checkout(previous, branch_checkout),
+            GitCommands::PostRewrite { command: _ } => cli.handle_git_post_rewrite(),
+        },
+        Commands::Bar {
+            format,
+            goal,
+            text,
+            watch,
+        } => cli.handle_bar(format, goal, text, watch),
+        Commands::Projects { prune, add } => cli.handle_projects(prune, add),
+        Commands::Add {
+            duration,
+            date,
+            at,
+            note,
+            tags,
+        } => cli.handle_add(duration, date, at, note, tags),
+        Commands::Tag {
+            tags,
+            session,
+            remove,
+        } => cli.handle_tag(tags, session, remove),
+        Commands::Report {
+            by,
+            from,
+            to,
+            tags,
+            commits,
+        } => cli.handle_report(by, from, to, tags, commits),
+        Commands::Log {
+            from,
+            to,
+            limit,
+            breaks,
+        } => cli.handle_log(from, to, limit, breaks),
+        Commands::Edit {
+            id,
+            start,
+            end,
+            break_id,
+        } => cli.handle_edit(id, start, end, break_id),
+        Commands::Delete { id, break_id } => cli.handle_delete(id, break_id),
+        Commands::Split { id, at } => cli.handle_split(id, at),
+        Commands::Merge {
+            first,
+            second,
+            force,
+        } => cli.handle_merge(first, second, force),
+        Commands::Export {
+            format,
+            from,
+            to,
+            file,
+            breaks,
+        } => cli.handle_export(format, from, to, file, breaks),
+        Commands::Import {
+            format,
+            file,
+            account,
+            breaks,
+        } => cli.handle_import(format, file, account, breaks),
+        Commands::Doctor { fix } => cli.handle_doctor(fix),
+        Commands::Undo { steps } => cli.handle_undo(steps),
+        Commands::Redo { steps } => cli.handle_redo(steps),
+        Commands::MigrateStorage { to } => cli.handle_migrate_storage(to),
     };
 
     if let Err(e) = result {
@@ -43,3 +437,34 @@ fn main() {
         std::process::exit(1);
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use clap::CommandFactory;
+
+    #[test]
+    fn test_args_are_consistent() {
+        Args::command().debug_assert();
+    }
+
+    #[test]
+    fn test_export_file_and_global_output() {
+        let args = Args::try_parse_from([
+            "clock-me",
+            "export",
+            "-o",
+            "october.csv",
+            "--output",
+            "text",
+        ])
+        .unwrap();
+
+        assert_eq!(args.output, "text");
+        let Commands::Export { file, format, .. } = args.command else {
+            panic!("expected export");
+        };
+        assert_eq!(file, Some(PathBuf::from("october.csv")));
+        assert_eq!(format, "csv");
+    }
+}
@@ -1,15 +1,28 @@
+use crate::models::id;
 use chrono::{DateTime, Duration, Local};
 use serde::{Deserialize, Serialize};
+use uuid::Uuid;
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct Break {
+    /// Nil for breaks stored before IDs existed, until backfilled on load
+    #[serde(default)]
+    pub id: Uuid,
     pub start: DateTime<Local>,
     pub end: Option<DateTime<Local>>,
+    /// Reason for the break
+    #[serde(default)]
+    pub note: Option<String>,
 }
 
 impl Break {
     pub fn new(start: DateTime<Local>) -> Self {
-        Self { start, end: None }
+        Self {
+            id: id::generate(),
+            start,
+            end: None,
+            note: None,
+        }
     }
 
     pub fn finish(&mut self, end: DateTime<Local>) {
@@ -23,6 +36,10 @@ impl Break {
     pub fn is_active(&self) -> bool {
         self.end.is_none()
     }
+
+    pub fn short_id(&self) -> String {
+        id::short(&self.id)
+    }
 }
 
 #[cfg(test)]
@@ -52,4 +69,13 @@ mod tests {
         assert!(!break_period.is_active());
         assert_eq!(break_period.duration().unwrap().num_minutes(), 15);
     }
+
+    #[test]
+    fn test_old_break_without_note() {
+        let json = r#"{"start":"2025-10-13T12:00:00+00:00","end":"2025-10-13T12:15:00+00:00"}"#;
+        let break_period: Break = serde_json::from_str(json).unwrap();
+
+        assert!(break_period.note.is_none());
+        assert_eq!(break_period.duration().unwrap().num_minutes(), 15);
+    }
 }
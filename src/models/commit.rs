@@ -0,0 +1,31 @@
+use chrono::{DateTime, Local};
+use serde::{Deserialize, Serialize};
+
+/// A git commit made while a session was running, recorded by the
+/// post-commit hook from `clock-me git install-hooks`
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Commit {
+    pub hash: String,
+    /// First line of the commit message
+    pub subject: String,
+    /// `None` on a detached HEAD
+    pub branch: Option<String>,
+    /// When the hook recorded the commit
+    pub at: DateTime<Local>,
+}
+
+impl Commit {
+    /// The abbreviated hash, as git prints it by default
+    pub fn short_hash(&self) -> &str {
+        &self.hash[..self.hash.len().min(7)]
+    }
+}
+
+/// A branch switch while a session was running, recorded by the
+/// post-checkout hook from `clock-me git install-hooks`
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Checkout {
+    pub branch: String,
+    /// When the hook recorded the switch
+    pub at: DateTime<Local>,
+}
@@ -0,0 +1,85 @@
+use anyhow::{anyhow, Result};
+use uuid::Uuid;
+
+/// Shortest ID prefix accepted when looking up a record
+pub const MIN_PREFIX_LEN: usize = 4;
+
+/// Number of characters shown for an ID in listings
+pub const SHORT_LEN: usize = 8;
+
+/// Namespace for IDs derived from existing data, see [`derived`]
+const NAMESPACE: Uuid = Uuid::from_u128(0x6c6f_636b_2d6d_6540_8000_0000_0000_0001);
+
+pub fn generate() -> Uuid {
+    Uuid::new_v4()
+}
+
+/// A stable ID for a record stored before IDs existed, so it stays the same
+/// on every load until the data is saved with it
+pub fn derived(name: &str) -> Uuid {
+    Uuid::new_v5(&NAMESPACE, name.as_bytes())
+}
+
+pub fn short(id: &Uuid) -> String {
+    id.simple().to_string()[..SHORT_LEN].to_string()
+}
+
+/// Position of the only ID that starts with `query` (dashes and case are ignored)
+pub fn find<'a>(ids: impl Iterator<Item = &'a Uuid>, query: &str, what: &str) -> Result<usize> {
+    let prefix = query.trim().replace('-', "").to_lowercase();
+    if prefix.len() < MIN_PREFIX_LEN || !prefix.chars().all(|c| c.is_ascii_hexdigit()) {
+        return Err(anyhow!(
+            "Invalid {} ID '{}'. Use at least {} characters of the ID shown by 'clock-me log'.",
+            what,
+            query,
+            MIN_PREFIX_LEN
+        ));
+    }
+
+    let matches: Vec<usize> = ids
+        .enumerate()
+        .filter(|(_, id)| id.simple().to_string().starts_with(&prefix))
+        .map(|(index, _)| index)
+        .collect();
+
+    match matches.as_slice() {
+        [index] => Ok(*index),
+        [] => Err(anyhow!("No {} with ID '{}'", what, query)),
+        _ => Err(anyhow!(
+            "ID '{}' matches {} {}s. Use more characters.",
+            query,
+            matches.len(),
+            what
+        )),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_find_by_prefix() {
+        let ids = [
+            Uuid::parse_str("0123abcd-0000-4000-8000-000000000000").unwrap(),
+            Uuid::parse_str("0123ef00-0000-4000-8000-000000000000").unwrap(),
+        ];
+
+        assert_eq!(find(ids.iter(), "0123AB", "session").unwrap(), 0);
+        assert_eq!(find(ids.iter(), "0123ef00-0000", "session").unwrap(), 1);
+        assert!(find(ids.iter(), "0123", "session")
+            .unwrap_err()
+            .to_string()
+            .contains("matches 2"));
+        assert!(find(ids.iter(), "ffff", "session").is_err());
+        assert!(find(ids.iter(), "012", "session").is_err());
+        assert!(find(ids.iter(), "zzzz", "session").is_err());
+    }
+
+    #[test]
+    fn test_derived_is_stable() {
+        assert_eq!(derived("session/1"), derived("session/1"));
+        assert_ne!(derived("session/1"), derived("session/2"));
+        assert_eq!(short(&derived("session/1")).len(), SHORT_LEN);
+    }
+}
@@ -0,0 +1,471 @@
+use crate::models::id;
+use crate::models::project::Project;
+use crate::models::r#break::Break;
+use crate::models::session::Session;
+use chrono::{DateTime, Local};
+use std::collections::HashSet;
+
+/// Record of the issue reported when the data cannot be read
+const UNREADABLE: &str = "project data";
+
+/// A broken invariant found by [`check`], with the record it was found in
+#[derive(Debug, Clone, PartialEq)]
+pub struct Issue {
+    pub record: String,
+    pub problem: String,
+    /// Whether [`repair`] resolves it
+    pub fixable: bool,
+}
+
+impl Issue {
+    fn new(record: &str, problem: String, fixable: bool) -> Self {
+        Self {
+            record: record.to_string(),
+            problem,
+            fixable,
+        }
+    }
+
+    /// The stored data could not be read at all, so nothing else was checked
+    pub fn unreadable(error: &anyhow::Error) -> Self {
+        Self::new(UNREADABLE, format!("cannot be read: {:#}", error), false)
+    }
+
+    pub fn is_unreadable(&self) -> bool {
+        self.record == UNREADABLE
+    }
+}
+
+/// Lists every inconsistency in the project. The active session is treated
+/// as running until `now`.
+pub fn check(project: &Project, now: DateTime<Local>) -> Vec<Issue> {
+    let mut issues = Vec::new();
+    let mut ids = HashSet::new();
+
+    for (index, session) in project.sessions.iter().enumerate() {
+        let record = describe(session);
+        if !ids.insert(session.id) {
+            issues.push(Issue::new(
+                &record,
+                "shares its ID with another session".to_string(),
+                true,
+            ));
+        }
+        if let Some(previous) = index.checked_sub(1).map(|i| &project.sessions[i]) {
+            if session.start < previous.start {
+                issues.push(Issue::new(
+                    &record,
+                    format!("is stored after the later {}", describe(previous)),
+                    true,
+                ));
+            }
+        }
+
+        match session.end {
+            None => issues.push(Issue::new(&record, "has no end".to_string(), false)),
+            Some(end) if end <= session.start => issues.push(Issue::new(
+                &record,
+                format!("ends at {}, before it starts", end.format("%Y-%m-%d %H:%M")),
+                false,
+            )),
+            Some(end) => issues.extend(check_breaks(session, end, &record)),
+        }
+    }
+
+    if let Some(ref session) = project.current_session {
+        let record = describe(session);
+        if !ids.insert(session.id) {
+            issues.push(Issue::new(
+                &record,
+                "shares its ID with another session".to_string(),
+                true,
+            ));
+        }
+        issues.extend(check_breaks(session, now, &record));
+    }
+
+    match (&project.current_session, &project.current_break) {
+        (None, Some(break_period)) => issues.push(Issue::new(
+            &format!(
+                "current break (since {})",
+                break_period.start.format("%Y-%m-%d %H:%M")
+            ),
+            "has no active session".to_string(),
+            true,
+        )),
+        (Some(session), Some(break_period)) if break_period.start < session.start => {
+            issues.push(Issue::new(
+                &describe(session),
+                format!(
+                    "current break starts at {}, before the session",
+                    break_period.start.format("%Y-%m-%d %H:%M")
+                ),
+                true,
+            ))
+        }
+        _ => {}
+    }
+
+    issues.extend(check_overlaps(project, now));
+    issues
+}
+
+/// Applies the repairs that lose no tracked time and returns what was done:
+/// sorts sessions and breaks, closes unfinished breaks at the end of their
+/// session, clamps breaks into their session and out of each other, drops an
+/// orphaned current break and gives duplicate sessions new IDs.
+pub fn repair(project: &mut Project, now: DateTime<Local>) -> Vec<String> {
+    let mut repairs = Vec::new();
+
+    match (&project.current_session, &mut project.current_break) {
+        (None, Some(break_period)) => {
+            repairs.push(format!(
+                "Removed the current break started {}, which had no active session",
+                break_period.start.format("%Y-%m-%d %H:%M")
+            ));
+            project.current_break = None;
+        }
+        (Some(session), Some(break_period)) if break_period.start < session.start => {
+            break_period.start = session.start;
+            repairs.push(format!(
+                "Moved the start of the current break to the start of {}",
+                describe(session)
+            ));
+        }
+        _ => {}
+    }
+
+    for session in project.sessions.iter_mut() {
+        if let Some(end) = session.end.filter(|end| *end > session.start) {
+            repair_breaks(session, end, &mut repairs);
+        }
+    }
+    if let Some(ref mut session) = project.current_session {
+        repair_breaks(session, now, &mut repairs);
+    }
+
+    let mut ids = HashSet::new();
+    let sessions = project
+        .sessions
+        .iter_mut()
+        .chain(project.current_session.as_mut());
+    for session in sessions {
+        if !ids.insert(session.id) {
+            let old = describe(session);
+            session.id = id::generate();
+            repairs.push(format!("Gave {} the new ID {}", old, session.short_id()));
+        }
+    }
+
+    if !project.sessions.is_sorted_by_key(|s| s.start) {
+        project.sessions.sort_by_key(|s| s.start);
+        repairs.push("Sorted sessions by start time".to_string());
+    }
+
+    repairs
+}
+
+/// Problems with the breaks of a session that lasts until `end`.
+/// Breaks are numbered from 1 in their stored order.
+fn check_breaks(session: &Session, end: DateTime<Local>, record: &str) -> Vec<Issue> {
+    let mut issues = Vec::new();
+    let mut valid: Vec<(usize, DateTime<Local>, DateTime<Local>)> = Vec::new();
+
+    for (index, break_period) in session.breaks.iter().enumerate() {
+        let number = index + 1;
+        if let Some(previous) = index.checked_sub(1).map(|i| &session.breaks[i]) {
+            if break_period.start < previous.start {
+                issues.push(Issue::new(
+                    record,
+                    format!("break {} is stored after the later break {}", number, index),
+                    true,
+                ));
+            }
+        }
+
+        let break_end = match break_period.end {
+            None => {
+                issues.push(Issue::new(
+                    record,
+                    format!(
+                        "break {} (since {}) has no end",
+                        number,
+                        time(break_period.start)
+                    ),
+                    true,
+                ));
+                end
+            }
+            Some(break_end) if break_end <= break_period.start => {
+                issues.push(Issue::new(
+                    record,
+                    format!(
+                        "break {} ({}) ends before it starts",
+                        number,
+                        span(break_period)
+                    ),
+                    false,
+                ));
+                continue;
+            }
+            Some(break_end) => break_end,
+        };
+
+        if break_period.start < session.start || break_end > end {
+            issues.push(Issue::new(
+                record,
+                format!(
+                    "break {} ({}) lies outside the session",
+                    number,
+                    span(break_period)
+                ),
+                true,
+            ));
+        }
+        valid.push((number, break_period.start, break_end));
+    }
+
+    valid.sort_by_key(|(_, start, _)| *start);
+    let mut latest: Option<(usize, DateTime<Local>)> = None;
+    for (number, start, break_end) in valid {
+        match latest {
+            Some((other, other_end)) if start < other_end => {
+                issues.push(Issue::new(
+                    record,
+                    format!("breaks {} and {} overlap", other, number),
+                    true,
+                ));
+                if break_end > other_end {
+                    latest = Some((number, break_end));
+                }
+            }
+            _ => latest = Some((number, break_end)),
+        }
+    }
+
+    issues
+}
+
+/// Finished and active sessions that share time with an earlier one
+fn check_overlaps(project: &Project, now: DateTime<Local>) -> Vec<Issue> {
+    let mut issues = Vec::new();
+    let mut sessions: Vec<(&Session, DateTime<Local>)> = project
+        .sessions
+        .iter()
+        .filter_map(|s| s.end.filter(|end| *end > s.start).map(|end| (s, end)))
+        .chain(project.current_session.iter().map(|s| (s, now)))
+        .collect();
+    sessions.sort_by_key(|(s, _)| s.start);
+
+    let mut latest: Option<(&Session, DateTime<Local>)> = None;
+    for (session, end) in sessions {
+        match latest {
+            Some((other, other_end)) if session.start < other_end => {
+                issues.push(Issue::new(
+                    &describe(session),
+                    format!("overlaps {}", describe(other)),
+                    false,
+                ));
+                if end > other_end {
+                    latest = Some((session, end));
+                }
+            }
+            _ => latest = Some((session, end)),
+        }
+    }
+
+    issues
+}
+
+/// Sorts the breaks of a session lasting until `end`, closes unfinished ones
+/// at `end` and trims them to the session and to each other. Breaks with no
+/// time left after trimming are removed; ones that end before they start are
+/// left alone.
+fn repair_breaks(session: &mut Session, end: DateTime<Local>, repairs: &mut Vec<String>) {
+    let record = describe(session);
+    if !session.breaks.is_sorted_by_key(|b| b.start) {
+        session.breaks.sort_by_key(|b| b.start);
+        repairs.push(format!("Sorted the breaks of {}", record));
+    }
+
+    let mut latest = session.start;
+    let mut kept = Vec::with_capacity(session.breaks.len());
+    for mut break_period in std::mem::take(&mut session.breaks) {
+        if break_period.end.is_some_and(|e| e <= break_period.start) {
+            kept.push(break_period);
+            continue;
+        }
+
+        let original = span(&break_period);
+        let was_open = break_period.end.is_none();
+        let break_end = break_period.end.unwrap_or(end).min(end);
+        break_period.start = break_period.start.max(latest);
+
+        if break_end <= break_period.start {
+            repairs.push(format!(
+                "Removed break {} of {}, which lay outside the session or within another break",
+                original, record
+            ));
+            continue;
+        }
+
+        break_period.end = Some(break_end);
+        if was_open {
+            repairs.push(format!(
+                "Closed break {} of {} at {}",
+                original,
+                record,
+                time(break_end)
+            ));
+        } else if span(&break_period) != original {
+            repairs.push(format!(
+                "Trimmed break {} of {} to {}",
+                original,
+                record,
+                span(&break_period)
+            ));
+        }
+
+        latest = break_end;
+        kept.push(break_period);
+    }
+    session.breaks = kept;
+}
+
+fn describe(session: &Session) -> String {
+    let end = session.end.map(time).unwrap_or_else(|| "now".to_string());
+    format!(
+        "session {} ({} {}–{})",
+        session.short_id(),
+        session.start.format("%Y-%m-%d"),
+        time(session.start),
+        end
+    )
+}
+
+fn span(break_period: &Break) -> String {
+    let end = break_period
+        .end
+        .map(time)
+        .unwrap_or_else(|| "…".to_string());
+    format!("{}–{}", time(break_period.start), end)
+}
+
+fn time(at: DateTime<Local>) -> String {
+    at.format("%H:%M").to_string()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::TimeZone;
+
+    fn at(day: u32, hour: u32, minute: u32) -> DateTime<Local> {
+        Local
+            .with_ymd_and_hms(2026, 10, day, hour, minute, 0)
+            .unwrap()
+    }
+
+    fn finished_break(start: DateTime<Local>, end: DateTime<Local>) -> Break {
+        let mut break_period = Break::new(start);
+        break_period.finish(end);
+        break_period
+    }
+
+    #[test]
+    fn test_consistent_project_has_no_issues() {
+        let mut project = Project::new("test".to_string());
+        let mut session = Session::manual(at(12, 9, 0), at(12, 17, 0));
+        session.add_break(finished_break(at(12, 12, 0), at(12, 12, 30)));
+        project.sessions.push(session);
+        project.start_session(at(13, 9, 0));
+        project.start_break(at(13, 10, 0)).unwrap();
+
+        assert!(check(&project, at(13, 11, 0)).is_empty());
+        assert!(repair(&mut project, at(13, 11, 0)).is_empty());
+    }
+
+    #[test]
+    fn test_reports_every_violation() {
+        let now = at(20, 18, 0);
+        let mut project = Project::new("test".to_string());
+
+        let mut later = Session::manual(at(13, 9, 0), at(13, 17, 0));
+        later.breaks.push(Break::new(at(13, 12, 0)));
+        later.add_break(finished_break(at(13, 8, 0), at(13, 9, 30)));
+        project.sessions.push(later);
+        project
+            .sessions
+            .push(Session::manual(at(12, 9, 0), at(12, 8, 0)));
+        project
+            .sessions
+            .push(Session::manual(at(13, 16, 0), at(13, 18, 0)));
+        project.current_break = Some(Break::new(at(20, 10, 0)));
+
+        let issues = check(&project, now);
+        let problems: Vec<(&str, bool)> = issues
+            .iter()
+            .map(|i| (i.problem.as_str(), i.fixable))
+            .collect();
+
+        assert!(problems.contains(&("break 1 (since 12:00) has no end", true)));
+        assert!(problems.contains(&("break 2 is stored after the later break 1", true)));
+        assert!(problems.contains(&("break 2 (08:00–09:30) lies outside the session", true)));
+        assert!(problems.contains(&("ends at 2026-10-12 08:00, before it starts", false)));
+        assert!(problems.contains(&("has no active session", true)));
+        assert!(issues
+            .iter()
+            .any(|i| i.problem.starts_with("is stored after the later") && i.fixable));
+        assert!(issues
+            .iter()
+            .any(|i| i.problem.starts_with("overlaps session") && !i.fixable));
+        assert_eq!(issues.len(), 7);
+    }
+
+    #[test]
+    fn test_repair_leaves_only_unfixable_issues() {
+        let now = at(20, 18, 0);
+        let mut project = Project::new("test".to_string());
+
+        let mut session = Session::manual(at(13, 9, 0), at(13, 17, 0));
+        session.breaks.push(Break::new(at(13, 16, 0)));
+        session.add_break(finished_break(at(13, 12, 0), at(13, 13, 0)));
+        session.add_break(finished_break(at(13, 12, 30), at(13, 13, 30)));
+        session.add_break(finished_break(at(13, 8, 0), at(13, 9, 30)));
+        session.add_break(finished_break(at(13, 18, 0), at(13, 19, 0)));
+        project.sessions.push(session);
+
+        let mut duplicate = Session::manual(at(12, 9, 0), at(12, 10, 0));
+        duplicate.id = project.sessions[0].id;
+        project.sessions.push(duplicate);
+        project
+            .sessions
+            .push(Session::manual(at(12, 9, 30), at(12, 11, 0)));
+        project.current_break = Some(Break::new(at(20, 10, 0)));
+
+        let repairs = repair(&mut project, now);
+        assert!(!repairs.is_empty());
+        assert!(project.current_break.is_none());
+
+        let session = &project.sessions[2];
+        let breaks: Vec<(DateTime<Local>, Option<DateTime<Local>>)> =
+            session.breaks.iter().map(|b| (b.start, b.end)).collect();
+        assert_eq!(
+            breaks,
+            [
+                (at(13, 9, 0), Some(at(13, 9, 30))),
+                (at(13, 12, 0), Some(at(13, 13, 0))),
+                (at(13, 13, 0), Some(at(13, 13, 30))),
+                (at(13, 16, 0), Some(at(13, 17, 0))),
+            ]
+        );
+        assert!(session.validate().is_ok());
+        assert_ne!(project.sessions[0].id, project.sessions[2].id);
+
+        // Only the overlap between the two sessions on the 12th needs a decision
+        let remaining = check(&project, now);
+        assert_eq!(remaining.len(), 1);
+        assert!(!remaining[0].fixable);
+        assert!(repair(&mut project, now).is_empty());
+    }
+}
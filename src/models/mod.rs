@@ -1,3 +1,6 @@
 pub mod r#break;
+pub mod commit;
+pub mod id;
+pub mod integrity;
 pub mod project;
 pub mod session;
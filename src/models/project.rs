@@ -1,9 +1,13 @@
+use crate::models::id;
 use crate::models::r#break::Break;
 use crate::models::session::Session;
 use anyhow::{anyhow, Result};
 use chrono::{DateTime, Duration, Local};
 use serde::{Deserialize, Serialize};
 
+/// Longest gap that `merge` turns into a break without `--force`
+pub const MAX_MERGE_GAP: Duration = Duration::hours(4);
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct Project {
     pub name: String,
@@ -82,6 +86,208 @@ impl Project {
         Ok(duration)
     }
 
+    /// Adds a completed session to the history, keeping `sessions` ordered by start time.
+    /// Fails if it overlaps any recorded session, including the active one.
+    pub fn add_session(&mut self, session: Session, now: DateTime<Local>) -> Result<()> {
+        session.validate()?;
+        let end = session
+            .end
+            .ok_or_else(|| anyhow!("Only completed sessions can be added"))?;
+
+        if let Some(existing) = self.find_overlap(session.start, end, now) {
+            return Err(anyhow!(
+                "Overlaps an existing session ({} – {})",
+                existing.start.format("%Y-%m-%d %H:%M"),
+                existing
+                    .end
+                    .map(|e| e.format("%H:%M").to_string())
+                    .unwrap_or_else(|| "now".to_string())
+            ));
+        }
+
+        let index = self
+            .sessions
+            .iter()
+            .position(|s| s.start > session.start)
+            .unwrap_or(self.sessions.len());
+        self.sessions.insert(index, session);
+        Ok(())
+    }
+
+    /// Swaps the finished session at `index` for an edited version, keeping the
+    /// history sorted. Leaves the project untouched if the edit is invalid.
+    pub fn replace_session(
+        &mut self,
+        index: usize,
+        session: Session,
+        now: DateTime<Local>,
+    ) -> Result<()> {
+        let original = self.sessions.remove(index);
+        if let Err(e) = self.add_session(session, now) {
+            self.sessions.insert(index, original);
+            return Err(e);
+        }
+        Ok(())
+    }
+
+    /// Replaces the finished session at `index` with its two halves
+    pub fn split_session(
+        &mut self,
+        index: usize,
+        at: DateTime<Local>,
+    ) -> Result<(Session, Session)> {
+        let (first, second) = self.sessions[index].split_at(at)?;
+        self.sessions[index] = first.clone();
+        self.sessions.insert(index + 1, second.clone());
+        Ok((first, second))
+    }
+
+    /// Merges two finished sessions that follow each other with no other
+    /// session in between. Sessions started on different days or more than
+    /// [`MAX_MERGE_GAP`] apart are only merged when `force` is set.
+    pub fn merge_sessions(&mut self, a: usize, b: usize, force: bool) -> Result<Session> {
+        if a == b {
+            return Err(anyhow!("Cannot merge a session with itself"));
+        }
+        let (first, second) = if self.sessions[a].start <= self.sessions[b].start {
+            (a, b)
+        } else {
+            (b, a)
+        };
+
+        if !force {
+            let (earlier, later) = (&self.sessions[first], &self.sessions[second]);
+            if earlier.start.date_naive() != later.start.date_naive() {
+                return Err(anyhow!(
+                    "The sessions started on different days ({} and {}). Pass --force to merge them anyway.",
+                    earlier.start.format("%Y-%m-%d"),
+                    later.start.format("%Y-%m-%d")
+                ));
+            }
+            let gap = earlier
+                .end
+                .map(|end| later.start.signed_duration_since(end))
+                .unwrap_or_else(Duration::zero);
+            if gap > MAX_MERGE_GAP {
+                return Err(anyhow!(
+                    "The sessions are {}h {}m apart, more than {}h. Pass --force to merge them anyway.",
+                    gap.num_hours(),
+                    gap.num_minutes() % 60,
+                    MAX_MERGE_GAP.num_hours()
+                ));
+            }
+        }
+
+        let (start, end) = (self.sessions[first].end, self.sessions[second].start);
+        if let Some(between) = start.and_then(|start| {
+            self.sessions
+                .iter()
+                .enumerate()
+                .find(|(i, s)| *i != first && *i != second && s.start >= start && s.start < end)
+        }) {
+            return Err(anyhow!(
+                "Session {} ({}) lies between them; merge it first",
+                between.1.short_id(),
+                between.1.start.format("%Y-%m-%d %H:%M")
+            ));
+        }
+
+        let merged = self.sessions[first].merge(&self.sessions[second])?;
+        self.sessions[first] = merged.clone();
+        self.sessions.remove(second);
+        Ok(merged)
+    }
+
+    /// Index of the finished session whose ID starts with `query`
+    pub fn find_session(&self, query: &str) -> Result<usize> {
+        id::find(self.sessions.iter().map(|s| &s.id), query, "session")
+    }
+
+    /// Gives sessions and breaks stored before IDs existed an ID derived from
+    /// their start time, so they keep it on every load until it is saved
+    pub fn backfill_ids(&mut self) {
+        let sessions = self
+            .sessions
+            .iter_mut()
+            .chain(self.current_session.as_mut());
+        for session in sessions {
+            let start = session.start.timestamp_micros();
+            if session.id.is_nil() {
+                session.id = id::derived(&format!("session/{}", start));
+            }
+            for break_period in session.breaks.iter_mut().filter(|b| b.id.is_nil()) {
+                break_period.id = id::derived(&format!(
+                    "session/{}/break/{}",
+                    start,
+                    break_period.start.timestamp_micros()
+                ));
+            }
+        }
+
+        if let Some(break_period) = self.current_break.as_mut().filter(|b| b.id.is_nil()) {
+            break_period.id =
+                id::derived(&format!("break/{}", break_period.start.timestamp_micros()));
+        }
+    }
+
+    /// Points a commit that git rewrote (`commit --amend`, `rebase`) at its
+    /// new hash. The original record keeps its session, time and branch; if
+    /// the post-commit hook recorded the new commit as well, that record is
+    /// dropped and its subject taken over. Returns false if the old commit
+    /// was never recorded or git reports it unchanged.
+    pub fn rewrite_commit(&mut self, old: &str, new: &str) -> bool {
+        let recorded = self
+            .sessions
+            .iter()
+            .chain(self.current_session.iter())
+            .any(|s| s.commits.iter().any(|c| c.hash == old));
+        if old == new || !recorded {
+            return false;
+        }
+
+        let mut subject = None;
+        for session in self
+            .sessions
+            .iter_mut()
+            .chain(self.current_session.as_mut())
+        {
+            if let Some(index) = session.commits.iter().position(|c| c.hash == new) {
+                subject = Some(session.commits.remove(index).subject);
+            }
+        }
+        for session in self
+            .sessions
+            .iter_mut()
+            .chain(self.current_session.as_mut())
+        {
+            for commit in session.commits.iter_mut().filter(|c| c.hash == old) {
+                commit.hash = new.to_string();
+                if let Some(ref subject) = subject {
+                    commit.subject = subject.clone();
+                }
+            }
+        }
+        true
+    }
+
+    /// Returns the first recorded session that overlaps `start..end`
+    pub fn find_overlap(
+        &self,
+        start: DateTime<Local>,
+        end: DateTime<Local>,
+        now: DateTime<Local>,
+    ) -> Option<&Session> {
+        self.sessions
+            .iter()
+            .chain(self.current_session.iter())
+            .find(|s| s.overlaps(start, end, now))
+    }
+
+    /// End time of the most recently finished session
+    pub fn last_session_end(&self) -> Option<DateTime<Local>> {
+        self.sessions.iter().filter_map(|s| s.end).max()
+    }
+
     pub fn is_on_break(&self) -> bool {
         self.current_break.is_some()
     }
@@ -104,6 +310,7 @@ impl Project {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::models::commit::Commit;
     use chrono::TimeZone;
 
     #[test]
@@ -150,4 +357,191 @@ mod tests {
         // 8 hours - 30 minutes = 7.5 hours
         assert_eq!(work_duration.num_minutes(), 8 * 60 - 30);
     }
+
+    #[test]
+    fn test_add_session_keeps_order() {
+        let mut project = Project::new("test".to_string());
+        let now = Local.with_ymd_and_hms(2025, 10, 14, 9, 0, 0).unwrap();
+        let at = |d, h| Local.with_ymd_and_hms(2025, 10, d, h, 0, 0).unwrap();
+
+        project
+            .add_session(Session::manual(at(13, 14), at(13, 16)), now)
+            .unwrap();
+        project
+            .add_session(Session::manual(at(13, 9), at(13, 12)), now)
+            .unwrap();
+
+        assert_eq!(project.sessions.len(), 2);
+        assert_eq!(project.sessions[0].start, at(13, 9));
+        assert_eq!(project.sessions[1].start, at(13, 14));
+    }
+
+    #[test]
+    fn test_add_session_rejects_overlap() {
+        let mut project = Project::new("test".to_string());
+        let now = Local.with_ymd_and_hms(2025, 10, 13, 18, 0, 0).unwrap();
+        let at = |h| Local.with_ymd_and_hms(2025, 10, 13, h, 0, 0).unwrap();
+
+        project
+            .add_session(Session::manual(at(9), at(12)), now)
+            .unwrap();
+        assert!(project
+            .add_session(Session::manual(at(11), at(13)), now)
+            .is_err());
+
+        // Active session counts as running until now
+        project.start_session(at(15));
+        assert!(project
+            .add_session(Session::manual(at(16), at(17)), now)
+            .is_err());
+        assert_eq!(project.sessions.len(), 1);
+    }
+
+    #[test]
+    fn test_replace_session() {
+        let mut project = Project::new("test".to_string());
+        let now = Local.with_ymd_and_hms(2025, 10, 14, 9, 0, 0).unwrap();
+        let at = |d, h| Local.with_ymd_and_hms(2025, 10, d, h, 0, 0).unwrap();
+        project
+            .add_session(Session::manual(at(12, 9), at(12, 12)), now)
+            .unwrap();
+        project
+            .add_session(Session::manual(at(13, 9), at(13, 12)), now)
+            .unwrap();
+
+        // Moving the first session after the second keeps the list sorted
+        project
+            .replace_session(0, Session::manual(at(13, 14), at(13, 16)), now)
+            .unwrap();
+        assert_eq!(project.sessions[0].start, at(13, 9));
+        assert_eq!(project.sessions[1].start, at(13, 14));
+
+        // An overlapping edit is rejected and nothing changes
+        assert!(project
+            .replace_session(1, Session::manual(at(13, 11), at(13, 16)), now)
+            .is_err());
+        assert_eq!(project.sessions[1].start, at(13, 14));
+        assert_eq!(project.sessions.len(), 2);
+    }
+
+    #[test]
+    fn test_backfill_ids_is_stable() {
+        let json = r#"{
+            "name": "test",
+            "current_session": null,
+            "sessions": [{
+                "start": "2025-10-13T09:00:00+00:00",
+                "end": "2025-10-13T17:00:00+00:00",
+                "breaks": [{"start": "2025-10-13T12:00:00+00:00", "end": "2025-10-13T12:30:00+00:00"}]
+            }]
+        }"#;
+        let load = || {
+            let mut project: Project = serde_json::from_str(json).unwrap();
+            project.backfill_ids();
+            project
+        };
+
+        let (first, second) = (load(), load());
+        let session = &first.sessions[0];
+        assert!(!session.id.is_nil() && !session.breaks[0].id.is_nil());
+        assert_eq!(session.id, second.sessions[0].id);
+        assert_eq!(session.breaks[0].id, second.sessions[0].breaks[0].id);
+
+        assert_eq!(first.find_session(&session.short_id()).unwrap(), 0);
+        assert_eq!(
+            session.find_break(&session.breaks[0].short_id()).unwrap(),
+            0
+        );
+        assert_eq!(session.find_break("1").unwrap(), 0);
+        assert!(session.find_break("2").is_err());
+    }
+
+    #[test]
+    fn test_backfill_keeps_existing_ids() {
+        let mut project = Project::new("test".to_string());
+        project.start_session(Local.with_ymd_and_hms(2025, 10, 13, 9, 0, 0).unwrap());
+        let id = project.current_session.as_ref().unwrap().id;
+
+        project.backfill_ids();
+        assert_eq!(project.current_session.unwrap().id, id);
+    }
+
+    #[test]
+    fn test_split_and_merge_sessions() {
+        let mut project = Project::new("test".to_string());
+        let at = |h| Local.with_ymd_and_hms(2025, 10, 13, h, 0, 0).unwrap();
+        project.sessions.push(Session::manual(at(8), at(9)));
+        project.sessions.push(Session::manual(at(10), at(17)));
+        project.sessions.push(Session::manual(at(18), at(19)));
+
+        project.split_session(1, at(13)).unwrap();
+        assert_eq!(project.sessions.len(), 4);
+        assert_eq!(project.sessions[2].start, at(13));
+
+        // Sessions 0 and 2 have session 1 between them
+        assert!(project.merge_sessions(0, 2, false).is_err());
+
+        let merged = project.merge_sessions(2, 1, false).unwrap();
+        assert_eq!((merged.start, merged.end), (at(10), Some(at(17))));
+        assert!(merged.breaks.is_empty());
+        assert_eq!(project.sessions.len(), 3);
+        assert_eq!(project.sessions[2].start, at(18));
+    }
+
+    #[test]
+    fn test_merge_sessions_far_apart_needs_force() {
+        let mut project = Project::new("test".to_string());
+        let at = |h| Local.with_ymd_and_hms(2025, 10, 13, h, 0, 0).unwrap();
+        project.sessions.push(Session::manual(at(7), at(8)));
+        project.sessions.push(Session::manual(at(13), at(14)));
+
+        let err = project.merge_sessions(0, 1, false).unwrap_err();
+        assert!(err.to_string().contains("5h 0m apart"));
+        assert_eq!(project.sessions.len(), 2);
+
+        let merged = project.merge_sessions(0, 1, true).unwrap();
+        assert_eq!((merged.start, merged.end), (at(7), Some(at(14))));
+        assert_eq!(merged.breaks.len(), 1);
+    }
+
+    #[test]
+    fn test_rewrite_commit() {
+        let mut project = Project::new("test".to_string());
+        let at = |h| Local.with_ymd_and_hms(2025, 10, 13, h, 0, 0).unwrap();
+        let commit = |hash: &str, subject: &str, branch: Option<&str>, h| Commit {
+            hash: hash.to_string(),
+            subject: subject.to_string(),
+            branch: branch.map(str::to_string),
+            at: at(h),
+        };
+        let mut morning = Session::manual(at(9), at(12));
+        morning.add_commit(commit("aaa", "Fix parser", Some("main"), 10));
+        morning.add_commit(commit("bbb", "Add tests", Some("main"), 11));
+        project.sessions.push(morning);
+        project.start_session(at(13));
+
+        // Rebased while clocked in: post-commit saw the new commit on a
+        // detached HEAD
+        project.current_session.as_mut().unwrap().add_commit(commit(
+            "ccc",
+            "Add more tests",
+            None,
+            14,
+        ));
+        assert!(project.rewrite_commit("bbb", "ccc"));
+        // Amended while clocked out: only the old hash is known
+        assert!(project.rewrite_commit("aaa", "ddd"));
+        // A forced rebase can recreate a commit with the same hash
+        assert!(!project.rewrite_commit("ddd", "ddd"));
+        assert!(!project.rewrite_commit("eee", "fff"));
+
+        assert_eq!(
+            project.sessions[0].commits,
+            vec![
+                commit("ddd", "Fix parser", Some("main"), 10),
+                commit("ccc", "Add more tests", Some("main"), 11)
+            ]
+        );
+        assert!(project.current_session.unwrap().commits.is_empty());
+    }
 }
@@ -1,21 +1,97 @@
+use crate::models::commit::{Checkout, Commit};
+use crate::models::id;
 use crate::models::r#break::Break;
+use anyhow::{anyhow, Result};
 use chrono::{DateTime, Duration, Local};
 use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+/// How a session was recorded
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum SessionOrigin {
+    /// Tracked live with start/stop
+    #[default]
+    Clocked,
+    /// Entered after the fact with `clock-me add`
+    Manual,
+    /// Converted from another time tracker with `clock-me import`
+    Imported,
+}
+
+impl SessionOrigin {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            SessionOrigin::Clocked => "clocked",
+            SessionOrigin::Manual => "manual",
+            SessionOrigin::Imported => "imported",
+        }
+    }
+}
+
+impl std::str::FromStr for SessionOrigin {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> anyhow::Result<Self> {
+        match s {
+            "clocked" => Ok(SessionOrigin::Clocked),
+            "manual" => Ok(SessionOrigin::Manual),
+            "imported" => Ok(SessionOrigin::Imported),
+            _ => Err(anyhow::anyhow!("Unknown session origin '{}'", s)),
+        }
+    }
+}
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct Session {
+    /// Nil for sessions stored before IDs existed, until backfilled on load
+    #[serde(default)]
+    pub id: Uuid,
     pub start: DateTime<Local>,
     pub end: Option<DateTime<Local>>,
     #[serde(default)]
     pub breaks: Vec<Break>,
+    #[serde(default)]
+    pub origin: SessionOrigin,
+    #[serde(default)]
+    pub note: Option<String>,
+    #[serde(default)]
+    pub tags: Vec<String>,
+    /// Commits recorded by the git hooks while the session ran
+    #[serde(default)]
+    pub commits: Vec<Commit>,
+    /// Branch switches recorded by the git hooks while the session ran
+    #[serde(default)]
+    pub checkouts: Vec<Checkout>,
 }
 
 impl Session {
     pub fn new(start: DateTime<Local>) -> Self {
         Self {
+            id: id::generate(),
             start,
             end: None,
             breaks: Vec::new(),
+            origin: SessionOrigin::Clocked,
+            note: None,
+            tags: Vec::new(),
+            commits: Vec::new(),
+            checkouts: Vec::new(),
+        }
+    }
+
+    /// Creates a completed session entered manually
+    pub fn manual(start: DateTime<Local>, end: DateTime<Local>) -> Self {
+        Self {
+            id: id::generate(),
+            start,
+            end: Some(end),
+            breaks: Vec::new(),
+            origin: SessionOrigin::Manual,
+            note: None,
+            tags: Vec::new(),
+            commits: Vec::new(),
+            checkouts: Vec::new(),
         }
     }
 
@@ -45,6 +121,225 @@ impl Session {
     pub fn add_break(&mut self, break_period: Break) {
         self.breaks.push(break_period);
     }
+
+    /// Creates a completed session converted from another time tracker
+    pub fn imported(start: DateTime<Local>, end: DateTime<Local>) -> Self {
+        Self {
+            origin: SessionOrigin::Imported,
+            ..Self::manual(start, end)
+        }
+    }
+
+    /// Adds text to the session note, separating it from any existing note
+    pub fn append_note(&mut self, text: &str) {
+        self.note = match self.note.take() {
+            Some(existing) => Some(format!("{}; {}", existing, text)),
+            None => Some(text.to_string()),
+        };
+    }
+
+    /// Adds tags that are not already present, keeping their order
+    pub fn add_tags(&mut self, tags: &[String]) {
+        for tag in tags {
+            if !self.tags.contains(tag) {
+                self.tags.push(tag.clone());
+            }
+        }
+    }
+
+    pub fn remove_tags(&mut self, tags: &[String]) {
+        self.tags.retain(|t| !tags.contains(t));
+    }
+
+    pub fn is_manual(&self) -> bool {
+        self.origin == SessionOrigin::Manual
+    }
+
+    /// Returns true if this session shares any time with the `start..end` range.
+    /// An active session is treated as running until `now`.
+    pub fn overlaps(
+        &self,
+        start: DateTime<Local>,
+        end: DateTime<Local>,
+        now: DateTime<Local>,
+    ) -> bool {
+        let session_end = self.end.unwrap_or(now);
+        self.start < end && start < session_end
+    }
+
+    pub fn short_id(&self) -> String {
+        id::short(&self.id)
+    }
+
+    /// Cuts a finished session in two at `at`. The first half keeps this
+    /// session's ID; note, tags and origin are copied to both halves, a
+    /// break running across `at` is divided between them and commits and
+    /// branch switches go to the half they were made in.
+    pub fn split_at(&self, at: DateTime<Local>) -> Result<(Session, Session)> {
+        let end = self
+            .end
+            .ok_or_else(|| anyhow!("Only finished sessions can be split"))?;
+        if at <= self.start || at >= end {
+            return Err(anyhow!(
+                "Split time {} is not inside the session ({} – {})",
+                at.format("%Y-%m-%d %H:%M"),
+                self.start.format("%Y-%m-%d %H:%M"),
+                end.format("%H:%M")
+            ));
+        }
+
+        let mut first = self.clone();
+        first.end = Some(at);
+        first.breaks.clear();
+        first.commits.retain(|c| c.at < at);
+        first.checkouts.retain(|c| c.at < at);
+
+        let mut second = self.clone();
+        second.id = id::generate();
+        second.start = at;
+        second.breaks.clear();
+        second.commits.retain(|c| c.at >= at);
+        second.checkouts.retain(|c| c.at >= at);
+
+        for break_period in &self.breaks {
+            let break_end = break_period.end.unwrap_or(end);
+            if break_end <= at {
+                first.breaks.push(break_period.clone());
+            } else if break_period.start >= at {
+                second.breaks.push(break_period.clone());
+            } else {
+                let mut before = break_period.clone();
+                before.end = Some(at);
+                first.breaks.push(before);
+
+                let mut after = break_period.clone();
+                after.id = id::generate();
+                after.start = at;
+                second.breaks.push(after);
+            }
+        }
+
+        Ok((first, second))
+    }
+
+    /// Joins two finished sessions into one, turning the time between them
+    /// into a break. The result keeps the earlier session's ID and origin;
+    /// notes are joined and tags, commits and branch switches combined.
+    pub fn merge(&self, other: &Session) -> Result<Session> {
+        let (first, second) = if self.start <= other.start {
+            (self, other)
+        } else {
+            (other, self)
+        };
+        let (Some(first_end), Some(second_end)) = (first.end, second.end) else {
+            return Err(anyhow!("Only finished sessions can be merged"));
+        };
+        if first_end > second.start {
+            return Err(anyhow!("Sessions overlap and cannot be merged"));
+        }
+
+        let mut merged = first.clone();
+        merged.end = Some(second_end);
+        if first_end < second.start {
+            let mut gap = Break::new(first_end);
+            gap.finish(second.start);
+            merged.add_break(gap);
+        }
+        merged.breaks.extend(second.breaks.iter().cloned());
+        if let Some(ref note) = second.note {
+            if first.note.as_ref() != Some(note) {
+                merged.append_note(note);
+            }
+        }
+        merged.add_tags(&second.tags);
+        merged.commits.extend(second.commits.iter().cloned());
+        merged.checkouts.extend(second.checkouts.iter().cloned());
+
+        Ok(merged)
+    }
+
+    /// Attaches a commit unless it was already recorded, as the hooks may run
+    /// more than once for the same commit. Returns false for a duplicate.
+    pub fn add_commit(&mut self, commit: Commit) -> bool {
+        if self.commits.iter().any(|c| c.hash == commit.hash) {
+            return false;
+        }
+        self.commits.push(commit);
+        true
+    }
+
+    /// Records a branch switch unless the session is already on that branch.
+    /// Returns false if nothing changed.
+    pub fn add_checkout(&mut self, checkout: Checkout) -> bool {
+        if self.checkouts.last().map(|c| &c.branch) == Some(&checkout.branch) {
+            return false;
+        }
+        self.checkouts.push(checkout);
+        true
+    }
+
+    /// Index of a break given by its number (from 1) or an ID prefix.
+    /// Anything shorter than an ID prefix is read as a number.
+    pub fn find_break(&self, query: &str) -> Result<usize> {
+        if query.len() < id::MIN_PREFIX_LEN {
+            if let Ok(number) = query.parse::<usize>() {
+                if number == 0 || number > self.breaks.len() {
+                    return Err(anyhow!(
+                        "Session has {} breaks, there is no break {}",
+                        self.breaks.len(),
+                        number
+                    ));
+                }
+                return Ok(number - 1);
+            }
+        }
+        id::find(self.breaks.iter().map(|b| &b.id), query, "break")
+    }
+
+    /// Checks that a finished session is consistent: it ends after it starts
+    /// and its breaks are finished, lie within it and don't overlap.
+    /// Breaks are numbered from 1 in the messages.
+    pub fn validate(&self) -> Result<()> {
+        let end = self
+            .end
+            .ok_or_else(|| anyhow!("Only completed sessions can be added"))?;
+        if end <= self.start {
+            return Err(anyhow!("Session end must be after its start"));
+        }
+
+        let mut previous: Option<(usize, &Break)> = None;
+        let mut breaks: Vec<(usize, &Break)> = self.breaks.iter().enumerate().collect();
+        breaks.sort_by_key(|(_, b)| b.start);
+
+        for (index, break_period) in breaks {
+            let number = index + 1;
+            let break_end = break_period
+                .end
+                .ok_or_else(|| anyhow!("Break {} has no end", number))?;
+
+            if break_end <= break_period.start {
+                return Err(anyhow!("Break {} must end after it starts", number));
+            }
+            if break_period.start < self.start || break_end > end {
+                return Err(anyhow!(
+                    "Break {} ({}–{}) is outside the session ({}–{})",
+                    number,
+                    break_period.start.format("%H:%M"),
+                    break_end.format("%H:%M"),
+                    self.start.format("%H:%M"),
+                    end.format("%H:%M")
+                ));
+            }
+            if let Some((other, earlier)) = previous {
+                if earlier.end.is_some_and(|e| e > break_period.start) {
+                    return Err(anyhow!("Breaks {} and {} overlap", other + 1, number));
+                }
+            }
+            previous = Some((index, break_period));
+        }
+
+        Ok(())
+    }
 }
 
 #[cfg(test)]
@@ -96,4 +391,202 @@ mod tests {
         assert_eq!(session.total_break_time().num_minutes(), 30);
         assert_eq!(session.work_time().unwrap().num_minutes(), 8 * 60 - 30);
     }
+
+    #[test]
+    fn test_append_note() {
+        let start = Local.with_ymd_and_hms(2025, 10, 13, 9, 0, 0).unwrap();
+        let mut session = Session::new(start);
+
+        session.append_note("feature X");
+        assert_eq!(session.note.as_deref(), Some("feature X"));
+
+        session.append_note("done");
+        assert_eq!(session.note.as_deref(), Some("feature X; done"));
+    }
+
+    #[test]
+    fn test_tags() {
+        let start = Local.with_ymd_and_hms(2025, 10, 13, 9, 0, 0).unwrap();
+        let mut session = Session::new(start);
+
+        session.add_tags(&["review".to_string(), "backend".to_string()]);
+        session.add_tags(&["review".to_string(), "meeting".to_string()]);
+        assert_eq!(session.tags, vec!["review", "backend", "meeting"]);
+
+        session.remove_tags(&["backend".to_string()]);
+        assert_eq!(session.tags, vec!["review", "meeting"]);
+    }
+
+    #[test]
+    fn test_manual_session() {
+        let start = Local.with_ymd_and_hms(2025, 10, 13, 9, 0, 0).unwrap();
+        let end = Local.with_ymd_and_hms(2025, 10, 13, 11, 30, 0).unwrap();
+
+        let session = Session::manual(start, end);
+
+        assert!(session.is_manual());
+        assert!(!session.is_active());
+        assert_eq!(session.work_time().unwrap().num_minutes(), 150);
+    }
+
+    #[test]
+    fn test_overlaps() {
+        let start = Local.with_ymd_and_hms(2025, 10, 13, 9, 0, 0).unwrap();
+        let end = Local.with_ymd_and_hms(2025, 10, 13, 12, 0, 0).unwrap();
+        let session = Session::manual(start, end);
+        let now = Local.with_ymd_and_hms(2025, 10, 13, 18, 0, 0).unwrap();
+
+        let at = |h, m| Local.with_ymd_and_hms(2025, 10, 13, h, m, 0).unwrap();
+
+        assert!(session.overlaps(at(11, 0), at(13, 0), now));
+        assert!(session.overlaps(at(8, 0), at(9, 30), now));
+        assert!(session.overlaps(at(10, 0), at(11, 0), now));
+        // Touching boundaries is not an overlap
+        assert!(!session.overlaps(at(12, 0), at(13, 0), now));
+        assert!(!session.overlaps(at(8, 0), at(9, 0), now));
+    }
+
+    #[test]
+    fn test_old_session_defaults_to_clocked() {
+        let json = r#"{"start":"2025-10-13T09:00:00+00:00","end":null}"#;
+        let session: Session = serde_json::from_str(json).unwrap();
+
+        assert_eq!(session.origin, SessionOrigin::Clocked);
+        assert!(session.note.is_none());
+        assert!(session.tags.is_empty());
+        assert!(session.breaks.is_empty());
+    }
+
+    #[test]
+    fn test_validate() {
+        let at = |h, m| Local.with_ymd_and_hms(2025, 10, 13, h, m, 0).unwrap();
+        let finished_break = |start, end| {
+            let mut break_period = Break::new(start);
+            break_period.finish(end);
+            break_period
+        };
+
+        let mut session = Session::manual(at(9, 0), at(17, 0));
+        session.add_break(finished_break(at(12, 0), at(12, 30)));
+        session.add_break(finished_break(at(15, 0), at(15, 10)));
+        assert!(session.validate().is_ok());
+
+        let mut reversed = session.clone();
+        reversed.end = Some(at(8, 0));
+        assert!(reversed.validate().is_err());
+
+        let mut outside = session.clone();
+        outside.breaks[1] = finished_break(at(16, 50), at(17, 10));
+        let err = outside.validate().unwrap_err().to_string();
+        assert!(err.contains("Break 2"), "{}", err);
+
+        let mut overlapping = session.clone();
+        overlapping.breaks[1] = finished_break(at(12, 15), at(12, 45));
+        assert_eq!(
+            overlapping.validate().unwrap_err().to_string(),
+            "Breaks 1 and 2 overlap"
+        );
+
+        let mut open = session.clone();
+        open.breaks[0].end = None;
+        assert!(open.validate().is_err());
+    }
+
+    #[test]
+    fn test_split_divides_breaks() {
+        let at = |h, m| Local.with_ymd_and_hms(2025, 10, 13, h, m, 0).unwrap();
+        let mut session = Session::manual(at(9, 0), at(17, 0));
+        for (start, end) in [
+            (at(10, 0), at(10, 15)),
+            (at(12, 30), at(13, 30)),
+            (at(15, 0), at(15, 10)),
+        ] {
+            let mut break_period = Break::new(start);
+            break_period.finish(end);
+            session.add_break(break_period);
+        }
+        session.tags = vec!["backend".to_string()];
+
+        let (first, second) = session.split_at(at(13, 0)).unwrap();
+
+        assert_eq!(first.id, session.id);
+        assert_ne!(second.id, session.id);
+        assert_eq!((first.start, first.end), (at(9, 0), Some(at(13, 0))));
+        assert_eq!((second.start, second.end), (at(13, 0), Some(at(17, 0))));
+        assert_eq!(first.total_break_time(), Duration::minutes(45));
+        assert_eq!(second.total_break_time(), Duration::minutes(40));
+        assert_ne!(first.breaks[1].id, second.breaks[0].id);
+        assert_eq!(second.tags, session.tags);
+        assert_eq!(
+            first.work_time().unwrap() + second.work_time().unwrap(),
+            session.work_time().unwrap()
+        );
+        assert!(first.validate().is_ok() && second.validate().is_ok());
+
+        assert!(session.split_at(at(9, 0)).is_err());
+        assert!(session.split_at(at(18, 0)).is_err());
+    }
+
+    #[test]
+    fn test_merge_turns_gap_into_break() {
+        let at = |h, m| Local.with_ymd_and_hms(2025, 10, 13, h, m, 0).unwrap();
+        let mut morning = Session::manual(at(9, 0), at(12, 0));
+        morning.note = Some("API".to_string());
+        let mut afternoon = Session::manual(at(12, 20), at(15, 0));
+        afternoon.note = Some("review".to_string());
+        afternoon.tags = vec!["review".to_string()];
+
+        let merged = afternoon.merge(&morning).unwrap();
+
+        assert_eq!(merged.id, morning.id);
+        assert_eq!((merged.start, merged.end), (at(9, 0), Some(at(15, 0))));
+        assert_eq!(merged.breaks.len(), 1);
+        assert_eq!(merged.total_break_time(), Duration::minutes(20));
+        assert_eq!(merged.note.as_deref(), Some("API; review"));
+        assert_eq!(merged.tags, vec!["review"]);
+
+        let overlapping = Session::manual(at(11, 0), at(13, 0));
+        assert!(morning.merge(&overlapping).is_err());
+    }
+
+    #[test]
+    fn test_commits_follow_split_and_merge() {
+        let at = |h, m| Local.with_ymd_and_hms(2025, 10, 13, h, m, 0).unwrap();
+        let commit = |hash: &str, at| Commit {
+            hash: hash.to_string(),
+            subject: format!("Commit {}", hash),
+            branch: Some("main".to_string()),
+            at,
+        };
+        let mut session = Session::manual(at(9, 0), at(17, 0));
+        assert!(session.add_commit(commit("aaa", at(10, 0))));
+        assert!(session.add_commit(commit("bbb", at(14, 0))));
+        assert!(!session.add_commit(commit("aaa", at(10, 5))));
+        assert_eq!(session.commits.len(), 2);
+
+        let (first, second) = session.split_at(at(13, 0)).unwrap();
+        assert_eq!(first.commits, vec![commit("aaa", at(10, 0))]);
+        assert_eq!(second.commits, vec![commit("bbb", at(14, 0))]);
+
+        let merged = first.merge(&second).unwrap();
+        assert_eq!(merged.commits, session.commits);
+    }
+
+    #[test]
+    fn test_checkouts_skip_the_current_branch() {
+        let at = |h, m| Local.with_ymd_and_hms(2025, 10, 13, h, m, 0).unwrap();
+        let checkout = |branch: &str, at| Checkout {
+            branch: branch.to_string(),
+            at,
+        };
+        let mut session = Session::manual(at(9, 0), at(17, 0));
+        assert!(session.add_checkout(checkout("feature", at(10, 0))));
+        assert!(!session.add_checkout(checkout("feature", at(10, 5))));
+        assert!(session.add_checkout(checkout("main", at(14, 0))));
+
+        let (first, second) = session.split_at(at(13, 0)).unwrap();
+        assert_eq!(first.checkouts, vec![checkout("feature", at(10, 0))]);
+        assert_eq!(second.checkouts, vec![checkout("main", at(14, 0))]);
+        assert_eq!(first.merge(&second).unwrap().checkouts, session.checkouts);
+    }
 }
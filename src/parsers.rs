@@ -1,5 +1,5 @@
 use anyhow::{anyhow, Result};
-use chrono::Duration;
+use chrono::{DateTime, Duration, Local, NaiveDate, NaiveDateTime, NaiveTime};
 use regex::Regex;
 
 pub struct DurationParser;
@@ -78,6 +78,81 @@ impl DurationParser {
     }
 }
 
+pub struct DateParser;
+
+impl DateParser {
+    /// Parse a calendar date in "YYYY-MM-DD" format, e.g. "2025-10-13"
+    pub fn parse(input: &str) -> Result<NaiveDate> {
+        NaiveDate::parse_from_str(input.trim(), "%Y-%m-%d")
+            .map_err(|_| anyhow!("Invalid date '{}'. Use format YYYY-MM-DD", input.trim()))
+    }
+}
+
+pub struct TimeParser;
+
+impl TimeParser {
+    /// Parse a time of day in 24-hour "HH:MM" format, e.g. "09:15" or "9:15"
+    pub fn parse(input: &str) -> Result<NaiveTime> {
+        NaiveTime::parse_from_str(input.trim(), "%H:%M")
+            .map_err(|_| anyhow!("Invalid time '{}'. Use 24-hour format HH:MM", input.trim()))
+    }
+}
+
+/// A user-supplied point in time for `--at` / `--ago`, resolved against the current time
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TimeSpec {
+    /// "09:15" - today at the given time
+    TimeOfDay(NaiveTime),
+    /// "2025-10-13 17:40" - an explicit date and time
+    DateTime(NaiveDateTime),
+    /// "20m" - this long before now
+    Ago(Duration),
+}
+
+impl TimeSpec {
+    /// Parse an `--at` value: either "HH:MM" or "YYYY-MM-DD HH:MM"
+    pub fn parse_at(input: &str) -> Result<Self> {
+        let input = input.trim();
+
+        if let Ok(datetime) = NaiveDateTime::parse_from_str(input, "%Y-%m-%d %H:%M") {
+            return Ok(TimeSpec::DateTime(datetime));
+        }
+
+        TimeParser::parse(input)
+            .map(TimeSpec::TimeOfDay)
+            .map_err(|_| {
+                anyhow!(
+                    "Invalid time '{}'. Use HH:MM or \"YYYY-MM-DD HH:MM\"",
+                    input
+                )
+            })
+    }
+
+    /// Parse an `--ago` value using the duration format, e.g. "20m" or "1h 15m"
+    pub fn parse_ago(input: &str) -> Result<Self> {
+        DurationParser::parse(input).map(TimeSpec::Ago)
+    }
+
+    /// Turns the spec into a concrete local timestamp relative to `now`
+    pub fn resolve(&self, now: DateTime<Local>) -> Result<DateTime<Local>> {
+        self.resolve_on(now.date_naive(), now)
+    }
+
+    /// Like [`TimeSpec::resolve`], but a bare time of day falls on `date`
+    pub fn resolve_on(&self, date: NaiveDate, now: DateTime<Local>) -> Result<DateTime<Local>> {
+        let naive = match self {
+            TimeSpec::Ago(duration) => return Ok(now - *duration),
+            TimeSpec::TimeOfDay(time) => date.and_time(*time),
+            TimeSpec::DateTime(datetime) => *datetime,
+        };
+
+        naive
+            .and_local_timezone(Local)
+            .earliest()
+            .ok_or_else(|| anyhow!("{} does not exist in the local timezone", naive))
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -177,4 +252,104 @@ mod tests {
         let duration = Duration::minutes(75);
         assert_eq!(DurationParser::format(duration), "1h 15m");
     }
+
+    #[test]
+    fn test_parse_date() {
+        let date = DateParser::parse("2025-10-13").unwrap();
+        assert_eq!(date, NaiveDate::from_ymd_opt(2025, 10, 13).unwrap());
+
+        let date = DateParser::parse(" 2025-01-05 ").unwrap();
+        assert_eq!(date, NaiveDate::from_ymd_opt(2025, 1, 5).unwrap());
+    }
+
+    #[test]
+    fn test_parse_invalid_date() {
+        assert!(DateParser::parse("").is_err());
+        assert!(DateParser::parse("13.10.2025").is_err());
+        assert!(DateParser::parse("2025-13-01").is_err());
+        assert!(DateParser::parse("2025-02-30").is_err());
+    }
+
+    #[test]
+    fn test_parse_time() {
+        let time = TimeParser::parse("09:15").unwrap();
+        assert_eq!(time, NaiveTime::from_hms_opt(9, 15, 0).unwrap());
+
+        let time = TimeParser::parse("9:05").unwrap();
+        assert_eq!(time, NaiveTime::from_hms_opt(9, 5, 0).unwrap());
+
+        let time = TimeParser::parse("23:59").unwrap();
+        assert_eq!(time, NaiveTime::from_hms_opt(23, 59, 0).unwrap());
+    }
+
+    #[test]
+    fn test_parse_invalid_time() {
+        assert!(TimeParser::parse("").is_err());
+        assert!(TimeParser::parse("24:00").is_err());
+        assert!(TimeParser::parse("9am").is_err());
+        assert!(TimeParser::parse("12:60").is_err());
+    }
+
+    #[test]
+    fn test_time_spec_parse_at() {
+        assert_eq!(
+            TimeSpec::parse_at("09:15").unwrap(),
+            TimeSpec::TimeOfDay(NaiveTime::from_hms_opt(9, 15, 0).unwrap())
+        );
+
+        let expected = NaiveDate::from_ymd_opt(2025, 10, 13)
+            .unwrap()
+            .and_hms_opt(17, 40, 0)
+            .unwrap();
+        assert_eq!(
+            TimeSpec::parse_at("2025-10-13 17:40").unwrap(),
+            TimeSpec::DateTime(expected)
+        );
+
+        assert!(TimeSpec::parse_at("yesterday").is_err());
+        assert!(TimeSpec::parse_at("2025-10-13").is_err());
+    }
+
+    #[test]
+    fn test_time_spec_resolve() {
+        use chrono::TimeZone;
+        let now = Local.with_ymd_and_hms(2025, 10, 13, 12, 0, 0).unwrap();
+
+        let spec = TimeSpec::parse_ago("20m").unwrap();
+        assert_eq!(
+            spec.resolve(now).unwrap(),
+            Local.with_ymd_and_hms(2025, 10, 13, 11, 40, 0).unwrap()
+        );
+
+        let spec = TimeSpec::parse_at("09:15").unwrap();
+        assert_eq!(
+            spec.resolve(now).unwrap(),
+            Local.with_ymd_and_hms(2025, 10, 13, 9, 15, 0).unwrap()
+        );
+
+        let spec = TimeSpec::parse_at("2025-10-12 17:40").unwrap();
+        assert_eq!(
+            spec.resolve(now).unwrap(),
+            Local.with_ymd_and_hms(2025, 10, 12, 17, 40, 0).unwrap()
+        );
+    }
+
+    #[test]
+    fn test_time_spec_resolve_on_date() {
+        use chrono::TimeZone;
+        let now = Local.with_ymd_and_hms(2025, 10, 13, 12, 0, 0).unwrap();
+        let date = NaiveDate::from_ymd_opt(2025, 10, 10).unwrap();
+
+        let spec = TimeSpec::parse_at("09:15").unwrap();
+        assert_eq!(
+            spec.resolve_on(date, now).unwrap(),
+            Local.with_ymd_and_hms(2025, 10, 10, 9, 15, 0).unwrap()
+        );
+
+        let spec = TimeSpec::parse_at("2025-10-11 08:00").unwrap();
+        assert_eq!(
+            spec.resolve_on(date, now).unwrap(),
+            Local.with_ymd_and_hms(2025, 10, 11, 8, 0, 0).unwrap()
+        );
+    }
 }
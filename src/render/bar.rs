@@ -0,0 +1,202 @@
+use crate::parsers::DurationParser;
+use crate::render::{prompt, State};
+use crate::session_service::StatusInfo;
+use anyhow::{anyhow, Result};
+use chrono::Duration;
+use serde_json::{json, Value};
+
+/// The JSON dialect of the status bar
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum BarFormat {
+    /// Waybar `custom` modules with `"return-type": "json"`
+    Waybar,
+    /// i3blocks blocks with `format=json`
+    I3blocks,
+}
+
+impl std::str::FromStr for BarFormat {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self> {
+        match s.trim().to_lowercase().as_str() {
+            "waybar" => Ok(BarFormat::Waybar),
+            "i3blocks" => Ok(BarFormat::I3blocks),
+            _ => Err(anyhow!(
+                "Unsupported bar format '{}'. Use 'waybar' or 'i3blocks'",
+                s
+            )),
+        }
+    }
+}
+
+/// One update of a status bar module
+pub struct BarStatus {
+    pub text: String,
+    pub tooltip: String,
+    pub state: State,
+    /// Today's work time against the daily goal, capped at 100
+    pub percentage: u32,
+}
+
+impl BarStatus {
+    /// `text` is a [`prompt`] template
+    pub fn new(status: &StatusInfo, goal: Duration, text: &str) -> Self {
+        let percentage = if goal > Duration::zero() {
+            let ratio = status.today_work_time.num_seconds() as f64 / goal.num_seconds() as f64;
+            (ratio * 100.0).clamp(0.0, 100.0) as u32
+        } else {
+            100
+        };
+
+        Self {
+            text: prompt::render(text, status),
+            tooltip: Self::tooltip(status, goal, percentage),
+            state: State::of(status),
+            percentage,
+        }
+    }
+
+    fn tooltip(status: &StatusInfo, goal: Duration, percentage: u32) -> String {
+        let state = match State::of(status) {
+            State::Working => "working",
+            State::OnBreak => "on a break",
+            State::ClockedOut => "clocked out",
+        };
+        let mut lines = vec![
+            format!("{}: {}", status.project_name, state),
+            format!(
+                "Today: {} of {} ({}%)",
+                DurationParser::format(status.today_work_time),
+                DurationParser::format(goal),
+                percentage
+            ),
+        ];
+
+        if let Some(ref session) = status.last_session {
+            let mut line = format!("Last session: {}", session.start.format("%a %H:%M"));
+            if let Some(end) = session.end {
+                line.push_str(&end.format("–%H:%M").to_string());
+            }
+            if let Some(work_time) = session.work_time() {
+                line.push_str(&format!(" ({})", DurationParser::format(work_time)));
+            }
+            lines.push(line);
+            if let Some(ref note) = session.note {
+                lines.push(format!("  {}", note));
+            }
+        }
+        lines.join("\n")
+    }
+
+    /// CSS class for waybar, also used as its `alt` for per-state icons
+    pub fn class(&self) -> &'static str {
+        match self.state {
+            State::Working => "working",
+            State::OnBreak => "on-break",
+            State::ClockedOut => "clocked-out",
+        }
+    }
+
+    fn color(&self) -> Option<&'static str> {
+        match self.state {
+            State::Working => Some("#a6e22e"),
+            State::OnBreak => Some("#e6db74"),
+            State::ClockedOut => None,
+        }
+    }
+
+    /// A single line, as bars read one JSON object per line
+    pub fn render(&self, format: BarFormat) -> String {
+        let value = match format {
+            BarFormat::Waybar => json!({
+                "text": self.text,
+                "alt": self.class(),
+                "tooltip": self.tooltip,
+                "class": self.class(),
+                "percentage": self.percentage,
+            }),
+            BarFormat::I3blocks => {
+                let mut block = json!({
+                    "full_text": self.text,
+                    "short_text": self.text,
+                });
+                if let Some(color) = self.color() {
+                    block["color"] = Value::from(color);
+                }
+                block
+            }
+        };
+        value.to_string()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::project::Project;
+    use crate::models::session::Session;
+    use crate::session_service::SessionService;
+    use chrono::{DateTime, Local, TimeZone};
+
+    fn at(day: u32, hour: u32, minute: u32) -> DateTime<Local> {
+        Local
+            .with_ymd_and_hms(2026, 10, day, hour, minute, 0)
+            .unwrap()
+    }
+
+    fn project() -> Project {
+        let mut project = Project::new("my-app".to_string());
+        let mut yesterday = Session::new(at(13, 9, 0));
+        yesterday.finish(at(13, 11, 30));
+        yesterday.note = Some("Code review".to_string());
+        project.sessions.push(yesterday);
+        project.start_session(at(14, 9, 0));
+        project
+    }
+
+    #[test]
+    fn test_waybar() {
+        let status = SessionService::status_at(&project(), at(14, 11, 0));
+        let bar = BarStatus::new(&status, Duration::hours(8), prompt::DEFAULT_FORMAT);
+
+        let value: Value = serde_json::from_str(&bar.render(BarFormat::Waybar)).unwrap();
+        assert_eq!(value["text"], "my-app ▶ 2h");
+        assert_eq!(value["class"], "working");
+        assert_eq!(value["alt"], "working");
+        assert_eq!(value["percentage"], 25);
+        assert_eq!(
+            value["tooltip"],
+            "my-app: working\nToday: 2h of 8h (25%)\nLast session: Tue 09:00–11:30 (2h 30m)\n  Code review"
+        );
+    }
+
+    #[test]
+    fn test_i3blocks_on_break() {
+        let mut project = project();
+        project.start_break(at(14, 12, 0)).unwrap();
+        let status = SessionService::status_at(&project, at(14, 12, 10));
+        let bar = BarStatus::new(&status, Duration::hours(8), "{icon} {elapsed}");
+
+        assert_eq!(bar.class(), "on-break");
+        let line = bar.render(BarFormat::I3blocks);
+        assert!(!line.contains('\n'));
+        let value: Value = serde_json::from_str(&line).unwrap();
+        assert_eq!(value["full_text"], "☕ 10m");
+        assert_eq!(value["color"], "#e6db74");
+    }
+
+    #[test]
+    fn test_percentage_is_capped() {
+        let mut project = project();
+        project.end_session(at(14, 19, 0)).unwrap();
+        let status = SessionService::status_at(&project, at(14, 20, 0));
+
+        let bar = BarStatus::new(&status, Duration::hours(8), prompt::DEFAULT_FORMAT);
+        assert_eq!(bar.class(), "clocked-out");
+        assert_eq!(bar.percentage, 100);
+
+        let value: Value = serde_json::from_str(&bar.render(BarFormat::I3blocks)).unwrap();
+        assert_eq!(value["full_text"], "my-app");
+        assert!(value.get("color").is_none());
+    }
+}
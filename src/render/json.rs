@@ -0,0 +1,510 @@
+//! The JSON documents printed by `--output json`. Field names and units are
+//! part of the interface: durations are whole seconds, times are RFC 3339.
+
+use crate::models::commit::{Checkout, Commit};
+use crate::models::r#break::Break;
+use crate::models::session::{Session, SessionOrigin};
+use crate::render::{Renderer, ReportOptions, State};
+use crate::session_service::{
+    BreakStarted, ClockIn, ClockOut, Report, ReportPeriod, StatusInfo, TimeStats,
+};
+use anyhow::Result;
+use chrono::{DateTime, Duration, Local, NaiveDate};
+use serde::{Serialize, Serializer};
+use uuid::Uuid;
+
+fn seconds<S: Serializer>(duration: &Duration, serializer: S) -> Result<S::Ok, S::Error> {
+    serializer.serialize_i64(duration.num_seconds())
+}
+
+fn period_name<S: Serializer>(period: &ReportPeriod, serializer: S) -> Result<S::Ok, S::Error> {
+    serializer.serialize_str(match period {
+        ReportPeriod::Day => "day",
+        ReportPeriod::Week => "week",
+        ReportPeriod::Month => "month",
+    })
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct BreakView {
+    pub id: Uuid,
+    pub start: DateTime<Local>,
+    pub end: Option<DateTime<Local>>,
+    /// Length so far for a running break
+    #[serde(rename = "duration_seconds", serialize_with = "seconds")]
+    pub duration: Duration,
+    pub note: Option<String>,
+}
+
+impl BreakView {
+    /// A running break is measured up to `now`
+    pub fn new(break_period: &Break, now: DateTime<Local>) -> Self {
+        Self {
+            id: break_period.id,
+            start: break_period.start,
+            end: break_period.end,
+            duration: break_period.end.unwrap_or(now) - break_period.start,
+            note: break_period.note.clone(),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct SessionView {
+    pub id: Uuid,
+    pub start: DateTime<Local>,
+    pub end: Option<DateTime<Local>>,
+    /// Work time so far for the active session
+    #[serde(rename = "work_seconds", serialize_with = "seconds")]
+    pub work_time: Duration,
+    /// Finished breaks only; a running break is reported separately
+    #[serde(rename = "break_seconds", serialize_with = "seconds")]
+    pub break_time: Duration,
+    pub breaks: Vec<BreakView>,
+    pub note: Option<String>,
+    pub tags: Vec<String>,
+    pub origin: SessionOrigin,
+    pub commits: Vec<Commit>,
+    pub checkouts: Vec<Checkout>,
+}
+
+impl SessionView {
+    /// An active session is counted as working until `until`: now, or the
+    /// start of the running break
+    pub fn new(session: &Session, until: DateTime<Local>) -> Self {
+        let break_time = session.total_break_time();
+        Self {
+            id: session.id,
+            start: session.start,
+            end: session.end,
+            work_time: session.end.unwrap_or(until) - session.start - break_time,
+            break_time,
+            breaks: session
+                .breaks
+                .iter()
+                .map(|b| BreakView::new(b, until))
+                .collect(),
+            note: session.note.clone(),
+            tags: session.tags.clone(),
+            origin: session.origin,
+            commits: session.commits.clone(),
+            checkouts: session.checkouts.clone(),
+        }
+    }
+}
+
+/// Work and break totals, as in `status`
+#[derive(Debug, Clone, Serialize)]
+pub struct Totals {
+    #[serde(rename = "work_seconds", serialize_with = "seconds")]
+    pub work_time: Duration,
+    #[serde(rename = "break_seconds", serialize_with = "seconds")]
+    pub break_time: Duration,
+    pub sessions: usize,
+    pub breaks: usize,
+}
+
+/// Totals of a report row, with the number of manual entries
+#[derive(Debug, Clone, Serialize)]
+pub struct StatsView {
+    #[serde(rename = "work_seconds", serialize_with = "seconds")]
+    pub work_time: Duration,
+    #[serde(rename = "break_seconds", serialize_with = "seconds")]
+    pub break_time: Duration,
+    pub sessions: usize,
+    pub manual_sessions: usize,
+    pub breaks: usize,
+}
+
+impl From<&TimeStats> for StatsView {
+    fn from(stats: &TimeStats) -> Self {
+        Self {
+            work_time: stats.work_time,
+            break_time: stats.break_time,
+            sessions: stats.sessions,
+            manual_sessions: stats.manual_sessions,
+            breaks: stats.breaks,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct BucketView {
+    /// First day of the bucket
+    pub start: NaiveDate,
+    #[serde(flatten)]
+    pub stats: StatsView,
+    pub notes: Vec<String>,
+    /// Sessions with commits or branch switches; only with `report --commits`
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub session_commits: Option<Vec<SessionCommitsView>>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct SessionCommitsView {
+    pub id: Uuid,
+    pub start: DateTime<Local>,
+    pub end: Option<DateTime<Local>>,
+    pub commits: Vec<Commit>,
+    pub checkouts: Vec<Checkout>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct TagView {
+    /// `null` for untagged sessions
+    pub tag: Option<String>,
+    #[serde(flatten)]
+    pub stats: StatsView,
+}
+
+/// `clock-me init`
+#[derive(Debug, Clone, Serialize)]
+pub struct InitDocument {
+    pub project: String,
+}
+
+/// `clock-me start`: a new session, or the end of a break
+#[derive(Debug, Clone, Serialize)]
+pub struct ClockInDocument {
+    pub project: String,
+    /// True when this ended a break rather than starting a session
+    pub resumed: bool,
+    pub session: SessionView,
+    /// The break that was just ended
+    #[serde(rename = "break")]
+    pub ended_break: Option<BreakView>,
+}
+
+impl From<&ClockIn> for ClockInDocument {
+    fn from(clock_in: &ClockIn) -> Self {
+        let until = clock_in
+            .ended_break
+            .as_ref()
+            .and_then(|b| b.end)
+            .unwrap_or(clock_in.session.start);
+        Self {
+            project: clock_in.project_name.clone(),
+            resumed: clock_in.ended_break.is_some(),
+            session: SessionView::new(&clock_in.session, until),
+            ended_break: clock_in
+                .ended_break
+                .as_ref()
+                .map(|b| BreakView::new(b, until)),
+        }
+    }
+}
+
+/// `clock-me stop`
+#[derive(Debug, Clone, Serialize)]
+pub struct ClockOutDocument {
+    pub project: String,
+    pub session: SessionView,
+}
+
+impl From<&ClockOut> for ClockOutDocument {
+    fn from(clock_out: &ClockOut) -> Self {
+        let end = clock_out.session.end.unwrap_or(clock_out.session.start);
+        Self {
+            project: clock_out.project_name.clone(),
+            session: SessionView::new(&clock_out.session, end),
+        }
+    }
+}
+
+/// `clock-me break`
+#[derive(Debug, Clone, Serialize)]
+pub struct BreakDocument {
+    pub project: String,
+    #[serde(rename = "break")]
+    pub break_period: BreakView,
+    /// The session being paused, with its work time up to the break
+    pub session: SessionView,
+}
+
+impl From<&BreakStarted> for BreakDocument {
+    fn from(started: &BreakStarted) -> Self {
+        let until = started.break_period.start;
+        Self {
+            project: started.project_name.clone(),
+            break_period: BreakView::new(&started.break_period, until),
+            session: SessionView::new(&started.session, until),
+        }
+    }
+}
+
+/// `clock-me status`
+#[derive(Debug, Clone, Serialize)]
+pub struct StatusDocument {
+    pub project: String,
+    pub state: State,
+    pub now: DateTime<Local>,
+    pub current_session: Option<SessionView>,
+    pub current_break: Option<BreakView>,
+    pub last_session: Option<SessionView>,
+    pub today: Totals,
+    pub total: Totals,
+}
+
+impl From<&StatusInfo> for StatusDocument {
+    fn from(status: &StatusInfo) -> Self {
+        let now = status.current_time;
+        let current_break = status
+            .current_break
+            .as_ref()
+            .map(|break_period| BreakView::new(break_period, now));
+        let until = status.current_break_start.unwrap_or(now);
+
+        Self {
+            project: status.project_name.clone(),
+            state: State::of(status),
+            now,
+            current_session: status
+                .current_session
+                .as_ref()
+                .map(|s| SessionView::new(s, until)),
+            current_break,
+            last_session: status
+                .last_session
+                .as_ref()
+                .map(|s| SessionView::new(s, now)),
+            today: Totals {
+                work_time: status.today_work_time,
+                break_time: status.today_break_time,
+                sessions: status.today_sessions,
+                breaks: status.today_breaks,
+            },
+            total: Totals {
+                work_time: status.total_work_time,
+                break_time: status.total_break_time,
+                sessions: status.total_sessions,
+                breaks: status.total_breaks,
+            },
+        }
+    }
+}
+
+/// `clock-me report`; unlike the text output it always includes `tags`
+#[derive(Debug, Clone, Serialize)]
+pub struct ReportDocument {
+    pub project: String,
+    #[serde(serialize_with = "period_name")]
+    pub period: ReportPeriod,
+    pub from: NaiveDate,
+    pub to: NaiveDate,
+    pub buckets: Vec<BucketView>,
+    pub tags: Vec<TagView>,
+    pub total: StatsView,
+}
+
+impl ReportDocument {
+    pub fn new(report: &Report, options: ReportOptions) -> Self {
+        Self {
+            project: report.project_name.clone(),
+            period: report.period,
+            from: report.from,
+            to: report.to,
+            buckets: report
+                .buckets
+                .iter()
+                .map(|bucket| BucketView {
+                    start: bucket.start,
+                    stats: StatsView::from(&bucket.stats),
+                    notes: bucket.notes.clone(),
+                    session_commits: options.commits.then(|| {
+                        bucket
+                            .commits
+                            .iter()
+                            .map(|session| SessionCommitsView {
+                                id: session.id,
+                                start: session.start,
+                                end: session.end,
+                                commits: session.commits.clone(),
+                                checkouts: session.checkouts.clone(),
+                            })
+                            .collect()
+                    }),
+                })
+                .collect(),
+            tags: report
+                .tags
+                .iter()
+                .map(|tag_total| TagView {
+                    tag: tag_total.tag.clone(),
+                    stats: StatsView::from(&tag_total.stats),
+                })
+                .collect(),
+            total: StatsView::from(&report.total),
+        }
+    }
+}
+
+/// Pretty-printed JSON, one document per command
+pub struct JsonRenderer;
+
+impl JsonRenderer {
+    fn document<T: Serialize>(value: &T) -> Result<String> {
+        Ok(format!("{}\n", serde_json::to_string_pretty(value)?))
+    }
+}
+
+impl Renderer for JsonRenderer {
+    fn init(&self, project_name: &str) -> Result<String> {
+        Self::document(&InitDocument {
+            project: project_name.to_string(),
+        })
+    }
+
+    fn clock_in(&self, clock_in: &ClockIn) -> Result<String> {
+        Self::document(&ClockInDocument::from(clock_in))
+    }
+
+    fn clock_out(&self, clock_out: &ClockOut) -> Result<String> {
+        Self::document(&ClockOutDocument::from(clock_out))
+    }
+
+    fn break_started(&self, started: &BreakStarted) -> Result<String> {
+        Self::document(&BreakDocument::from(started))
+    }
+
+    fn status(&self, status: &StatusInfo) -> Result<String> {
+        Self::document(&StatusDocument::from(status))
+    }
+
+    fn report(&self, report: &Report, options: ReportOptions) -> Result<String> {
+        Self::document(&ReportDocument::new(report, options))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::session_service::{ReportBucket, SessionCommits};
+    use chrono::TimeZone;
+    use serde_json::json;
+
+    fn at(hour: u32, minute: u32) -> DateTime<Local> {
+        Local
+            .with_ymd_and_hms(2026, 10, 13, hour, minute, 0)
+            .unwrap()
+    }
+
+    #[test]
+    fn test_status_json_document() {
+        let mut session = Session::new(at(9, 0));
+        let mut lunch = Break::new(at(12, 0));
+        lunch.finish(at(12, 30));
+        session.add_break(lunch);
+        session.id = Uuid::from_u128(1);
+        session.breaks[0].id = Uuid::from_u128(2);
+
+        let mut coffee = Break::new(at(15, 0));
+        coffee.note = Some("coffee".to_string());
+        coffee.id = Uuid::from_u128(3);
+
+        let status = StatusInfo {
+            project_name: "api".to_string(),
+            current_session: Some(session),
+            current_break_start: Some(at(15, 0)),
+            current_break: Some(coffee),
+            last_session: None,
+            total_sessions: 0,
+            current_time: at(15, 10),
+            today_work_time: Duration::hours(5) + Duration::minutes(30),
+            today_break_time: Duration::minutes(40),
+            today_sessions: 1,
+            today_breaks: 2,
+            total_work_time: Duration::hours(5) + Duration::minutes(30),
+            total_break_time: Duration::minutes(40),
+            total_breaks: 2,
+        };
+
+        let value = serde_json::to_value(StatusDocument::from(&status)).unwrap();
+        assert_eq!(value["state"], "on_break");
+        assert_eq!(value["now"], json!(at(15, 10)));
+        assert_eq!(value["current_break"]["duration_seconds"], 600);
+        assert_eq!(value["current_break"]["note"], "coffee");
+        assert_eq!(value["current_break"]["id"], Uuid::from_u128(3).to_string());
+        assert_eq!(
+            value["current_session"],
+            json!({
+                "id": "00000000-0000-0000-0000-000000000001",
+                "start": json!(at(9, 0)),
+                "end": null,
+                "work_seconds": 5 * 3600 + 30 * 60,
+                "break_seconds": 1800,
+                "breaks": [{
+                    "id": "00000000-0000-0000-0000-000000000002",
+                    "start": json!(at(12, 0)),
+                    "end": json!(at(12, 30)),
+                    "duration_seconds": 1800,
+                    "note": null
+                }],
+                "note": null,
+                "tags": [],
+                "origin": "clocked",
+                "commits": [],
+                "checkouts": []
+            })
+        );
+        assert_eq!(value["last_session"], json!(null));
+        assert_eq!(
+            value["today"],
+            json!({"work_seconds": 19800, "break_seconds": 2400, "sessions": 1, "breaks": 2})
+        );
+    }
+
+    #[test]
+    fn test_report_commits_do_not_replace_session_count() {
+        let commit = Commit {
+            hash: "3f9c2a1e".repeat(5),
+            subject: "Sketch the orders API".to_string(),
+            branch: None,
+            at: at(10, 0),
+        };
+        let stats = TimeStats {
+            sessions: 1,
+            ..TimeStats::zero()
+        };
+        let report = Report {
+            project_name: "api".to_string(),
+            period: ReportPeriod::Day,
+            from: at(9, 0).date_naive(),
+            to: at(9, 0).date_naive(),
+            buckets: vec![ReportBucket {
+                start: at(9, 0).date_naive(),
+                stats,
+                notes: Vec::new(),
+                commits: vec![SessionCommits {
+                    id: Uuid::from_u128(1),
+                    start: at(9, 0),
+                    end: Some(at(11, 0)),
+                    commits: vec![commit],
+                    checkouts: Vec::new(),
+                }],
+            }],
+            tags: Vec::new(),
+            total: stats,
+        };
+
+        let value =
+            serde_json::to_value(ReportDocument::new(&report, ReportOptions::default())).unwrap();
+        assert_eq!(value["buckets"][0]["sessions"], 1);
+        assert!(value["buckets"][0].get("session_commits").is_none());
+
+        let options = ReportOptions {
+            tags: false,
+            commits: true,
+        };
+        let value = serde_json::to_value(ReportDocument::new(&report, options)).unwrap();
+        let bucket = &value["buckets"][0];
+        assert_eq!(bucket["sessions"], 1);
+        assert_eq!(
+            bucket["session_commits"][0]["id"],
+            "00000000-0000-0000-0000-000000000001"
+        );
+        assert_eq!(
+            bucket["session_commits"][0]["commits"][0]["subject"],
+            "Sketch the orders API"
+        );
+    }
+}
@@ -0,0 +1,345 @@
+//! Turns command outcomes into what is printed.
+//!
+//! `CommandHandler` passes every result through a [`Renderer`], so the same
+//! outcome can be shown as plain text, colored text, JSON or a single word.
+
+pub mod bar;
+pub mod json;
+pub mod prompt;
+pub mod quiet;
+pub mod text;
+
+use crate::session_service::{BreakStarted, ClockIn, ClockOut, Report, StatusInfo};
+use anyhow::{anyhow, Result};
+use serde::Serialize;
+use std::io::{self, IsTerminal};
+
+pub trait Renderer {
+    fn init(&self, project_name: &str) -> Result<String>;
+    fn clock_in(&self, clock_in: &ClockIn) -> Result<String>;
+    fn clock_out(&self, clock_out: &ClockOut) -> Result<String>;
+    fn break_started(&self, started: &BreakStarted) -> Result<String>;
+    fn status(&self, status: &StatusInfo) -> Result<String>;
+    fn report(&self, report: &Report, options: ReportOptions) -> Result<String>;
+}
+
+/// Sections of the report that only show when asked for, in formats that
+/// can leave them out
+#[derive(Debug, Clone, Copy, Default)]
+pub struct ReportOptions {
+    /// Work time per tag
+    pub tags: bool,
+    /// Commits of each session
+    pub commits: bool,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum OutputFormat {
+    Text,
+    Color,
+    Json,
+    Quiet,
+}
+
+impl std::str::FromStr for OutputFormat {
+    type Err = anyhow::Error;
+
+    /// `auto` picks color on a terminal unless `NO_COLOR` is set
+    fn from_str(s: &str) -> Result<Self> {
+        match s.trim().to_lowercase().as_str() {
+            "auto" => Ok(OutputFormat::auto(
+                io::stdout().is_terminal(),
+                std::env::var_os("NO_COLOR").is_some_and(|v| !v.is_empty()),
+            )),
+            "text" => Ok(OutputFormat::Text),
+            "color" => Ok(OutputFormat::Color),
+            "json" => Ok(OutputFormat::Json),
+            "quiet" => Ok(OutputFormat::Quiet),
+            _ => Err(anyhow!(
+                "Unsupported output format '{}'. Use 'auto', 'text', 'color', 'json' or 'quiet'",
+                s
+            )),
+        }
+    }
+}
+
+impl OutputFormat {
+    fn auto(terminal: bool, no_color: bool) -> Self {
+        if terminal && !no_color {
+            OutputFormat::Color
+        } else {
+            OutputFormat::Text
+        }
+    }
+
+    pub fn renderer(&self) -> Box<dyn Renderer> {
+        match self {
+            OutputFormat::Text => Box::new(text::TextRenderer::plain()),
+            OutputFormat::Color => Box::new(text::TextRenderer::color()),
+            OutputFormat::Json => Box::new(json::JsonRenderer),
+            OutputFormat::Quiet => Box::new(quiet::QuietRenderer),
+        }
+    }
+}
+
+/// What the user is doing, as reported by `status`
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum State {
+    Working,
+    OnBreak,
+    ClockedOut,
+}
+
+impl State {
+    pub fn of(status: &StatusInfo) -> Self {
+        match (&status.current_session, status.current_break_start) {
+            (Some(_), Some(_)) => State::OnBreak,
+            (Some(_), None) => State::Working,
+            (None, _) => State::ClockedOut,
+        }
+    }
+
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            State::Working => "working",
+            State::OnBreak => "on_break",
+            State::ClockedOut => "clocked_out",
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::clock::MockClock;
+    use crate::models::commit::{Checkout, Commit};
+    use crate::models::project::Project;
+    use crate::models::r#break::Break;
+    use crate::models::session::Session;
+    use crate::parsers::TimeSpec;
+    use crate::repository::lock::RepositoryLock;
+    use crate::repository::MockRepository;
+    use crate::session_service::{ReportPeriod, SessionService};
+    use chrono::{DateTime, Local, NaiveDate, TimeZone};
+    use regex::Regex;
+    use std::fs;
+    use std::path::PathBuf;
+
+    const FORMATS: [(&str, OutputFormat, &str); 4] = [
+        ("text", OutputFormat::Text, "txt"),
+        ("color", OutputFormat::Color, "txt"),
+        ("json", OutputFormat::Json, "json"),
+        ("quiet", OutputFormat::Quiet, "txt"),
+    ];
+
+    fn at(day: u32, hour: u32, minute: u32) -> DateTime<Local> {
+        Local
+            .with_ymd_and_hms(2026, 10, day, hour, minute, 0)
+            .unwrap()
+    }
+
+    fn finished(start: DateTime<Local>, end: DateTime<Local>, breaks: &[(u32, u32)]) -> Session {
+        let mut session = Session::new(start);
+        for &(hour, minutes) in breaks {
+            let break_start = start.date_naive().and_hms_opt(hour, 0, 0).unwrap();
+            let break_start = break_start.and_local_timezone(Local).unwrap();
+            let mut break_period = Break::new(break_start);
+            break_period.finish(break_start + chrono::Duration::minutes(minutes as i64));
+            session.add_break(break_period);
+        }
+        session.finish(end);
+        session
+    }
+
+    /// Two days of history: a tagged Monday with a lunch break, two commits and
+    /// a branch switch, a manual entry on Tuesday
+    fn history() -> Project {
+        let mut project = Project::new("my-app".to_string());
+        let mut monday = finished(at(12, 9, 0), at(12, 17, 30), &[(12, 45)]);
+        monday.note = Some("API design".to_string());
+        monday.tags = vec!["backend".to_string()];
+        for (hash, subject, hour) in [
+            ("3f9c2a1e", "Sketch the orders API", 11),
+            ("7b01d4c2", "Add pagination", 16),
+        ] {
+            monday.add_commit(Commit {
+                hash: hash.repeat(5),
+                subject: subject.to_string(),
+                branch: Some("api-design".to_string()),
+                at: at(12, hour, 20),
+            });
+        }
+        monday.add_checkout(Checkout {
+            branch: "api-design".to_string(),
+            at: at(12, 10, 5),
+        });
+        project.sessions.push(monday);
+
+        let mut tuesday = Session::manual(at(13, 10, 0), at(13, 12, 0));
+        tuesday.note = Some("Code review".to_string());
+        project.sessions.push(tuesday);
+        project
+    }
+
+    fn service(project: Project, now: DateTime<Local>) -> SessionService {
+        let mut repository = MockRepository::new();
+        repository
+            .expect_lock()
+            .returning(|| Ok(RepositoryLock::none()));
+        repository
+            .expect_load()
+            .returning(move || Ok(project.clone()));
+        repository.expect_save().returning(|_| Ok(()));
+        let mut clock = MockClock::new();
+        clock.expect_now().returning(move || now);
+        SessionService::new(Box::new(repository), Box::new(clock))
+    }
+
+    type Scenario = Box<dyn Fn(&dyn Renderer) -> Result<String>>;
+
+    /// Every command outcome that goes through a renderer, on Wednesday 2026-10-14
+    fn scenarios() -> Vec<(&'static str, Scenario)> {
+        let working = || {
+            let mut project = history();
+            project.start_session(at(14, 9, 0));
+            let session = project.current_session.as_mut().unwrap();
+            session.note = Some("Release".to_string());
+            session.tags = vec!["ops".to_string()];
+            project
+        };
+        let on_break = move || {
+            let mut project = working();
+            project.start_break(at(14, 10, 0)).unwrap();
+            project.end_break(at(14, 10, 15)).unwrap();
+            project.start_break(at(14, 12, 0)).unwrap();
+            project.current_break.as_mut().unwrap().note = Some("lunch".to_string());
+            project
+        };
+
+        vec![
+            ("init", Box::new(|r: &dyn Renderer| r.init("my-app"))),
+            (
+                "start",
+                Box::new(|r: &dyn Renderer| {
+                    let service = service(history(), at(14, 9, 0));
+                    let tags = vec!["ops".to_string()];
+                    r.clock_in(&service.start_session(None, Some("Release".into()), tags)?)
+                }),
+            ),
+            (
+                "start_after_break",
+                Box::new(move |r: &dyn Renderer| {
+                    let service = service(on_break(), at(14, 12, 40));
+                    r.clock_in(&service.start_session(None, None, Vec::new())?)
+                }),
+            ),
+            (
+                "break",
+                Box::new(move |r: &dyn Renderer| {
+                    let service = service(working(), at(14, 12, 5));
+                    let at = TimeSpec::parse_ago("5m")?;
+                    r.break_started(&service.start_break(Some(at), Some("lunch".into()))?)
+                }),
+            ),
+            (
+                "stop",
+                Box::new(move |r: &dyn Renderer| {
+                    let mut project = on_break();
+                    project.end_break(at(14, 12, 45)).unwrap();
+                    let service = service(project, at(14, 17, 0));
+                    r.clock_out(&service.end_session(None, Some("shipped".into()))?)
+                }),
+            ),
+            (
+                "status_working",
+                Box::new(move |r: &dyn Renderer| {
+                    r.status(&service(working(), at(14, 11, 30)).get_status()?)
+                }),
+            ),
+            (
+                "status_on_break",
+                Box::new(move |r: &dyn Renderer| {
+                    r.status(&service(on_break(), at(14, 12, 20)).get_status()?)
+                }),
+            ),
+            (
+                "status_clocked_out",
+                Box::new(|r: &dyn Renderer| {
+                    r.status(&service(history(), at(14, 8, 0)).get_status()?)
+                }),
+            ),
+            (
+                "report",
+                Box::new(|r: &dyn Renderer| {
+                    let report = service(history(), at(14, 8, 0)).get_report(
+                        ReportPeriod::Day,
+                        NaiveDate::from_ymd_opt(2026, 10, 12),
+                        None,
+                    )?;
+                    let options = ReportOptions {
+                        tags: true,
+                        commits: true,
+                    };
+                    r.report(&report, options)
+                }),
+            ),
+        ]
+    }
+
+    /// Replaces what differs between runs: generated IDs, and the UTC offset
+    /// of the machine running the tests
+    fn normalize(output: &str) -> String {
+        let uuid =
+            Regex::new(r"[0-9a-f]{8}-[0-9a-f]{4}-[0-9a-f]{4}-[0-9a-f]{4}-[0-9a-f]{12}").unwrap();
+        let output = uuid.replace_all(output, "<id>").to_string();
+        let offset = at(14, 0, 0).format("%:z").to_string();
+        output.replace(&format!("{}\"", offset), "Z\"")
+    }
+
+    /// Compares against `tests/golden/<format>/<scenario>`. Run with
+    /// `UPDATE_GOLDEN=1` to rewrite the files after an intended change.
+    #[test]
+    fn test_golden_output() {
+        let golden_dir = PathBuf::from(env!("CARGO_MANIFEST_DIR")).join("tests/golden");
+        let update = std::env::var_os("UPDATE_GOLDEN").is_some();
+        let mut mismatches = Vec::new();
+
+        for (format_name, format, extension) in FORMATS {
+            let renderer = format.renderer();
+            for (scenario, render) in scenarios() {
+                let actual = normalize(&render(renderer.as_ref()).unwrap());
+                let path = golden_dir
+                    .join(format_name)
+                    .join(format!("{}.{}", scenario, extension));
+
+                if update {
+                    fs::create_dir_all(path.parent().unwrap()).unwrap();
+                    fs::write(&path, &actual).unwrap();
+                } else if fs::read_to_string(&path).ok().as_deref() != Some(actual.as_str()) {
+                    mismatches.push(format!("{}:\n{}", path.display(), actual));
+                }
+            }
+        }
+
+        assert!(
+            mismatches.is_empty(),
+            "Output differs from the golden files (UPDATE_GOLDEN=1 rewrites them):\n\n{}",
+            mismatches.join("\n")
+        );
+    }
+
+    #[test]
+    fn test_parse_output_format() {
+        assert_eq!("json".parse::<OutputFormat>().unwrap(), OutputFormat::Json);
+        assert_eq!(
+            " Quiet".parse::<OutputFormat>().unwrap(),
+            OutputFormat::Quiet
+        );
+        assert!("yaml".parse::<OutputFormat>().is_err());
+
+        assert_eq!(OutputFormat::auto(true, false), OutputFormat::Color);
+        assert_eq!(OutputFormat::auto(true, true), OutputFormat::Text);
+        assert_eq!(OutputFormat::auto(false, false), OutputFormat::Text);
+    }
+}
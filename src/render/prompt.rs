@@ -0,0 +1,153 @@
+use crate::parsers::DurationParser;
+use crate::render::State;
+use crate::session_service::StatusInfo;
+use regex::{Captures, Regex};
+
+/// Used when `clock-me prompt` is given no `--format`
+pub const DEFAULT_FORMAT: &str = "{project} {icon} {elapsed}";
+
+/// Fills a template such as `"{project} {icon} {elapsed}"` for shell prompts
+/// and status lines.
+///
+/// Placeholders:
+/// - `{project}`: project name
+/// - `{state}`: `working`, `on_break` or `clocked_out`
+/// - `{icon}`: ▶ while working, ☕ on a break, nothing when clocked out
+/// - `{session}`: work time of the active session
+/// - `{break}`: length of the running break
+/// - `{elapsed}`: `{break}` on a break, `{session}` otherwise
+/// - `{today}`: work time today, including the active session
+///
+/// Placeholders that do not apply are left empty, unknown ones as they are,
+/// and the result is trimmed.
+pub fn render(template: &str, status: &StatusInfo) -> String {
+    let state = State::of(status);
+    let session_time = status.current_session.as_ref().map(|session| {
+        let until = status.current_break_start.unwrap_or(status.current_time);
+        until - session.start - session.total_break_time()
+    });
+    let break_time = status
+        .current_break_start
+        .map(|start| status.current_time - start);
+
+    let placeholder = Regex::new(r"\{(\w+)\}").unwrap();
+    let output = placeholder.replace_all(template, |caps: &Captures| match &caps[1] {
+        "project" => status.project_name.clone(),
+        "state" => state.as_str().to_string(),
+        "icon" => match state {
+            State::Working => "▶".to_string(),
+            State::OnBreak => "☕".to_string(),
+            State::ClockedOut => String::new(),
+        },
+        "session" => session_time.map(DurationParser::format).unwrap_or_default(),
+        "break" => break_time.map(DurationParser::format).unwrap_or_default(),
+        "elapsed" => break_time
+            .or(session_time)
+            .map(DurationParser::format)
+            .unwrap_or_default(),
+        "today" => DurationParser::format(status.today_work_time),
+        _ => caps[0].to_string(),
+    });
+    output.trim().to_string()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::project::Project;
+    use crate::repository::{self, FileRepository, Repository, StorageBackend};
+    use crate::session_service::SessionService;
+    use chrono::{DateTime, Duration, Local, TimeZone};
+    use std::fs;
+    use tempfile::TempDir;
+
+    fn at(hour: u32, minute: u32) -> DateTime<Local> {
+        Local
+            .with_ymd_and_hms(2026, 10, 14, hour, minute, 0)
+            .unwrap()
+    }
+
+    fn working() -> Project {
+        let mut project = Project::new("my-app".to_string());
+        project.start_session(at(9, 0));
+        project.start_break(at(10, 0)).unwrap();
+        project.end_break(at(10, 20)).unwrap();
+        project
+    }
+
+    #[test]
+    fn test_render_working() {
+        let status = SessionService::status_at(&working(), at(10, 32));
+        assert_eq!(render(DEFAULT_FORMAT, &status), "my-app ▶ 1h 12m");
+        assert_eq!(
+            render("{state} {session} ({break}) today {today}", &status),
+            "working 1h 12m () today 1h 12m"
+        );
+    }
+
+    #[test]
+    fn test_render_on_break() {
+        let mut project = working();
+        project.start_break(at(11, 0)).unwrap();
+        let status = SessionService::status_at(&project, at(11, 8));
+
+        assert_eq!(render("{icon} {elapsed}", &status), "☕ 8m");
+        assert_eq!(render(DEFAULT_FORMAT, &status), "my-app ☕ 8m");
+        assert_eq!(render("{session}", &status), "1h 40m");
+    }
+
+    #[test]
+    fn test_render_clocked_out() {
+        let mut project = working();
+        project.end_session(at(12, 0)).unwrap();
+        let status = SessionService::status_at(&project, at(13, 0));
+
+        assert_eq!(render(DEFAULT_FORMAT, &status), "my-app");
+        assert_eq!(
+            render("{state} {today} {unknown}", &status),
+            "clocked_out 2h 40m {unknown}"
+        );
+    }
+
+    /// The prompt of a project stored with `backend`, read the way
+    /// `clock-me prompt` reads it
+    fn prompt_with(backend: StorageBackend) -> String {
+        let temp_dir = TempDir::new().unwrap();
+        let data_dir = temp_dir.path().join(".clockme");
+        let start = Local::now() - Duration::minutes(90);
+        let mut project = Project::new("my-app".to_string());
+        project.start_session(start);
+        FileRepository::with_data_dir(data_dir.clone())
+            .save(&project)
+            .unwrap();
+        if backend != StorageBackend::Json {
+            repository::migrate_storage(&data_dir, backend).unwrap();
+        }
+        if backend == StorageBackend::Events {
+            // Cut short by a crash; skipped without a warning
+            let mut log = fs::read_to_string(data_dir.join("events.jsonl")).unwrap();
+            log.push_str("{\"at\": \"2025-10-1");
+            fs::write(data_dir.join("events.jsonl"), log).unwrap();
+        }
+
+        let project = repository::read_project(&data_dir).unwrap();
+        let status = SessionService::status_at(&project, start + Duration::minutes(90));
+        render(DEFAULT_FORMAT, &status)
+    }
+
+    #[test]
+    fn test_render_from_each_backend() {
+        for backend in [
+            StorageBackend::Json,
+            StorageBackend::Sqlite,
+            StorageBackend::Events,
+        ] {
+            assert_eq!(
+                prompt_with(backend),
+                "my-app ▶ 1h 30m",
+                "{}",
+                backend.as_str()
+            );
+        }
+    }
+}
@@ -0,0 +1,37 @@
+use crate::parsers::DurationParser;
+use crate::render::{Renderer, ReportOptions, State};
+use crate::session_service::{BreakStarted, ClockIn, ClockOut, Report, StatusInfo};
+use anyhow::Result;
+
+/// Prints nothing for commands that change state, and a single word or
+/// duration for the ones that report, for use in scripts
+pub struct QuietRenderer;
+
+impl Renderer for QuietRenderer {
+    fn init(&self, _project_name: &str) -> Result<String> {
+        Ok(String::new())
+    }
+
+    fn clock_in(&self, _clock_in: &ClockIn) -> Result<String> {
+        Ok(String::new())
+    }
+
+    fn clock_out(&self, _clock_out: &ClockOut) -> Result<String> {
+        Ok(String::new())
+    }
+
+    fn break_started(&self, _started: &BreakStarted) -> Result<String> {
+        Ok(String::new())
+    }
+
+    fn status(&self, status: &StatusInfo) -> Result<String> {
+        Ok(format!("{}\n", State::of(status).as_str()))
+    }
+
+    fn report(&self, report: &Report, _options: ReportOptions) -> Result<String> {
+        Ok(format!(
+            "{}\n",
+            DurationParser::format(report.total.work_time)
+        ))
+    }
+}
@@ -0,0 +1,451 @@
+use crate::models::session::Session;
+use crate::parsers::DurationParser;
+use crate::render::{Renderer, ReportOptions};
+use crate::session_service::{
+    BreakStarted, ClockIn, ClockOut, Report, ReportPeriod, SessionCommits, StatusInfo,
+};
+use anyhow::Result;
+use chrono::{DateTime, Datelike, Duration, Local, NaiveDate};
+use std::fmt::Write;
+
+const SEPARATOR: &str = "━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━";
+
+#[derive(Debug, Clone, Copy)]
+enum Style {
+    /// Confirmation lines and the clocked-in state
+    Success,
+    /// The on-break state
+    Warning,
+    /// Section headings and report rows
+    Heading,
+    /// Durations
+    Value,
+    /// Separators
+    Muted,
+}
+
+impl Style {
+    fn code(&self) -> &'static str {
+        match self {
+            Style::Success => "32",
+            Style::Warning => "33",
+            Style::Heading => "1",
+            Style::Value => "36",
+            Style::Muted => "2",
+        }
+    }
+}
+
+/// The human-readable output, optionally with ANSI colors
+pub struct TextRenderer {
+    color: bool,
+}
+
+impl TextRenderer {
+    pub fn plain() -> Self {
+        Self { color: false }
+    }
+
+    pub fn color() -> Self {
+        Self { color: true }
+    }
+
+    fn paint(&self, style: Style, text: &str) -> String {
+        if self.color {
+            format!("\x1b[{}m{}\x1b[0m", style.code(), text)
+        } else {
+            text.to_string()
+        }
+    }
+
+    fn duration(&self, duration: Duration) -> String {
+        self.paint(Style::Value, &DurationParser::format(duration))
+    }
+
+    fn separator(&self) -> String {
+        self.paint(Style::Muted, SEPARATOR)
+    }
+
+    fn last_session(&self, out: &mut String, session: &Session) -> std::fmt::Result {
+        writeln!(out, "\n{}", self.paint(Style::Heading, "Last session:"))?;
+        writeln!(
+            out,
+            "  Started: {}",
+            session.start.format("%Y-%m-%d %H:%M:%S")
+        )?;
+        let Some(end) = session.end else {
+            return Ok(());
+        };
+
+        writeln!(out, "  Ended: {}", end.format("%Y-%m-%d %H:%M:%S"))?;
+        if let Some(ref note) = session.note {
+            writeln!(out, "  Note: {}", note)?;
+        }
+        if !session.tags.is_empty() {
+            writeln!(out, "  Tags: {}", session.tags.join(", "))?;
+        }
+        if let Some(work_time) = session.work_time() {
+            writeln!(out, "  Work time: {}", self.duration(work_time))?;
+            let break_time = session.total_break_time();
+            if break_time.num_minutes() > 0 {
+                writeln!(
+                    out,
+                    "  Break time: {} ({} breaks)",
+                    self.duration(break_time),
+                    session.breaks.len()
+                )?;
+            }
+        }
+        Ok(())
+    }
+
+    fn session_commits(&self, out: &mut String, session: &SessionCommits) -> std::fmt::Result {
+        let end = match session.end {
+            Some(end) => end.format("%H:%M").to_string(),
+            None => "now".to_string(),
+        };
+        writeln!(out, "  Session {}–{}:", session.start.format("%H:%M"), end)?;
+
+        let mut lines: Vec<(DateTime<Local>, String)> = session
+            .commits
+            .iter()
+            .map(|commit| {
+                let branch = commit
+                    .branch
+                    .as_ref()
+                    .map(|branch| format!(" ({})", branch))
+                    .unwrap_or_default();
+                let line = format!(
+                    "{} {}{}",
+                    self.paint(Style::Value, commit.short_hash()),
+                    commit.subject,
+                    branch
+                );
+                (commit.at, line)
+            })
+            .collect();
+        lines.extend(
+            session
+                .checkouts
+                .iter()
+                .map(|checkout| (checkout.at, format!("→ switched to {}", checkout.branch))),
+        );
+        lines.sort_by_key(|(at, _)| *at);
+        for (_, line) in lines {
+            writeln!(out, "    {}", line)?;
+        }
+        Ok(())
+    }
+
+    fn bucket_label(period: ReportPeriod, start: NaiveDate) -> String {
+        match period {
+            ReportPeriod::Day => start.format("%a %Y-%m-%d").to_string(),
+            ReportPeriod::Week => {
+                let week = start.iso_week();
+                let end = start + Duration::days(6);
+                format!(
+                    "{}-W{:02} ({} – {})",
+                    week.year(),
+                    week.week(),
+                    start.format("%m-%d"),
+                    end.format("%m-%d")
+                )
+            }
+            ReportPeriod::Month => start.format("%Y-%m (%B)").to_string(),
+        }
+    }
+}
+
+impl Renderer for TextRenderer {
+    fn init(&self, _project_name: &str) -> Result<String> {
+        let mut out = String::new();
+        writeln!(
+            out,
+            "{}",
+            self.paint(Style::Success, "✓ Project initialized successfully!")
+        )?;
+        writeln!(
+            out,
+            "You can now use 'clock-me start' to start tracking time."
+        )?;
+        Ok(out)
+    }
+
+    fn clock_in(&self, clock_in: &ClockIn) -> Result<String> {
+        let mut out = String::new();
+        let session = &clock_in.session;
+
+        if let Some(ref ended_break) = clock_in.ended_break {
+            let line = format!(
+                "✓ Break ended, continuing work on: {}",
+                clock_in.project_name
+            );
+            writeln!(out, "{}", self.paint(Style::Success, &line))?;
+            if let Some(duration) = ended_break.duration() {
+                writeln!(out, "Break duration: {}", self.duration(duration))?;
+            }
+
+            // Show accumulated break time in this session
+            let total_break_time = session.total_break_time();
+            if total_break_time.num_minutes() > 0 {
+                writeln!(
+                    out,
+                    "Total break time this session: {}",
+                    self.duration(total_break_time)
+                )?;
+            }
+        } else {
+            let line = format!("✓ Clocked in to project: {}", clock_in.project_name);
+            writeln!(out, "{}", self.paint(Style::Success, &line))?;
+            if !session.tags.is_empty() {
+                writeln!(out, "Tags: {}", session.tags.join(", "))?;
+            }
+            writeln!(
+                out,
+                "Started tracking time at {}",
+                session.start.format("%H:%M:%S")
+            )?;
+        }
+        Ok(out)
+    }
+
+    fn clock_out(&self, clock_out: &ClockOut) -> Result<String> {
+        let mut out = String::new();
+        let session = &clock_out.session;
+
+        let line = format!("✓ Clocked out from project: {}", clock_out.project_name);
+        writeln!(out, "{}", self.paint(Style::Success, &line))?;
+        writeln!(
+            out,
+            "Session work time: {}",
+            self.duration(clock_out.work_time)
+        )?;
+        writeln!(
+            out,
+            "  ({:.2} hours)",
+            clock_out.work_time.num_minutes() as f64 / 60.0
+        )?;
+
+        if let Some(ref note) = session.note {
+            writeln!(out, "Note: {}", note)?;
+        }
+        let break_time = session.total_break_time();
+        if break_time.num_minutes() > 0 {
+            writeln!(out, "Break time: {}", self.duration(break_time))?;
+            writeln!(out, "  (Breaks taken: {})", session.breaks.len())?;
+        }
+        Ok(out)
+    }
+
+    fn break_started(&self, started: &BreakStarted) -> Result<String> {
+        let mut out = String::new();
+
+        let line = format!("✓ Break started for project: {}", started.project_name);
+        writeln!(out, "{}", self.paint(Style::Success, &line))?;
+        if let Some(ref note) = started.break_period.note {
+            writeln!(out, "Reason: {}", note)?;
+        }
+        writeln!(
+            out,
+            "Work time before break: {}",
+            self.duration(started.work_time)
+        )?;
+
+        let accumulated_breaks = started.session.total_break_time();
+        if accumulated_breaks.num_minutes() > 0 {
+            writeln!(
+                out,
+                "Previous breaks this session: {}",
+                self.duration(accumulated_breaks)
+            )?;
+        }
+
+        writeln!(out, "\nUse 'clock-me start' to continue working")?;
+        Ok(out)
+    }
+
+    fn status(&self, status: &StatusInfo) -> Result<String> {
+        let mut out = String::new();
+        writeln!(out, "{}", self.separator())?;
+        writeln!(out, "Project: {}", status.project_name)?;
+
+        if let Some(ref session) = status.current_session {
+            if let Some(break_start) = status.current_break_start {
+                writeln!(out, "Status: {}", self.paint(Style::Warning, "On BREAK 🔴"))?;
+                writeln!(out, "Break started at: {}", break_start.format("%H:%M:%S"))?;
+                if let Some(reason) = status.current_break.as_ref().and_then(|b| b.note.as_ref()) {
+                    writeln!(out, "Reason: {}", reason)?;
+                }
+
+                let break_duration = status.current_time.signed_duration_since(break_start);
+                writeln!(
+                    out,
+                    "Current break duration: {}",
+                    self.duration(break_duration)
+                )?;
+
+                // Show accumulated break time
+                let accumulated_breaks = session.total_break_time();
+                if accumulated_breaks.num_minutes() > 0 {
+                    writeln!(
+                        out,
+                        "Previous breaks this session: {}",
+                        self.duration(accumulated_breaks)
+                    )?;
+                }
+            } else {
+                writeln!(
+                    out,
+                    "Status: {}",
+                    self.paint(Style::Success, "Clocked IN ✓")
+                )?;
+                writeln!(
+                    out,
+                    "Started at: {}",
+                    session.start.format("%Y-%m-%d %H:%M:%S")
+                )?;
+
+                let elapsed = status.current_time.signed_duration_since(session.start);
+                let break_time = session.total_break_time();
+                writeln!(out, "Working for: {}", self.duration(elapsed - break_time))?;
+                if let Some(ref note) = session.note {
+                    writeln!(out, "Note: {}", note)?;
+                }
+                if !session.tags.is_empty() {
+                    writeln!(out, "Tags: {}", session.tags.join(", "))?;
+                }
+
+                if break_time.num_minutes() > 0 {
+                    writeln!(
+                        out,
+                        "Break time this session: {} ({} breaks)",
+                        self.duration(break_time),
+                        session.breaks.len()
+                    )?;
+                }
+            }
+        } else {
+            writeln!(out, "Status: Clocked OUT")?;
+            if let Some(ref last_session) = status.last_session {
+                self.last_session(&mut out, last_session)?;
+            }
+        }
+
+        writeln!(out, "\n{}", self.paint(Style::Heading, "Today's Summary:"))?;
+        writeln!(
+            out,
+            "  Work time: {}",
+            self.duration(status.today_work_time)
+        )?;
+        if status.today_break_time.num_minutes() > 0 {
+            writeln!(
+                out,
+                "  Break time: {}",
+                self.duration(status.today_break_time)
+            )?;
+        }
+        writeln!(out, "  Sessions: {}", status.today_sessions)?;
+        if status.today_breaks > 0 {
+            writeln!(out, "  Breaks: {}", status.today_breaks)?;
+        }
+
+        writeln!(
+            out,
+            "\n {}",
+            self.paint(Style::Heading, "Total (all time):")
+        )?;
+        writeln!(
+            out,
+            "  Work time: {}",
+            self.duration(status.total_work_time)
+        )?;
+        if status.total_break_time.num_minutes() > 0 {
+            writeln!(
+                out,
+                "  Break time: {}",
+                self.duration(status.total_break_time)
+            )?;
+        }
+        writeln!(out, "  Sessions: {}", status.total_sessions)?;
+
+        writeln!(out, "{}", self.separator())?;
+        Ok(out)
+    }
+
+    fn report(&self, report: &Report, options: ReportOptions) -> Result<String> {
+        let mut out = String::new();
+        writeln!(out, "{}", self.separator())?;
+        writeln!(out, "Report: {}", report.project_name)?;
+        writeln!(
+            out,
+            "Range: {} – {}",
+            report.from.format("%Y-%m-%d"),
+            report.to.format("%Y-%m-%d")
+        )?;
+
+        if report.buckets.is_empty() {
+            writeln!(out, "\nNo sessions in this range.")?;
+        } else {
+            writeln!(out)?;
+            for bucket in &report.buckets {
+                let label = Self::bucket_label(report.period, bucket.start);
+                writeln!(out, "{}", self.paint(Style::Heading, &label))?;
+                let manual = if bucket.stats.manual_sessions > 0 {
+                    format!(" ({} manual)", bucket.stats.manual_sessions)
+                } else {
+                    String::new()
+                };
+                writeln!(
+                    out,
+                    "  Work: {}  Break: {}  Sessions: {}{}  Breaks: {}",
+                    self.duration(bucket.stats.work_time),
+                    self.duration(bucket.stats.break_time),
+                    bucket.stats.sessions,
+                    manual,
+                    bucket.stats.breaks
+                )?;
+                for note in &bucket.notes {
+                    writeln!(out, "  • {}", note)?;
+                }
+                if options.commits {
+                    for session in &bucket.commits {
+                        self.session_commits(&mut out, session)?;
+                    }
+                }
+            }
+        }
+
+        if options.tags && !report.tags.is_empty() {
+            writeln!(out, "\n{}", self.paint(Style::Heading, "By tag:"))?;
+            for tag_total in &report.tags {
+                writeln!(
+                    out,
+                    "  {:<20} {} ({} sessions)",
+                    tag_total.tag.as_deref().unwrap_or("(untagged)"),
+                    self.duration(tag_total.stats.work_time),
+                    tag_total.stats.sessions
+                )?;
+            }
+        }
+
+        writeln!(out, "\n{}", self.paint(Style::Heading, "Total:"))?;
+        writeln!(
+            out,
+            "  Work time: {}",
+            self.duration(report.total.work_time)
+        )?;
+        writeln!(
+            out,
+            "  Break time: {}",
+            self.duration(report.total.break_time)
+        )?;
+        writeln!(out, "  Sessions: {}", report.total.sessions)?;
+        if report.total.manual_sessions > 0 {
+            writeln!(out, "  Manual entries: {}", report.total.manual_sessions)?;
+        }
+        writeln!(out, "  Breaks: {}", report.total.breaks)?;
+
+        writeln!(out, "{}", self.separator())?;
+        Ok(out)
+    }
+}
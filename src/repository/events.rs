@@ -0,0 +1,630 @@
+use crate::clock::{Clock, SystemClock};
+use crate::models::project::Project;
+use crate::models::r#break::Break;
+use crate::models::session::Session;
+use crate::repository::lock::{RepositoryLock, LOCK_TIMEOUT};
+use crate::repository::migrations::{self, VersionedProject};
+use crate::repository::Repository;
+use anyhow::{anyhow, Context, Result};
+use chrono::{DateTime, Local};
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::collections::{HashMap, HashSet};
+use std::fs::{self, File, OpenOptions};
+use std::io::{Read, Write};
+use std::path::PathBuf;
+use uuid::Uuid;
+
+/// Number of events after which the replayed state is written to a snapshot
+const SNAPSHOT_INTERVAL: usize = 100;
+
+/// One part of the difference between two saved states
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum Change {
+    Name {
+        name: String,
+    },
+    CurrentSession {
+        session: Option<Session>,
+    },
+    CurrentBreak {
+        #[serde(rename = "break")]
+        break_period: Option<Break>,
+    },
+    /// Adds a finished session, or replaces the one with the same ID
+    PutSession {
+        session: Session,
+    },
+    /// Deletes the finished session with this ID
+    RemoveSession {
+        id: Uuid,
+    },
+    /// Replaces `removed` finished sessions starting at `index` with `added`.
+    /// Only found in logs written before changes were keyed by session ID.
+    Sessions {
+        index: usize,
+        removed: usize,
+        added: Vec<Session>,
+    },
+}
+
+/// A line of `events.jsonl`: what a command changed and when it was recorded
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Event {
+    pub at: DateTime<Local>,
+    /// What happened: init, start, stop, break, resume, add or edit
+    pub action: String,
+    pub changes: Vec<Change>,
+}
+
+impl Event {
+    /// Describes how `new` differs from `old`, or `None` if nothing changed
+    pub fn between(old: Option<&Project>, new: &Project, at: DateTime<Local>) -> Option<Self> {
+        let empty = Project::new(String::new());
+        let previous = old.unwrap_or(&empty);
+        let mut changes = Vec::new();
+
+        if previous.name != new.name {
+            changes.push(Change::Name {
+                name: new.name.clone(),
+            });
+        }
+        if !same(&previous.current_session, &new.current_session) {
+            changes.push(Change::CurrentSession {
+                session: new.current_session.clone(),
+            });
+        }
+        if !same(&previous.current_break, &new.current_break) {
+            changes.push(Change::CurrentBreak {
+                break_period: new.current_break.clone(),
+            });
+        }
+        changes.extend(Self::session_changes(&previous.sessions, &new.sessions));
+
+        if changes.is_empty() {
+            return None;
+        }
+
+        let action = Self::action(old, new);
+        Some(Self {
+            at,
+            action: action.to_string(),
+            changes,
+        })
+    }
+
+    /// The finished sessions added, changed or removed, keyed by ID so that
+    /// logs written on different machines can be replayed together
+    fn session_changes(old: &[Session], new: &[Session]) -> Vec<Change> {
+        let old_by_id: HashMap<Uuid, &Session> = old.iter().map(|s| (s.id, s)).collect();
+        let new_ids: HashSet<Uuid> = new.iter().map(|s| s.id).collect();
+
+        let mut changes: Vec<Change> = old
+            .iter()
+            .filter(|s| !new_ids.contains(&s.id))
+            .map(|s| Change::RemoveSession { id: s.id })
+            .collect();
+        changes.extend(
+            new.iter()
+                .filter(|s| !old_by_id.get(&s.id).is_some_and(|old| same(old, s)))
+                .map(|s| Change::PutSession { session: s.clone() }),
+        );
+        changes
+    }
+
+    fn action(old: Option<&Project>, new: &Project) -> &'static str {
+        let Some(old) = old else {
+            return "init";
+        };
+
+        match (
+            old.current_session.is_some(),
+            new.current_session.is_some(),
+            old.current_break.is_some(),
+            new.current_break.is_some(),
+        ) {
+            (false, true, _, _) => "start",
+            (true, false, _, _) => "stop",
+            (true, true, false, true) => "break",
+            (true, true, true, false) => "resume",
+            _ if new.sessions.len() > old.sessions.len() => "add",
+            _ => "edit",
+        }
+    }
+
+    pub fn apply(&self, project: &mut Project) -> Result<()> {
+        for change in &self.changes {
+            change.apply(project).with_context(|| {
+                format!(
+                    "Event from {} does not match the replayed state",
+                    self.at.format("%Y-%m-%d %H:%M:%S")
+                )
+            })?;
+        }
+        Ok(())
+    }
+}
+
+impl Change {
+    pub fn apply(&self, project: &mut Project) -> Result<()> {
+        match self {
+            Change::Name { name } => project.name = name.clone(),
+            Change::CurrentSession { session } => project.current_session = session.clone(),
+            Change::CurrentBreak { break_period } => project.current_break = break_period.clone(),
+            Change::PutSession { session } => {
+                match project.sessions.iter().position(|s| s.id == session.id) {
+                    Some(index) => project.sessions[index] = session.clone(),
+                    None => {
+                        let index = project
+                            .sessions
+                            .partition_point(|s| s.start <= session.start);
+                        project.sessions.insert(index, session.clone());
+                    }
+                }
+            }
+            // Already gone if both sides of a merged log removed it
+            Change::RemoveSession { id } => project.sessions.retain(|s| s.id != *id),
+            Change::Sessions {
+                index,
+                removed,
+                added,
+            } => {
+                if index + removed > project.sessions.len() {
+                    return Err(anyhow!(
+                        "Only {} sessions recorded, cannot replace {}..{}",
+                        project.sessions.len(),
+                        index,
+                        index + removed
+                    ));
+                }
+                project
+                    .sessions
+                    .splice(*index..index + removed, added.iter().cloned());
+            }
+        }
+        Ok(())
+    }
+}
+
+fn same<T: Serialize>(a: &T, b: &T) -> bool {
+    serde_json::to_value(a).ok() == serde_json::to_value(b).ok()
+}
+
+/// Keeps every change as a line in `.clockme/events.jsonl` and rebuilds the
+/// project by replaying them.
+///
+/// `events.snapshot.json` holds the state after the first `events` lines so
+/// that only the newer ones need to be replayed; the log itself is never
+/// rewritten. A snapshot that cannot be read, or whose lines no longer match
+/// the log (after merging logs from another machine, say), is ignored and the
+/// whole log replayed.
+pub struct EventLogRepository {
+    data_dir: PathBuf,
+    log_file: PathBuf,
+    snapshot_file: PathBuf,
+    clock: Box<dyn Clock>,
+    /// Report skipped log lines on stderr
+    warnings: bool,
+}
+
+/// The replayed state saved in `events.snapshot.json`
+struct Snapshot {
+    project: Project,
+    /// Number of log lines replayed into `project`
+    events: usize,
+    /// Fingerprint of those lines, see [`EventLogRepository::digest`]
+    digest: Option<String>,
+}
+
+impl EventLogRepository {
+    pub fn new(data_dir: PathBuf) -> Self {
+        let log_file = data_dir.join("events.jsonl");
+        let snapshot_file = data_dir.join("events.snapshot.json");
+        Self {
+            data_dir,
+            log_file,
+            snapshot_file,
+            clock: Box::new(SystemClock),
+            warnings: true,
+        }
+    }
+
+    /// Uses `clock` to timestamp events
+    pub fn with_clock(mut self, clock: Box<dyn Clock>) -> Self {
+        self.clock = clock;
+        self
+    }
+
+    /// Skips unreadable log lines silently, for callers such as the prompt
+    /// that must not print anything of their own
+    pub fn without_warnings(mut self) -> Self {
+        self.warnings = false;
+        self
+    }
+
+    fn digest(lines: &[&str]) -> String {
+        Uuid::new_v5(&Uuid::NAMESPACE_OID, lines.join("\n").as_bytes()).to_string()
+    }
+
+    /// Every event in the log, oldest first
+    pub fn events(&self) -> Result<Vec<Event>> {
+        let content = self.read_log()?;
+        Ok(self.parse_lines(content.lines()))
+    }
+
+    fn read_log(&self) -> Result<String> {
+        let mut content = String::new();
+        if self.log_file.exists() {
+            File::open(&self.log_file)
+                .and_then(|mut f| f.read_to_string(&mut content))
+                .context("Failed to read .clockme/events.jsonl")?;
+        }
+        Ok(content)
+    }
+
+    /// Parses event lines, skipping ones cut short by a crash mid-append
+    fn parse_lines<'a>(&self, lines: impl Iterator<Item = &'a str>) -> Vec<Event> {
+        lines
+            .filter(|line| !line.trim().is_empty())
+            .filter_map(|line| match serde_json::from_str(line) {
+                Ok(event) => Some(event),
+                Err(e) => {
+                    if self.warnings {
+                        eprintln!("⚠ Skipping unreadable line in events.jsonl: {}", e);
+                    }
+                    None
+                }
+            })
+            .collect()
+    }
+
+    fn read_snapshot(&self) -> Result<Option<Snapshot>> {
+        if !self.snapshot_file.exists() {
+            return Ok(None);
+        }
+
+        let content = fs::read_to_string(&self.snapshot_file)
+            .context("Failed to read .clockme/events.snapshot.json")?;
+        let mut doc = match serde_json::from_str(&content)? {
+            Value::Object(doc) => doc,
+            _ => return Err(anyhow!("Event snapshot is not a JSON object")),
+        };
+        let events = doc
+            .remove("events")
+            .and_then(|v| v.as_u64())
+            .ok_or_else(|| anyhow!("Event snapshot is missing its event count"))?;
+        let digest = doc
+            .remove("digest")
+            .and_then(|v| v.as_str().map(str::to_string));
+        let (project, _) = migrations::upgrade(doc)?;
+
+        Ok(Some(Snapshot {
+            project,
+            events: events as usize,
+            digest,
+        }))
+    }
+
+    /// Replays the log; returns the project, the number of log lines and how
+    /// many of them a usable snapshot covered
+    fn replay(&self) -> Result<(Option<Project>, usize, usize)> {
+        let content = self.read_log()?;
+        let lines: Vec<&str> = content.lines().collect();
+
+        let (mut project, covered) = match self.read_snapshot() {
+            Ok(Some(snapshot))
+                if snapshot.events <= lines.len()
+                    && snapshot.digest.as_deref()
+                        == Some(Self::digest(&lines[..snapshot.events]).as_str()) =>
+            {
+                (Some(snapshot.project), snapshot.events)
+            }
+            _ => (None, 0),
+        };
+
+        for event in self.parse_lines(lines[covered..].iter().copied()) {
+            let project = project.get_or_insert_with(|| Project::new(String::new()));
+            // Sessions from logs written before IDs existed get the same
+            // derived IDs as on load, so keyed changes find them
+            project.backfill_ids();
+            event.apply(project)?;
+        }
+
+        Ok((project, lines.len(), covered))
+    }
+
+    fn append(&self, event: &Event) -> Result<()> {
+        let line = serde_json::to_string(event).context("Failed to serialize event")?;
+
+        // A line torn by a crash must not swallow the next event
+        let needs_newline = fs::read(&self.log_file)
+            .map(|bytes| bytes.last().is_some_and(|&b| b != b'\n'))
+            .unwrap_or(false);
+
+        let mut file = OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(&self.log_file)
+            .context("Failed to open .clockme/events.jsonl")?;
+        let prefix = if needs_newline { "\n" } else { "" };
+        file.write_all(format!("{}{}\n", prefix, line).as_bytes())
+            .and_then(|_| file.sync_all())
+            .context("Failed to append to .clockme/events.jsonl")?;
+
+        Ok(())
+    }
+
+    fn write_snapshot(&self, project: &Project, lines: &[&str]) -> Result<()> {
+        #[derive(Serialize)]
+        struct Stored<'a> {
+            events: usize,
+            digest: String,
+            #[serde(flatten)]
+            project: VersionedProject<'a>,
+        }
+
+        let json = serde_json::to_string_pretty(&Stored {
+            events: lines.len(),
+            digest: Self::digest(lines),
+            project: VersionedProject::new(project),
+        })
+        .context("Failed to serialize event snapshot")?;
+
+        let temp_file = self.data_dir.join("events.snapshot.json.tmp");
+        let mut file = File::create(&temp_file).context("Failed to write event snapshot")?;
+        file.write_all(json.as_bytes())
+            .and_then(|_| file.sync_all())
+            .context("Failed to write event snapshot")?;
+        fs::rename(&temp_file, &self.snapshot_file).context("Failed to write event snapshot")?;
+
+        Ok(())
+    }
+}
+
+impl Repository for EventLogRepository {
+    fn load(&self) -> Result<Project> {
+        let (project, _, _) = self.replay()?;
+        let mut project = project.ok_or_else(|| {
+            anyhow!("Failed to read project data. Has the project been initialized?")
+        })?;
+        project.backfill_ids();
+        Ok(project)
+    }
+
+    fn save(&self, project: &Project) -> Result<()> {
+        if !self.data_dir.exists() {
+            fs::create_dir_all(&self.data_dir).context("Failed to create .clockme directory")?;
+        }
+
+        // A snapshot without its log is left over from an earlier migration
+        if !self.log_file.exists() && self.snapshot_file.exists() {
+            fs::remove_file(&self.snapshot_file)
+                .context("Failed to remove stale event snapshot")?;
+        }
+
+        let (mut previous, lines, covered) = self.replay()?;
+        if let Some(ref mut previous) = previous {
+            previous.backfill_ids();
+        }
+        let Some(event) = Event::between(previous.as_ref(), project, self.clock.now()) else {
+            return Ok(());
+        };
+        self.append(&event)?;
+
+        if lines + 1 - covered >= SNAPSHOT_INTERVAL {
+            let content = self.read_log()?;
+            let lines: Vec<&str> = content.lines().collect();
+            self.write_snapshot(project, &lines)?;
+        }
+
+        Ok(())
+    }
+
+    fn lock(&self) -> Result<RepositoryLock> {
+        RepositoryLock::acquire(&self.data_dir, LOCK_TIMEOUT)
+    }
+
+    fn create_and_lock(&self) -> Result<RepositoryLock> {
+        RepositoryLock::create(&self.data_dir, LOCK_TIMEOUT)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::clock::MockClock;
+    use chrono::{Duration, TimeZone};
+    use serde_json::json;
+    use tempfile::TempDir;
+
+    fn at(h: u32, m: u32) -> DateTime<Local> {
+        Local.with_ymd_and_hms(2025, 10, 13, h, m, 0).unwrap()
+    }
+
+    fn assert_same(a: &Project, b: &Project) {
+        assert_eq!(
+            serde_json::to_value(a).unwrap(),
+            serde_json::to_value(b).unwrap()
+        );
+    }
+
+    fn repository(data_dir: PathBuf) -> EventLogRepository {
+        let mut clock = MockClock::new();
+        clock.expect_now().returning(|| at(18, 0));
+        EventLogRepository::new(data_dir).with_clock(Box::new(clock))
+    }
+
+    #[test]
+    fn test_replay_records_each_action() {
+        let temp_dir = TempDir::new().unwrap();
+        let repo = repository(temp_dir.path().join(".clockme"));
+
+        let mut project = Project::new("test-project".to_string());
+        repo.save(&project).unwrap();
+        project.start_session(at(9, 0));
+        repo.save(&project).unwrap();
+        project.start_break(at(12, 0)).unwrap();
+        repo.save(&project).unwrap();
+        project.end_break(at(12, 30)).unwrap();
+        repo.save(&project).unwrap();
+        project.end_session(at(17, 0)).unwrap();
+        repo.save(&project).unwrap();
+        project.sessions[0].note = Some("API work".to_string());
+        repo.save(&project).unwrap();
+        repo.save(&project).unwrap();
+
+        assert_same(&repo.load().unwrap(), &project);
+
+        let events = repo.events().unwrap();
+        let actions: Vec<&str> = events.iter().map(|e| e.action.as_str()).collect();
+        assert_eq!(
+            actions,
+            ["init", "start", "break", "resume", "stop", "edit"]
+        );
+        assert!(events.iter().all(|e| e.at == at(18, 0)));
+    }
+
+    #[test]
+    fn test_session_changes_are_keyed_by_id() {
+        let session = |h| Session::manual(at(h, 0), at(h, 30));
+        let old = [session(8), session(9), session(10)];
+        let mut edited = old[2].clone();
+        edited.note = Some("review".to_string());
+        let new = [old[0].clone(), session(11), edited];
+
+        let changes = Event::session_changes(&old, &new);
+        assert_eq!(changes.len(), 3);
+        assert!(matches!(changes[0], Change::RemoveSession { id } if id == old[1].id));
+        assert!(matches!(&changes[1], Change::PutSession { session } if session.id == new[1].id));
+        assert!(matches!(&changes[2], Change::PutSession { session } if session.id == old[2].id));
+    }
+
+    #[test]
+    fn test_merged_logs_replay() {
+        let temp_dir = TempDir::new().unwrap();
+        let laptop = repository(temp_dir.path().join("laptop"));
+        let desktop = repository(temp_dir.path().join("desktop"));
+
+        let mut project = Project::new("test-project".to_string());
+        project.sessions.push(Session::manual(at(8, 0), at(9, 0)));
+        laptop.save(&project).unwrap();
+        fs::create_dir_all(&desktop.data_dir).unwrap();
+        fs::copy(&laptop.log_file, &desktop.log_file).unwrap();
+
+        // Each machine adds a session; the desktop also edits the shared one
+        let mut on_laptop = project.clone();
+        on_laptop
+            .add_session(Session::manual(at(13, 0), at(14, 0)), at(18, 0))
+            .unwrap();
+        laptop.save(&on_laptop).unwrap();
+        let mut on_desktop = project.clone();
+        on_desktop.sessions[0].note = Some("standup".to_string());
+        on_desktop
+            .add_session(Session::manual(at(10, 0), at(11, 0)), at(18, 0))
+            .unwrap();
+        desktop.save(&on_desktop).unwrap();
+
+        // What a union merge of the two files looks like
+        let desktop_log = fs::read_to_string(&desktop.log_file).unwrap();
+        let mut merged = fs::read_to_string(&laptop.log_file).unwrap();
+        merged.push_str(desktop_log.lines().last().unwrap());
+        merged.push('\n');
+        fs::write(&laptop.log_file, merged).unwrap();
+
+        let loaded = laptop.load().unwrap();
+        let starts: Vec<_> = loaded.sessions.iter().map(|s| s.start).collect();
+        assert_eq!(starts, [at(8, 0), at(10, 0), at(13, 0)]);
+        assert_eq!(loaded.sessions[0].note.as_deref(), Some("standup"));
+    }
+
+    #[test]
+    fn test_legacy_splice_events_replay() {
+        let temp_dir = TempDir::new().unwrap();
+        let repo = repository(temp_dir.path().join(".clockme"));
+        fs::create_dir_all(&repo.data_dir).unwrap();
+
+        let session = Session::manual(at(8, 0), at(9, 0));
+        let lines = [
+            json!({"at": at(8, 0), "action": "init", "changes": [
+                {"type": "name", "name": "old-project"}]}),
+            json!({"at": at(9, 0), "action": "add", "changes": [
+                {"type": "sessions", "index": 0, "removed": 0, "added": [session]}]}),
+        ];
+        let log: String = lines.iter().map(|line| format!("{}\n", line)).collect();
+        fs::write(&repo.log_file, log).unwrap();
+
+        let mut project = repo.load().unwrap();
+        project.sessions[0].note = Some("standup".to_string());
+        repo.save(&project).unwrap();
+
+        let loaded = repo.load().unwrap();
+        assert_eq!(loaded.sessions.len(), 1);
+        assert_eq!(loaded.sessions[0].note.as_deref(), Some("standup"));
+    }
+
+    #[test]
+    fn test_snapshot_is_used_and_log_kept() {
+        let temp_dir = TempDir::new().unwrap();
+        let repo = repository(temp_dir.path().join(".clockme"));
+
+        let mut project = Project::new("test-project".to_string());
+        repo.save(&project).unwrap();
+        for i in 0..SNAPSHOT_INTERVAL as i64 + 5 {
+            let start = at(8, 0) - Duration::days(i + 1);
+            project
+                .sessions
+                .insert(0, Session::manual(start, start + Duration::hours(1)));
+            repo.save(&project).unwrap();
+        }
+
+        let snapshot = repo.read_snapshot().unwrap().unwrap();
+        assert_eq!(snapshot.events, SNAPSHOT_INTERVAL);
+        assert_eq!(repo.events().unwrap().len(), SNAPSHOT_INTERVAL + 6);
+        assert_same(&repo.load().unwrap(), &project);
+
+        // A snapshot that cannot be read is ignored in favour of the log
+        fs::write(&repo.snapshot_file, "{\"events\": 100, \"name\": ").unwrap();
+        assert_same(&repo.load().unwrap(), &project);
+
+        // So is one that no longer matches the lines it claims to cover
+        let mut replaced = project.clone();
+        replaced.name = "from-snapshot".to_string();
+        let content = fs::read_to_string(&repo.log_file).unwrap();
+        let mut lines: Vec<&str> = content.lines().collect();
+        repo.write_snapshot(&replaced, &lines[..SNAPSHOT_INTERVAL])
+            .unwrap();
+        assert_eq!(repo.load().unwrap().name, "from-snapshot");
+        lines.swap(1, 2);
+        fs::write(&repo.log_file, lines.join("\n") + "\n").unwrap();
+        assert_eq!(repo.load().unwrap().name, "test-project");
+    }
+
+    #[test]
+    fn test_torn_last_line_is_skipped() {
+        let temp_dir = TempDir::new().unwrap();
+        let repo = repository(temp_dir.path().join(".clockme"));
+
+        let mut project = Project::new("test-project".to_string());
+        repo.save(&project).unwrap();
+        let mut file = OpenOptions::new()
+            .append(true)
+            .open(&repo.log_file)
+            .unwrap();
+        file.write_all(b"{\"at\": \"2025-10-1").unwrap();
+
+        project.start_session(at(9, 0));
+        repo.save(&project).unwrap();
+
+        assert_same(&repo.load().unwrap(), &project);
+        assert_eq!(repo.events().unwrap().len(), 2);
+    }
+
+    #[test]
+    fn test_load_without_log() {
+        let temp_dir = TempDir::new().unwrap();
+        let repo = EventLogRepository::new(temp_dir.path().join(".clockme"));
+
+        assert!(repo.load().is_err());
+    }
+}
@@ -0,0 +1,230 @@
+use crate::models::project::Project;
+use crate::repository::events::{Change, Event};
+use anyhow::{anyhow, Context, Result};
+use chrono::{DateTime, Local};
+use serde::{Deserialize, Serialize};
+use std::fs::{self, File};
+use std::io::Write;
+use std::path::{Path, PathBuf};
+
+/// How many commands can be undone
+pub const HISTORY_LIMIT: usize = 50;
+
+/// A state-changing command, stored as the changes that revert and reapply it
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct HistoryEntry {
+    /// When the command ran
+    pub at: DateTime<Local>,
+    /// start, stop, break, resume, add or edit
+    pub action: String,
+    undo: Vec<Change>,
+    redo: Vec<Change>,
+}
+
+/// Undo and redo stacks, most recent command last
+#[derive(Debug, Default, Serialize, Deserialize)]
+pub struct HistoryLog {
+    undo: Vec<HistoryEntry>,
+    redo: Vec<HistoryEntry>,
+}
+
+impl HistoryLog {
+    /// Remembers the change from `before` to `after`; a new command
+    /// discards whatever could have been redone
+    pub fn push(&mut self, before: &Project, after: &Project, at: DateTime<Local>) {
+        let (Some(redo), Some(undo)) = (
+            Event::between(Some(before), after, at),
+            Event::between(Some(after), before, at),
+        ) else {
+            return;
+        };
+
+        self.undo.push(HistoryEntry {
+            at,
+            action: redo.action,
+            undo: undo.changes,
+            redo: redo.changes,
+        });
+        if self.undo.len() > HISTORY_LIMIT {
+            self.undo.drain(..self.undo.len() - HISTORY_LIMIT);
+        }
+        self.redo.clear();
+    }
+
+    /// Reverts up to `steps` commands on `project`, newest first
+    pub fn undo(&mut self, project: &mut Project, steps: usize) -> Result<Vec<HistoryEntry>> {
+        if self.undo.is_empty() {
+            return Err(anyhow!("Nothing to undo"));
+        }
+
+        let mut undone = Vec::new();
+        while undone.len() < steps {
+            let Some(entry) = self.undo.pop() else {
+                break;
+            };
+            Self::apply(project, &entry.undo, &entry)?;
+            self.redo.push(entry.clone());
+            undone.push(entry);
+        }
+        Ok(undone)
+    }
+
+    /// Reapplies up to `steps` undone commands on `project`, oldest first
+    pub fn redo(&mut self, project: &mut Project, steps: usize) -> Result<Vec<HistoryEntry>> {
+        if self.redo.is_empty() {
+            return Err(anyhow!("Nothing to redo"));
+        }
+
+        let mut redone = Vec::new();
+        while redone.len() < steps {
+            let Some(entry) = self.redo.pop() else {
+                break;
+            };
+            Self::apply(project, &entry.redo, &entry)?;
+            self.undo.push(entry.clone());
+            redone.push(entry);
+        }
+        Ok(redone)
+    }
+
+    fn apply(project: &mut Project, changes: &[Change], entry: &HistoryEntry) -> Result<()> {
+        for change in changes {
+            change.apply(project).with_context(|| {
+                format!(
+                    "Project data no longer matches the '{}' from {}",
+                    entry.action,
+                    entry.at.format("%Y-%m-%d %H:%M")
+                )
+            })?;
+        }
+        Ok(())
+    }
+}
+
+/// The undo history of a project, kept in `.clockme/history.json`
+pub struct History {
+    data_dir: PathBuf,
+    file: PathBuf,
+}
+
+impl History {
+    pub fn new(data_dir: &Path) -> Self {
+        Self {
+            data_dir: data_dir.to_path_buf(),
+            file: data_dir.join("history.json"),
+        }
+    }
+
+    pub fn load(&self) -> Result<HistoryLog> {
+        if !self.file.exists() {
+            return Ok(HistoryLog::default());
+        }
+
+        let content =
+            fs::read_to_string(&self.file).context("Failed to read .clockme/history.json")?;
+        serde_json::from_str(&content).context("Failed to parse .clockme/history.json")
+    }
+
+    pub fn save(&self, log: &HistoryLog) -> Result<()> {
+        let json = serde_json::to_string(log).context("Failed to serialize undo history")?;
+
+        let temp_file = self.data_dir.join("history.json.tmp");
+        let mut file = File::create(&temp_file).context("Failed to write undo history")?;
+        file.write_all(json.as_bytes())
+            .and_then(|_| file.sync_all())
+            .context("Failed to write undo history")?;
+        fs::rename(&temp_file, &self.file).context("Failed to write undo history")?;
+
+        Ok(())
+    }
+
+    /// Adds a command to the stored history
+    pub fn record(&self, before: &Project, after: &Project, at: DateTime<Local>) -> Result<()> {
+        let mut log = self.load()?;
+        log.push(before, after, at);
+        self.save(&log)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::TimeZone;
+    use tempfile::TempDir;
+
+    fn at(h: u32, m: u32) -> DateTime<Local> {
+        Local.with_ymd_and_hms(2025, 10, 13, h, m, 0).unwrap()
+    }
+
+    fn to_value(project: &Project) -> serde_json::Value {
+        serde_json::to_value(project).unwrap()
+    }
+
+    /// Clocked in at 9:00, on break at 12:00, clocked out at 17:00
+    fn states() -> Vec<Project> {
+        let mut project = Project::new("test-project".to_string());
+        let mut states = vec![project.clone()];
+        project.start_session(at(9, 0));
+        states.push(project.clone());
+        project.start_break(at(12, 0)).unwrap();
+        states.push(project.clone());
+        project.end_session(at(17, 0)).unwrap();
+        states.push(project.clone());
+        states
+    }
+
+    #[test]
+    fn test_undo_and_redo() {
+        let states = states();
+        let mut log = HistoryLog::default();
+        for pair in states.windows(2) {
+            log.push(&pair[0], &pair[1], at(17, 0));
+        }
+
+        let mut project = states[3].clone();
+        let undone = log.undo(&mut project, 2).unwrap();
+        assert_eq!(undone[0].action, "stop");
+        assert_eq!(undone[1].action, "break");
+        assert_eq!(to_value(&project), to_value(&states[1]));
+
+        let redone = log.redo(&mut project, 1).unwrap();
+        assert_eq!(redone[0].action, "break");
+        assert_eq!(to_value(&project), to_value(&states[2]));
+
+        log.undo(&mut project, 10).unwrap();
+        assert_eq!(to_value(&project), to_value(&states[0]));
+        assert!(log.undo(&mut project, 1).is_err());
+    }
+
+    #[test]
+    fn test_new_command_clears_redo() {
+        let states = states();
+        let mut log = HistoryLog::default();
+        log.push(&states[0], &states[1], at(9, 0));
+
+        let mut project = states[1].clone();
+        log.undo(&mut project, 1).unwrap();
+        log.push(&states[0], &states[1], at(9, 5));
+
+        assert!(log.redo(&mut project, 1).is_err());
+    }
+
+    #[test]
+    fn test_history_is_bounded_and_persisted() {
+        let temp_dir = TempDir::new().unwrap();
+        let history = History::new(temp_dir.path());
+
+        let mut before = Project::new("test-project".to_string());
+        for i in 0..HISTORY_LIMIT + 5 {
+            let mut after = before.clone();
+            after.name = format!("name-{}", i);
+            history.record(&before, &after, at(9, 0)).unwrap();
+            before = after;
+        }
+        history.record(&before, &before, at(9, 0)).unwrap();
+
+        let log = history.load().unwrap();
+        assert_eq!(log.undo.len(), HISTORY_LIMIT);
+        assert!(log.redo.is_empty());
+    }
+}
@@ -0,0 +1,105 @@
+use anyhow::{anyhow, Context, Result};
+use std::fs::{self, File, OpenOptions, TryLockError};
+use std::path::Path;
+use std::thread;
+use std::time::{Duration, Instant};
+
+/// How long a command waits for another clock-me to finish writing
+pub const LOCK_TIMEOUT: Duration = Duration::from_secs(5);
+
+const RETRY_INTERVAL: Duration = Duration::from_millis(25);
+
+/// Exclusive advisory lock on `.clockme/lock`, released when dropped.
+///
+/// Held for a whole load → mutate → save cycle so concurrent invocations
+/// (shell prompt, editor plugin, manual commands) can't overwrite each other.
+#[derive(Debug)]
+pub struct RepositoryLock {
+    _file: Option<File>,
+}
+
+impl RepositoryLock {
+    /// A guard that holds nothing, for stores without a lock file
+    pub fn none() -> Self {
+        Self { _file: None }
+    }
+
+    /// Creates `data_dir` if needed and waits up to `timeout` for its lock, for
+    /// `init`, which must not race another `init` for the same directory
+    pub fn create(data_dir: &Path, timeout: Duration) -> Result<Self> {
+        fs::create_dir_all(data_dir).context("Failed to create .clockme directory")?;
+        Self::acquire(data_dir, timeout)
+    }
+
+    /// Waits up to `timeout` for the lock of the project in `data_dir`.
+    ///
+    /// A missing directory means there is no project to protect yet, so no lock
+    /// file is created (which would otherwise leave a stray `.clockme` behind).
+    pub fn acquire(data_dir: &Path, timeout: Duration) -> Result<Self> {
+        if !data_dir.is_dir() {
+            return Ok(Self::none());
+        }
+
+        let file = OpenOptions::new()
+            .create(true)
+            .truncate(false)
+            .write(true)
+            .open(data_dir.join("lock"))
+            .context("Failed to open .clockme/lock")?;
+
+        let deadline = Instant::now() + timeout;
+        loop {
+            match file.try_lock() {
+                Ok(()) => return Ok(Self { _file: Some(file) }),
+                Err(TryLockError::WouldBlock) if Instant::now() < deadline => {
+                    thread::sleep(RETRY_INTERVAL);
+                }
+                Err(TryLockError::WouldBlock) => {
+                    return Err(anyhow!(
+                        "Another clock-me is writing to this project. Try again in a moment."
+                    ));
+                }
+                Err(TryLockError::Error(e)) => {
+                    return Err(e).context("Failed to lock .clockme/lock");
+                }
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    #[test]
+    fn test_second_lock_times_out() {
+        let temp_dir = TempDir::new().unwrap();
+
+        let _held = RepositoryLock::acquire(temp_dir.path(), LOCK_TIMEOUT).unwrap();
+        let err = RepositoryLock::acquire(temp_dir.path(), Duration::from_millis(100)).unwrap_err();
+
+        assert!(err.to_string().contains("Another clock-me is writing"));
+    }
+
+    #[test]
+    fn test_lock_released_on_drop() {
+        let temp_dir = TempDir::new().unwrap();
+
+        drop(RepositoryLock::acquire(temp_dir.path(), LOCK_TIMEOUT).unwrap());
+
+        assert!(RepositoryLock::acquire(temp_dir.path(), Duration::ZERO).is_ok());
+    }
+
+    #[test]
+    fn test_missing_directory_is_not_created() {
+        let temp_dir = TempDir::new().unwrap();
+        let data_dir = temp_dir.path().join(".clockme");
+
+        assert!(RepositoryLock::acquire(&data_dir, LOCK_TIMEOUT).is_ok());
+        assert!(!data_dir.exists());
+
+        let _held = RepositoryLock::create(&data_dir, LOCK_TIMEOUT).unwrap();
+        assert!(RepositoryLock::acquire(&data_dir, Duration::ZERO).is_err());
+    }
+}
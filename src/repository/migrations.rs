@@ -0,0 +1,227 @@
+use crate::models::project::Project;
+use anyhow::{anyhow, Result};
+use serde::Serialize;
+use serde_json::{Map, Value};
+
+/// Version written to `data.json` by this build
+pub const CURRENT_VERSION: u32 = 4;
+
+/// One upgrade step per version: `STEPS[n]` turns a version `n` document into `n + 1`
+const STEPS: [fn(&mut Map<String, Value>); CURRENT_VERSION as usize] =
+    [legacy_to_v1, explicit_defaults, record_ids, git_activity];
+
+/// How the project is written to disk, with the schema version up front
+#[derive(Serialize)]
+pub struct VersionedProject<'a> {
+    pub schema_version: u32,
+    #[serde(flatten)]
+    pub project: &'a Project,
+}
+
+impl<'a> VersionedProject<'a> {
+    pub fn new(project: &'a Project) -> Self {
+        Self {
+            schema_version: CURRENT_VERSION,
+            project,
+        }
+    }
+}
+
+/// Version of a stored document.
+///
+/// Files from before versioning have no `schema_version`: the original layout
+/// (`project_name`/`session_list`, see Docs.md) is 0, everything later is 1.
+pub fn version_of(doc: &Map<String, Value>) -> Result<u32> {
+    match doc.get("schema_version") {
+        Some(version) => version
+            .as_u64()
+            .map(|v| v as u32)
+            .ok_or_else(|| anyhow!("Invalid schema_version {}", version)),
+        None if doc.contains_key("project_name") || doc.contains_key("session_list") => Ok(0),
+        None => Ok(1),
+    }
+}
+
+/// Version of a stored document, if it is a readable JSON object
+pub fn stored_version(content: &str) -> Option<u32> {
+    match serde_json::from_str(content).ok()? {
+        Value::Object(doc) => version_of(&doc).ok(),
+        _ => None,
+    }
+}
+
+/// Upgrades a stored document step by step to the current version.
+///
+/// Returns the project and the version the document was stored with.
+pub fn load(content: &str) -> Result<(Project, u32)> {
+    match serde_json::from_str(content)? {
+        Value::Object(doc) => upgrade(doc),
+        _ => Err(anyhow!("Project data is not a JSON object")),
+    }
+}
+
+/// Same as [`load`], for a document that is already parsed
+pub fn upgrade(mut doc: Map<String, Value>) -> Result<(Project, u32)> {
+    let version = version_of(&doc)?;
+    if version > CURRENT_VERSION {
+        return Err(anyhow!(
+            "Project data was written by a newer version of clock-me (schema {}, this build supports up to {}). Please upgrade clock-me.",
+            version,
+            CURRENT_VERSION
+        ));
+    }
+
+    for step in &STEPS[version as usize..] {
+        step(&mut doc);
+    }
+    doc.remove("schema_version");
+
+    let mut project: Project = serde_json::from_value(Value::Object(doc))?;
+    project.backfill_ids();
+    Ok((project, version))
+}
+
+/// 0 → 1: the layout from the first design (`project_name`, `session_list`
+/// with a cached `duration_minutes`)
+fn legacy_to_v1(doc: &mut Map<String, Value>) {
+    if let Some(name) = doc.remove("project_name") {
+        doc.insert("name".to_string(), name);
+    }
+    let mut sessions = doc
+        .remove("session_list")
+        .unwrap_or(Value::Array(Vec::new()));
+    if let Some(sessions) = sessions.as_array_mut() {
+        for session in sessions.iter_mut().filter_map(Value::as_object_mut) {
+            session.remove("duration_minutes");
+        }
+    }
+    doc.insert("sessions".to_string(), sessions);
+    doc.entry("current_session").or_insert(Value::Null);
+}
+
+/// 1 → 2: spell out every field older builds left to `#[serde(default)]`
+fn explicit_defaults(doc: &mut Map<String, Value>) {
+    let current_break = doc.entry("current_break").or_insert(Value::Null);
+    if let Some(current_break) = current_break.as_object_mut() {
+        current_break.entry("note").or_insert(Value::Null);
+    }
+
+    if let Some(session) = doc
+        .get_mut("current_session")
+        .and_then(Value::as_object_mut)
+    {
+        fill_session(session);
+    }
+    if let Some(sessions) = doc.get_mut("sessions").and_then(Value::as_array_mut) {
+        sessions
+            .iter_mut()
+            .filter_map(Value::as_object_mut)
+            .for_each(fill_session);
+    }
+}
+
+/// 2 → 3: sessions and breaks carry an `id`. Nothing to rewrite here: records
+/// without one get a derived ID from `Project::backfill_ids` after parsing.
+fn record_ids(_doc: &mut Map<String, Value>) {}
+
+/// 3 → 4: sessions list the git commits and branch switches made during them
+fn git_activity(doc: &mut Map<String, Value>) {
+    let add_lists = |session: &mut Map<String, Value>| {
+        session.entry("commits").or_insert(Value::Array(Vec::new()));
+        session
+            .entry("checkouts")
+            .or_insert(Value::Array(Vec::new()));
+    };
+    if let Some(session) = doc
+        .get_mut("current_session")
+        .and_then(Value::as_object_mut)
+    {
+        add_lists(session);
+    }
+    if let Some(sessions) = doc.get_mut("sessions").and_then(Value::as_array_mut) {
+        sessions
+            .iter_mut()
+            .filter_map(Value::as_object_mut)
+            .for_each(add_lists);
+    }
+}
+
+fn fill_session(session: &mut Map<String, Value>) {
+    session.entry("end").or_insert(Value::Null);
+    session.entry("origin").or_insert("clocked".into());
+    session.entry("note").or_insert(Value::Null);
+    session.entry("tags").or_insert(Value::Array(Vec::new()));
+
+    let breaks = session.entry("breaks").or_insert(Value::Array(Vec::new()));
+    if let Some(breaks) = breaks.as_array_mut() {
+        for break_period in breaks.iter_mut().filter_map(Value::as_object_mut) {
+            break_period.entry("note").or_insert(Value::Null);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_load_legacy_layout() {
+        let legacy = r#"{
+            "project_name": "my-app",
+            "current_session": {"start": "2025-10-13T09:00:00+02:00"},
+            "session_list": [
+                {"start": "2025-10-12T09:00:00+02:00", "end": "2025-10-12T11:30:00+02:00", "duration_minutes": 150.0}
+            ]
+        }"#;
+
+        let (project, version) = load(legacy).unwrap();
+
+        assert_eq!(version, 0);
+        assert_eq!(project.name, "my-app");
+        assert_eq!(project.sessions.len(), 1);
+        assert_eq!(
+            project.sessions[0].duration(),
+            Some(chrono::Duration::minutes(150))
+        );
+        assert!(project.current_session.unwrap().is_active());
+        assert!(project.current_break.is_none());
+    }
+
+    #[test]
+    fn test_load_unversioned_layout() {
+        let v1 = r#"{
+            "name": "my-app",
+            "current_session": null,
+            "sessions": [{"start": "2025-10-12T09:00:00+02:00", "end": "2025-10-12T11:00:00+02:00"}]
+        }"#;
+
+        let (project, version) = load(v1).unwrap();
+
+        assert_eq!(version, 1);
+        assert!(!project.sessions[0].id.is_nil());
+        assert!(project.sessions[0].breaks.is_empty());
+        assert!(project.sessions[0].tags.is_empty());
+    }
+
+    #[test]
+    fn test_current_version_round_trip() {
+        let project = Project::new("my-app".to_string());
+        let json = serde_json::to_string(&VersionedProject::new(&project)).unwrap();
+
+        assert!(json.starts_with(&format!("{{\"schema_version\":{}", CURRENT_VERSION)));
+        let (loaded, version) = load(&json).unwrap();
+        assert_eq!(version, CURRENT_VERSION);
+        assert_eq!(loaded.name, "my-app");
+    }
+
+    #[test]
+    fn test_refuses_newer_version() {
+        let newer = format!(
+            r#"{{"schema_version": {}, "name": "my-app", "current_session": null, "sessions": []}}"#,
+            CURRENT_VERSION + 1
+        );
+
+        let err = load(&newer).unwrap_err();
+        assert!(err.to_string().contains("newer version of clock-me"));
+    }
+}
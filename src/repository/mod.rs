@@ -0,0 +1,836 @@
+pub mod events;
+pub mod history;
+pub mod lock;
+pub mod migrations;
+pub mod registry;
+pub mod sqlite;
+
+use crate::models::project::Project;
+use anyhow::{anyhow, Context, Result};
+use chrono::{DateTime, Local};
+use events::EventLogRepository;
+use lock::{RepositoryLock, LOCK_TIMEOUT};
+use serde::{Deserialize, Serialize};
+use sqlite::SqliteRepository;
+use std::env;
+use std::fs::{self, File};
+use std::io::Write;
+use std::path::{Path, PathBuf};
+
+#[cfg(test)]
+use mockall::automock;
+
+/// Repository trait for persistence abstraction
+#[cfg_attr(test, automock)]
+pub trait Repository: Send + Sync {
+    fn load(&self) -> Result<Project>;
+    /// Loads the stored data as it is, for `clock-me doctor`: damaged data is
+    /// an error instead of being replaced by a backup
+    fn load_strict(&self) -> Result<Project> {
+        self.load()
+    }
+    fn save(&self, project: &Project) -> Result<()>;
+    /// Blocks other processes from writing until the returned guard is dropped
+    fn lock(&self) -> Result<RepositoryLock>;
+    /// Like [`Repository::lock`], creating the data directory first, for a
+    /// project that does not exist yet
+    fn create_and_lock(&self) -> Result<RepositoryLock>;
+}
+
+/// How many previous versions of `data.json` are kept in `.clockme/backups`
+const BACKUP_COUNT: usize = 5;
+
+/// How many numbered names are tried for a backup or a migrated store before
+/// giving up
+const MAX_NUMBERED_NAMES: usize = 100;
+
+pub struct FileRepository {
+    data_dir: PathBuf,
+    data_file: PathBuf,
+}
+
+impl Default for FileRepository {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl FileRepository {
+    pub fn new() -> Self {
+        // Try to find .clockme by walking up the directory tree
+        let (data_dir, data_file) = Self::find_clockme_dir().unwrap_or_else(|| {
+            // If not found, use current directory (for init command)
+            let data_dir = PathBuf::from(".clockme");
+            let data_file = data_dir.join("data.json");
+            (data_dir, data_file)
+        });
+
+        Self {
+            data_dir,
+            data_file,
+        }
+    }
+
+    pub fn with_data_dir(data_dir: PathBuf) -> Self {
+        let data_file = data_dir.join("data.json");
+        Self {
+            data_dir,
+            data_file,
+        }
+    }
+
+    fn find_clockme_dir() -> Option<(PathBuf, PathBuf)> {
+        let current_dir = env::current_dir().ok()?;
+        let mut path = current_dir.as_path();
+
+        loop {
+            let clockme_dir = path.join(".clockme");
+            if clockme_dir.exists() && clockme_dir.is_dir() {
+                let data_file = clockme_dir.join("data.json");
+                return Some((clockme_dir, data_file));
+            }
+            path = path.parent()?;
+        }
+    }
+
+    pub fn get_project_root(&self) -> Option<PathBuf> {
+        self.data_dir.parent().map(|p| p.to_path_buf())
+    }
+
+    fn ensure_directory_exists(&self) -> Result<()> {
+        if !self.data_dir.exists() {
+            fs::create_dir_all(&self.data_dir).context("Failed to create .clockme directory")?;
+        }
+        Ok(())
+    }
+
+    fn backup_file(&self, n: usize) -> PathBuf {
+        self.data_dir
+            .join("backups")
+            .join(format!("data.json.{}", n))
+    }
+
+    /// Reads a data file of any supported schema version, upgrading it in memory
+    fn read_project(path: &Path) -> Result<(Project, u32)> {
+        let content = fs::read_to_string(path)?;
+        migrations::load(&content)
+    }
+
+    /// Keeps a copy of a file written with an older schema before the next
+    /// save replaces it with the current layout
+    fn preserve_original(&self, version: u32) -> Result<()> {
+        let backup = self
+            .data_dir
+            .join("backups")
+            .join(format!("data.json.v{}", version));
+        if backup.exists() {
+            return Ok(());
+        }
+
+        fs::create_dir_all(self.data_dir.join("backups"))
+            .context("Failed to create .clockme/backups directory")?;
+        fs::copy(&self.data_file, &backup).context("Failed to back up project data")?;
+        eprintln!(
+            "Upgraded project data from schema version {} to {}; the original is kept at {}",
+            version,
+            migrations::CURRENT_VERSION,
+            backup.display()
+        );
+        Ok(())
+    }
+
+    /// Shifts `data.json.N` to `N + 1` and copies the current file to
+    /// `data.json.1`, dropping the oldest snapshot. A current file that does
+    /// not parse or is already the newest backup is not worth a slot.
+    fn rotate_backups(&self) -> Result<()> {
+        let Ok(current) = fs::read_to_string(&self.data_file) else {
+            return Ok(());
+        };
+        if migrations::load(&current).is_err() {
+            return Ok(());
+        }
+        if fs::read_to_string(self.backup_file(1)).is_ok_and(|newest| newest == current) {
+            return Ok(());
+        }
+
+        fs::create_dir_all(self.data_dir.join("backups"))
+            .context("Failed to create .clockme/backups directory")?;
+
+        for n in (1..BACKUP_COUNT).rev() {
+            let from = self.backup_file(n);
+            if from.exists() {
+                fs::rename(&from, self.backup_file(n + 1)).context("Failed to rotate backups")?;
+            }
+        }
+        fs::copy(&self.data_file, self.backup_file(1)).context("Failed to back up project data")?;
+
+        Ok(())
+    }
+
+    /// Writes to a temporary file, syncs it and renames it over `data.json`,
+    /// so the data file is always either the old or the new version
+    fn write_atomically(&self, content: &str) -> Result<()> {
+        let temp_file = self.data_dir.join("data.json.tmp");
+
+        let mut file = File::create(&temp_file).context("Failed to write project data")?;
+        file.write_all(content.as_bytes())
+            .and_then(|_| file.sync_all())
+            .context("Failed to write project data")?;
+        drop(file);
+
+        fs::rename(&temp_file, &self.data_file).context("Failed to replace project data")?;
+
+        // Persist the rename itself; not every platform can open a directory
+        if let Ok(dir) = File::open(&self.data_dir) {
+            let _ = dir.sync_all();
+        }
+
+        Ok(())
+    }
+}
+
+impl Repository for FileRepository {
+    fn load(&self) -> Result<Project> {
+        if !self.data_file.exists() {
+            return Err(anyhow!(
+                "Failed to read project data. Has the project been initialized?"
+            ));
+        }
+
+        let error = match Self::read_project(&self.data_file) {
+            Ok((project, version)) => {
+                if version < migrations::CURRENT_VERSION {
+                    self.preserve_original(version)?;
+                }
+                return Ok(project);
+            }
+            Err(e) => e,
+        };
+        // A file from a newer build is intact; falling back would lose its changes
+        let stored_version = fs::read_to_string(&self.data_file)
+            .ok()
+            .and_then(|content| migrations::stored_version(&content));
+        if stored_version.is_some_and(|v| v > migrations::CURRENT_VERSION) {
+            return Err(error);
+        }
+
+        for n in 1..=BACKUP_COUNT {
+            let backup = self.backup_file(n);
+            if let Ok((project, _)) = Self::read_project(&backup) {
+                eprintln!(
+                    "⚠ Could not read {} ({}). Using backup {} instead; the next change will overwrite the damaged file.",
+                    self.data_file.display(),
+                    error,
+                    backup.display()
+                );
+                return Ok(project);
+            }
+        }
+
+        Err(error.context("Failed to parse project data and no valid backup was found"))
+    }
+
+    fn load_strict(&self) -> Result<Project> {
+        let (project, _) = Self::read_project(&self.data_file)
+            .with_context(|| format!("Failed to read {}", self.data_file.display()))?;
+        Ok(project)
+    }
+
+    fn save(&self, project: &Project) -> Result<()> {
+        self.ensure_directory_exists()?;
+
+        let json = serde_json::to_string_pretty(&migrations::VersionedProject::new(project))
+            .context("Failed to serialize project data")?;
+
+        // Saving what is already stored would only push out a backup
+        if fs::read_to_string(&self.data_file).is_ok_and(|current| current == json) {
+            return Ok(());
+        }
+        self.rotate_backups()?;
+        self.write_atomically(&json)?;
+
+        Ok(())
+    }
+
+    fn lock(&self) -> Result<RepositoryLock> {
+        RepositoryLock::acquire(&self.data_dir, LOCK_TIMEOUT)
+    }
+
+    fn create_and_lock(&self) -> Result<RepositoryLock> {
+        RepositoryLock::create(&self.data_dir, LOCK_TIMEOUT)
+    }
+}
+
+/// Where project data is stored, selected in `.clockme/config.json`
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum StorageBackend {
+    /// A single pretty-printed `data.json` document
+    #[default]
+    Json,
+    /// Normalized tables in `data.db`
+    Sqlite,
+    /// Append-only change log in `events.jsonl`
+    Events,
+}
+
+impl StorageBackend {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            StorageBackend::Json => "json",
+            StorageBackend::Sqlite => "sqlite",
+            StorageBackend::Events => "events",
+        }
+    }
+
+    fn data_file(&self, data_dir: &Path) -> PathBuf {
+        match self {
+            StorageBackend::Json => data_dir.join("data.json"),
+            StorageBackend::Sqlite => data_dir.join("data.db"),
+            StorageBackend::Events => data_dir.join("events.jsonl"),
+        }
+    }
+
+    fn open(&self, data_dir: PathBuf) -> Box<dyn Repository> {
+        match self {
+            StorageBackend::Json => Box::new(FileRepository::with_data_dir(data_dir)),
+            StorageBackend::Sqlite => Box::new(SqliteRepository::new(data_dir)),
+            StorageBackend::Events => Box::new(EventLogRepository::new(data_dir)),
+        }
+    }
+}
+
+impl std::str::FromStr for StorageBackend {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self> {
+        match s.trim().to_lowercase().as_str() {
+            "json" => Ok(StorageBackend::Json),
+            "sqlite" => Ok(StorageBackend::Sqlite),
+            "events" => Ok(StorageBackend::Events),
+            _ => Err(anyhow!(
+                "Unknown storage backend '{}'. Use 'json', 'sqlite' or 'events'",
+                s
+            )),
+        }
+    }
+}
+
+/// Per-project settings kept next to the data in `.clockme/config.json`
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct StorageConfig {
+    #[serde(default)]
+    pub storage: StorageBackend,
+}
+
+impl StorageConfig {
+    fn path(data_dir: &Path) -> PathBuf {
+        data_dir.join("config.json")
+    }
+
+    /// Reads the config, falling back to the defaults when there is none
+    pub fn load(data_dir: &Path) -> Result<Self> {
+        let path = Self::path(data_dir);
+        if !path.exists() {
+            return Ok(Self::default());
+        }
+
+        let content = fs::read_to_string(&path).context("Failed to read .clockme/config.json")?;
+        serde_json::from_str(&content).context("Failed to parse .clockme/config.json")
+    }
+
+    pub fn save(&self, data_dir: &Path) -> Result<()> {
+        let json = serde_json::to_string_pretty(self).context("Failed to serialize config")?;
+        fs::write(Self::path(data_dir), json).context("Failed to write .clockme/config.json")
+    }
+}
+
+/// The `.clockme` directory of the enclosing project, or `./.clockme` if
+/// there is none yet (for init)
+pub fn find_data_dir() -> PathBuf {
+    enclosing_data_dir().unwrap_or_else(|| PathBuf::from(".clockme"))
+}
+
+/// The `.clockme` directory of the enclosing project, if any
+pub fn enclosing_data_dir() -> Option<PathBuf> {
+    FileRepository::find_clockme_dir().map(|(data_dir, _)| data_dir)
+}
+
+/// Loads the project without taking the lock, writing or upgrading anything,
+/// falling back to backups or printing warnings, for callers that only glance
+/// at it
+pub fn read_project(data_dir: &Path) -> Result<Project> {
+    let data_dir = data_dir.to_path_buf();
+    match StorageConfig::load(&data_dir)?.storage {
+        StorageBackend::Json => {
+            let (project, _) = FileRepository::read_project(&data_dir.join("data.json"))?;
+            Ok(project)
+        }
+        StorageBackend::Sqlite => SqliteRepository::new(data_dir).read_only(),
+        StorageBackend::Events => EventLogRepository::new(data_dir).without_warnings().load(),
+    }
+}
+
+/// Opens the repository configured for the current project
+pub fn open_repository() -> Result<Box<dyn Repository>> {
+    open_repository_at(&find_data_dir())
+}
+
+/// Opens the repository configured for the project in `data_dir`
+pub fn open_repository_at(data_dir: &Path) -> Result<Box<dyn Repository>> {
+    let config = StorageConfig::load(data_dir)?;
+    Ok(config.storage.open(data_dir.to_path_buf()))
+}
+
+/// Copies the project into the `to` backend, checks that it reads back
+/// identically and switches the config over.
+///
+/// The old store is kept as `<file>.migrated` rather than deleted, or as
+/// `<file>.migrated.2` and so on when an earlier migration left one behind.
+pub fn migrate_storage(data_dir: &Path, to: StorageBackend) -> Result<Project> {
+    let _lock = RepositoryLock::acquire(data_dir, LOCK_TIMEOUT)?;
+    let mut config = StorageConfig::load(data_dir)?;
+    let from = config.storage;
+    if from == to {
+        return Err(anyhow!("Project already uses {} storage", to.as_str()));
+    }
+
+    let target_file = to.data_file(data_dir);
+    if target_file.exists() {
+        return Err(anyhow!(
+            "{} already exists. Move it away before migrating.",
+            target_file.display()
+        ));
+    }
+    let source_file = from.data_file(data_dir);
+    let migrated = (1..=MAX_NUMBERED_NAMES)
+        .map(|n| {
+            let mut name = source_file.clone().into_os_string();
+            name.push(".migrated");
+            if n > 1 {
+                name.push(format!(".{}", n));
+            }
+            PathBuf::from(name)
+        })
+        .find(|path| !path.exists())
+        .ok_or_else(|| {
+            anyhow!(
+                "Too many earlier copies of {} in {}. Remove some before migrating.",
+                source_file.display(),
+                data_dir.display()
+            )
+        })?;
+
+    let project = from.open(data_dir.to_path_buf()).load()?;
+    let target = to.open(data_dir.to_path_buf());
+    target.save(&project)?;
+
+    let verify = || -> Result<()> {
+        let copied = target.load()?;
+        if serde_json::to_value(&copied)? != serde_json::to_value(&project)? {
+            return Err(anyhow!("Migrated data does not match the original"));
+        }
+        Ok(())
+    };
+    if let Err(e) = verify() {
+        let _ = fs::remove_file(&target_file);
+        return Err(e);
+    }
+
+    config.storage = to;
+    config.save(data_dir)?;
+
+    fs::rename(&source_file, &migrated).context("Failed to move the old data file aside")?;
+
+    Ok(project)
+}
+
+/// Writes a copy of `project` in the `data.json` layout to `.clockme/backups`,
+/// whatever the storage backend, and returns its path. A backup taken in the
+/// same second gets a numbered name rather than replacing the earlier one.
+pub fn backup_project(data_dir: &Path, project: &Project, at: DateTime<Local>) -> Result<PathBuf> {
+    let backup_dir = data_dir.join("backups");
+    fs::create_dir_all(&backup_dir).context("Failed to create .clockme/backups directory")?;
+
+    let json = serde_json::to_string_pretty(&migrations::VersionedProject::new(project))
+        .context("Failed to serialize project data")?;
+    let stamp = at.format("%Y%m%d-%H%M%S");
+    for n in 1..=MAX_NUMBERED_NAMES {
+        let name = match n {
+            1 => format!("data-{}.json", stamp),
+            n => format!("data-{}-{}.json", stamp, n),
+        };
+        let backup = backup_dir.join(name);
+        let mut file = match File::options().write(true).create_new(true).open(&backup) {
+            Ok(file) => file,
+            Err(e) if e.kind() == std::io::ErrorKind::AlreadyExists => continue,
+            Err(e) => return Err(e).context("Failed to back up project data"),
+        };
+        file.write_all(json.as_bytes())
+            .context("Failed to back up project data")?;
+        return Ok(backup);
+    }
+    Err(anyhow!(
+        "Too many backups taken at {}. Remove some from {}.",
+        stamp,
+        backup_dir.display()
+    ))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::project::Project;
+    use std::fs;
+    use tempfile::TempDir;
+
+    #[test]
+    fn test_save_and_load() {
+        let temp_dir = TempDir::new().unwrap();
+        let data_dir = temp_dir.path().join(".clockme");
+        let data_file = data_dir.join("data.json");
+
+        let repo = FileRepository {
+            data_dir,
+            data_file,
+        };
+
+        let project = Project::new("test-project".to_string());
+
+        assert!(repo.save(&project).is_ok());
+
+        let loaded = repo.load().unwrap();
+        assert_eq!(loaded.name, "test-project");
+    }
+
+    #[test]
+    fn test_save_leaves_no_temp_file_and_rotates_backups() {
+        let temp_dir = TempDir::new().unwrap();
+        let repo = FileRepository::with_data_dir(temp_dir.path().join(".clockme"));
+
+        for i in 0..BACKUP_COUNT + 3 {
+            repo.save(&Project::new(format!("version-{}", i))).unwrap();
+        }
+
+        assert!(!repo.data_dir.join("data.json.tmp").exists());
+        assert_eq!(
+            repo.load().unwrap().name,
+            format!("version-{}", BACKUP_COUNT + 2)
+        );
+
+        let (newest, _) = FileRepository::read_project(&repo.backup_file(1)).unwrap();
+        assert_eq!(newest.name, format!("version-{}", BACKUP_COUNT + 1));
+        let (oldest, _) = FileRepository::read_project(&repo.backup_file(BACKUP_COUNT)).unwrap();
+        assert_eq!(oldest.name, "version-2");
+        assert!(!repo.backup_file(BACKUP_COUNT + 1).exists());
+    }
+
+    #[test]
+    fn test_rotation_keeps_only_distinct_readable_versions() {
+        let temp_dir = TempDir::new().unwrap();
+        let repo = FileRepository::with_data_dir(temp_dir.path().join(".clockme"));
+        let backups = || {
+            (1..=BACKUP_COUNT)
+                .filter(|&n| repo.backup_file(n).exists())
+                .count()
+        };
+
+        repo.save(&Project::new("first".to_string())).unwrap();
+        repo.save(&Project::new("second".to_string())).unwrap();
+        repo.save(&Project::new("second".to_string())).unwrap();
+        assert_eq!(backups(), 1);
+
+        // Written by hand to the same content as the newest backup
+        fs::copy(repo.backup_file(1), &repo.data_file).unwrap();
+        repo.save(&Project::new("third".to_string())).unwrap();
+        assert_eq!(backups(), 1);
+
+        fs::write(&repo.data_file, "{\"name\": \"thi").unwrap();
+        repo.save(&Project::new("fourth".to_string())).unwrap();
+        assert_eq!(backups(), 1);
+        let (newest, _) = FileRepository::read_project(&repo.backup_file(1)).unwrap();
+        assert_eq!(newest.name, "first");
+    }
+
+    #[test]
+    fn test_load_falls_back_to_newest_valid_backup() {
+        let temp_dir = TempDir::new().unwrap();
+        let repo = FileRepository::with_data_dir(temp_dir.path().join(".clockme"));
+
+        repo.save(&Project::new("first".to_string())).unwrap();
+        repo.save(&Project::new("second".to_string())).unwrap();
+        repo.save(&Project::new("third".to_string())).unwrap();
+
+        fs::write(&repo.data_file, "{\"name\": \"thi").unwrap();
+        fs::write(repo.backup_file(1), "").unwrap();
+
+        assert_eq!(repo.load().unwrap().name, "first");
+        assert!(repo.load_strict().is_err());
+    }
+
+    #[test]
+    fn test_backups_in_the_same_second_are_kept() {
+        let temp_dir = TempDir::new().unwrap();
+        let at = chrono::Local::now();
+
+        let first = backup_project(temp_dir.path(), &Project::new("first".to_string()), at);
+        let second = backup_project(temp_dir.path(), &Project::new("second".to_string()), at);
+
+        let (first, second) = (first.unwrap(), second.unwrap());
+        assert_ne!(first, second);
+        let name = |path: &Path| {
+            migrations::load(&fs::read_to_string(path).unwrap())
+                .unwrap()
+                .0
+                .name
+        };
+        assert_eq!(name(&first), "first");
+        assert_eq!(name(&second), "second");
+    }
+
+    #[test]
+    fn test_load_fails_without_valid_backup() {
+        let temp_dir = TempDir::new().unwrap();
+        let repo = FileRepository::with_data_dir(temp_dir.path().join(".clockme"));
+
+        repo.save(&Project::new("only".to_string())).unwrap();
+        fs::write(&repo.data_file, "garbage").unwrap();
+
+        assert!(repo.load().is_err());
+    }
+
+    #[test]
+    fn test_load_upgrades_legacy_file_and_keeps_original() {
+        let temp_dir = TempDir::new().unwrap();
+        let repo = FileRepository::with_data_dir(temp_dir.path().join(".clockme"));
+        fs::create_dir_all(&repo.data_dir).unwrap();
+        let legacy = r#"{"project_name": "old-app", "current_session": null, "session_list": []}"#;
+        fs::write(&repo.data_file, legacy).unwrap();
+
+        let project = repo.load().unwrap();
+        assert_eq!(project.name, "old-app");
+        let original = repo.data_dir.join("backups").join("data.json.v0");
+        assert_eq!(fs::read_to_string(&original).unwrap(), legacy);
+
+        repo.save(&project).unwrap();
+        let saved = fs::read_to_string(&repo.data_file).unwrap();
+        assert!(saved.contains(&format!(
+            "\"schema_version\": {}",
+            migrations::CURRENT_VERSION
+        )));
+        assert_eq!(fs::read_to_string(&original).unwrap(), legacy);
+    }
+
+    #[test]
+    fn test_load_refuses_newer_file_without_falling_back() {
+        let temp_dir = TempDir::new().unwrap();
+        let repo = FileRepository::with_data_dir(temp_dir.path().join(".clockme"));
+        repo.save(&Project::new("first".to_string())).unwrap();
+        repo.save(&Project::new("second".to_string())).unwrap();
+
+        let newer = format!(
+            r#"{{"schema_version": {}, "name": "future", "sessions": []}}"#,
+            migrations::CURRENT_VERSION + 1
+        );
+        fs::write(&repo.data_file, newer).unwrap();
+
+        assert!(repo.load().is_err());
+    }
+
+    #[test]
+    fn test_parallel_writers_do_not_lose_updates() {
+        let temp_dir = TempDir::new().unwrap();
+        let data_dir = temp_dir.path().join(".clockme");
+        FileRepository::with_data_dir(data_dir.clone())
+            .save(&Project::new("test-project".to_string()))
+            .unwrap();
+
+        let writers: Vec<_> = (0..4)
+            .map(|writer| {
+                let data_dir = data_dir.clone();
+                std::thread::spawn(move || {
+                    let repo = FileRepository::with_data_dir(data_dir);
+                    for i in 0..10 {
+                        let _lock = repo.lock().unwrap();
+                        let mut project = repo.load().unwrap();
+                        let start =
+                            chrono::Local::now() - chrono::Duration::days(writer * 100 + i + 1);
+                        project
+                            .sessions
+                            .push(crate::models::session::Session::manual(
+                                start,
+                                start + chrono::Duration::hours(1),
+                            ));
+                        repo.save(&project).unwrap();
+                    }
+                })
+            })
+            .collect();
+        for writer in writers {
+            writer.join().unwrap();
+        }
+
+        let project = FileRepository::with_data_dir(data_dir).load().unwrap();
+        assert_eq!(project.sessions.len(), 40);
+    }
+
+    #[test]
+    fn test_find_clockme_dir_in_subdirectory() {
+        let temp_dir = TempDir::new().unwrap();
+        let project_root = temp_dir.path();
+
+        // Create .clockme in root
+        let clockme_dir = project_root.join(".clockme");
+        fs::create_dir(&clockme_dir).unwrap();
+
+        // Create a subdirectory
+        let subdir = project_root.join("src").join("models");
+        fs::create_dir_all(&subdir).unwrap();
+
+        // Change to subdirectory
+        let original_dir = env::current_dir().unwrap();
+        env::set_current_dir(&subdir).unwrap();
+
+        // Should find .clockme by walking up
+        let result = FileRepository::find_clockme_dir();
+
+        // Restore original directory
+        env::set_current_dir(original_dir).unwrap();
+
+        assert!(result.is_some());
+        let (found_dir, _) = result.unwrap();
+        assert_eq!(found_dir, clockme_dir);
+    }
+
+    /// Every file in `dir` with its content
+    fn contents(dir: &Path) -> Vec<(PathBuf, Vec<u8>)> {
+        let mut files: Vec<_> = fs::read_dir(dir)
+            .unwrap()
+            .map(|entry| {
+                let path = entry.unwrap().path();
+                let bytes = fs::read(&path).unwrap();
+                (path, bytes)
+            })
+            .collect();
+        files.sort();
+        files
+    }
+
+    /// `read_project` returns the stored project and leaves `.clockme`
+    /// exactly as it was
+    fn assert_read_project_writes_nothing(backend: StorageBackend) {
+        let temp_dir = TempDir::new().unwrap();
+        let data_dir = temp_dir.path().join(".clockme");
+        let mut project = Project::new("my-app".to_string());
+        project.start_session(chrono::Local::now());
+        fs::create_dir_all(&data_dir).unwrap();
+        StorageConfig { storage: backend }.save(&data_dir).unwrap();
+        backend.open(data_dir.clone()).save(&project).unwrap();
+        if backend == StorageBackend::Events {
+            let mut log = fs::read_to_string(data_dir.join("events.jsonl")).unwrap();
+            log.push_str("{\"at\": \"2025-10-1");
+            fs::write(data_dir.join("events.jsonl"), log).unwrap();
+        }
+        let before = contents(&data_dir);
+
+        let read = read_project(&data_dir).unwrap();
+        assert_eq!(
+            serde_json::to_value(&read).unwrap(),
+            serde_json::to_value(&project).unwrap(),
+            "{}",
+            backend.as_str()
+        );
+        assert_eq!(contents(&data_dir), before, "{}", backend.as_str());
+    }
+
+    #[test]
+    fn test_read_project_writes_nothing_to_json() {
+        assert_read_project_writes_nothing(StorageBackend::Json);
+    }
+
+    #[test]
+    fn test_read_project_writes_nothing_to_sqlite() {
+        assert_read_project_writes_nothing(StorageBackend::Sqlite);
+    }
+
+    #[test]
+    fn test_read_project_writes_nothing_to_events() {
+        assert_read_project_writes_nothing(StorageBackend::Events);
+    }
+
+    #[test]
+    fn test_read_project_does_not_upgrade_sqlite() {
+        let temp_dir = TempDir::new().unwrap();
+        let data_dir = temp_dir.path().join(".clockme");
+        fs::create_dir_all(&data_dir).unwrap();
+        StorageConfig {
+            storage: StorageBackend::Sqlite,
+        }
+        .save(&data_dir)
+        .unwrap();
+        SqliteRepository::new(data_dir.clone())
+            .save(&Project::new("my-app".to_string()))
+            .unwrap();
+        let conn = rusqlite::Connection::open(data_dir.join("data.db")).unwrap();
+        conn.pragma_update(None, "user_version", 2).unwrap();
+        drop(conn);
+        let before = contents(&data_dir);
+
+        assert!(read_project(&data_dir).is_err());
+        assert_eq!(contents(&data_dir), before);
+    }
+
+    #[test]
+    fn test_migrate_storage_round_trip() {
+        let temp_dir = TempDir::new().unwrap();
+        let data_dir = temp_dir.path().join(".clockme");
+
+        let mut project = Project::new("test-project".to_string());
+        project.start_session(chrono::Local::now());
+        FileRepository::with_data_dir(data_dir.clone())
+            .save(&project)
+            .unwrap();
+
+        migrate_storage(&data_dir, StorageBackend::Sqlite).unwrap();
+        assert_eq!(
+            StorageConfig::load(&data_dir).unwrap().storage,
+            StorageBackend::Sqlite
+        );
+        assert!(!data_dir.join("data.json").exists());
+        assert!(data_dir.join("data.json.migrated").exists());
+
+        let loaded = SqliteRepository::new(data_dir.clone()).load().unwrap();
+        assert_eq!(loaded.name, "test-project");
+        assert!(loaded.current_session.is_some());
+
+        migrate_storage(&data_dir, StorageBackend::Json).unwrap();
+        let loaded = FileRepository::with_data_dir(data_dir.clone())
+            .load()
+            .unwrap();
+        assert_eq!(
+            serde_json::to_value(&loaded).unwrap(),
+            serde_json::to_value(&project).unwrap()
+        );
+
+        // The copy left by the first migration is kept
+        let first_copy = fs::read(data_dir.join("data.json.migrated")).unwrap();
+        migrate_storage(&data_dir, StorageBackend::Sqlite).unwrap();
+        assert_eq!(
+            fs::read(data_dir.join("data.json.migrated")).unwrap(),
+            first_copy
+        );
+        assert!(data_dir.join("data.json.migrated.2").exists());
+    }
+
+    #[test]
+    fn test_migrate_storage_refuses_same_backend() {
+        let temp_dir = TempDir::new().unwrap();
+        let data_dir = temp_dir.path().join(".clockme");
+        FileRepository::with_data_dir(data_dir.clone())
+            .save(&Project::new("test-project".to_string()))
+            .unwrap();
+
+        assert!(migrate_storage(&data_dir, StorageBackend::Json).is_err());
+        assert!(data_dir.join("data.json").exists());
+    }
+}
@@ -0,0 +1,194 @@
+use crate::repository::lock::{RepositoryLock, LOCK_TIMEOUT};
+use anyhow::{anyhow, Context, Result};
+use serde::{Deserialize, Serialize};
+use std::env;
+use std::ffi::OsString;
+use std::fs::{self, File};
+use std::io::Write;
+use std::path::{Path, PathBuf};
+
+/// A project known to the registry, by the directory holding its `.clockme`
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct RegisteredProject {
+    pub path: PathBuf,
+}
+
+impl RegisteredProject {
+    pub fn data_dir(&self) -> PathBuf {
+        self.path.join(".clockme")
+    }
+
+    /// The project directory or its `.clockme` has been moved or deleted
+    pub fn is_stale(&self) -> bool {
+        !self.data_dir().is_dir()
+    }
+}
+
+#[derive(Debug, Default, Serialize, Deserialize)]
+struct RegistryFile {
+    projects: Vec<RegisteredProject>,
+}
+
+/// Every project initialized on this machine, kept in
+/// `$XDG_DATA_HOME/clock-me/projects.json` so commands can look across them
+pub struct Registry {
+    dir: PathBuf,
+    file: PathBuf,
+}
+
+impl Registry {
+    pub fn new(dir: &Path) -> Self {
+        Self {
+            dir: dir.to_path_buf(),
+            file: dir.join("projects.json"),
+        }
+    }
+
+    /// The registry of the current user
+    pub fn open() -> Result<Self> {
+        let data_home =
+            data_home(env::var_os("XDG_DATA_HOME"), env::var_os("HOME")).ok_or_else(|| {
+                anyhow!("Cannot locate the registry: neither XDG_DATA_HOME nor HOME is set")
+            })?;
+        Ok(Self::new(&data_home.join("clock-me")))
+    }
+
+    pub fn load(&self) -> Result<Vec<RegisteredProject>> {
+        Ok(self.read()?.projects)
+    }
+
+    /// Records the project whose data lives in `data_dir`. Returns false if it
+    /// was already registered.
+    pub fn register(&self, data_dir: &Path) -> Result<bool> {
+        let data_dir = data_dir
+            .canonicalize()
+            .with_context(|| format!("Failed to resolve {}", data_dir.display()))?;
+        let path = data_dir.parent().unwrap_or(&data_dir).to_path_buf();
+
+        if self.load()?.iter().any(|p| p.path == path) {
+            return Ok(false);
+        }
+
+        fs::create_dir_all(&self.dir)
+            .with_context(|| format!("Failed to create {}", self.dir.display()))?;
+        let _lock = RepositoryLock::acquire(&self.dir, LOCK_TIMEOUT)?;
+        let mut registry = self.read()?;
+        if registry.projects.iter().any(|p| p.path == path) {
+            return Ok(false);
+        }
+        registry.projects.push(RegisteredProject { path });
+        self.write(&registry)?;
+        Ok(true)
+    }
+
+    /// Forgets projects whose `.clockme` no longer exists and returns them
+    pub fn prune(&self) -> Result<Vec<RegisteredProject>> {
+        let _lock = RepositoryLock::acquire(&self.dir, LOCK_TIMEOUT)?;
+        let mut registry = self.read()?;
+        let (stale, kept) = registry
+            .projects
+            .into_iter()
+            .partition(RegisteredProject::is_stale);
+        registry.projects = kept;
+
+        if !stale.is_empty() {
+            self.write(&registry)?;
+        }
+        Ok(stale)
+    }
+
+    fn read(&self) -> Result<RegistryFile> {
+        if !self.file.exists() {
+            return Ok(RegistryFile::default());
+        }
+
+        let content = fs::read_to_string(&self.file)
+            .with_context(|| format!("Failed to read {}", self.file.display()))?;
+        serde_json::from_str(&content)
+            .with_context(|| format!("Failed to parse {}", self.file.display()))
+    }
+
+    fn write(&self, registry: &RegistryFile) -> Result<()> {
+        let json =
+            serde_json::to_string_pretty(registry).context("Failed to serialize the registry")?;
+
+        let temp_file = self.dir.join("projects.json.tmp");
+        let mut file = File::create(&temp_file).context("Failed to write the registry")?;
+        file.write_all(json.as_bytes())
+            .and_then(|_| file.sync_all())
+            .context("Failed to write the registry")?;
+        fs::rename(&temp_file, &self.file).context("Failed to write the registry")?;
+
+        Ok(())
+    }
+}
+
+/// `$XDG_DATA_HOME`, or `$HOME/.local/share` when it is unset or not absolute
+/// as the XDG spec asks
+fn data_home(xdg_data_home: Option<OsString>, home: Option<OsString>) -> Option<PathBuf> {
+    xdg_data_home
+        .map(PathBuf::from)
+        .filter(|path| path.is_absolute())
+        .or_else(|| {
+            home.filter(|home| !home.is_empty())
+                .map(|home| PathBuf::from(home).join(".local").join("share"))
+        })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    #[test]
+    fn test_data_home() {
+        assert_eq!(
+            data_home(Some("/data".into()), Some("/home/me".into())),
+            Some(PathBuf::from("/data"))
+        );
+        assert_eq!(
+            data_home(Some("relative".into()), Some("/home/me".into())),
+            Some(PathBuf::from("/home/me/.local/share"))
+        );
+        assert_eq!(data_home(None, Some("".into())), None);
+    }
+
+    #[test]
+    fn test_register_once() {
+        let temp_dir = TempDir::new().unwrap();
+        let registry = Registry::new(&temp_dir.path().join("registry"));
+        let data_dir = temp_dir.path().join("work").join(".clockme");
+        fs::create_dir_all(&data_dir).unwrap();
+
+        assert!(registry.register(&data_dir).unwrap());
+        assert!(!registry.register(&data_dir).unwrap());
+
+        let projects = registry.load().unwrap();
+        assert_eq!(projects.len(), 1);
+        assert_eq!(
+            projects[0].path,
+            temp_dir.path().join("work").canonicalize().unwrap()
+        );
+    }
+
+    #[test]
+    fn test_prune_stale_projects() {
+        let temp_dir = TempDir::new().unwrap();
+        let registry = Registry::new(&temp_dir.path().join("registry"));
+        for name in ["kept", "moved"] {
+            let data_dir = temp_dir.path().join(name).join(".clockme");
+            fs::create_dir_all(&data_dir).unwrap();
+            registry.register(&data_dir).unwrap();
+        }
+        fs::remove_dir_all(temp_dir.path().join("moved")).unwrap();
+
+        let stale = registry.prune().unwrap();
+        assert_eq!(stale.len(), 1);
+        assert!(stale[0].path.ends_with("moved"));
+
+        let projects = registry.load().unwrap();
+        assert_eq!(projects.len(), 1);
+        assert!(projects[0].path.ends_with("kept"));
+        assert!(registry.prune().unwrap().is_empty());
+    }
+}
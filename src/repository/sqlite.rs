@@ -0,0 +1,657 @@
+use crate::models::commit::{Checkout, Commit};
+use crate::models::project::Project;
+use crate::models::r#break::Break;
+use crate::models::session::Session;
+use crate::repository::lock::{RepositoryLock, LOCK_TIMEOUT};
+use crate::repository::Repository;
+use anyhow::{anyhow, Context, Result};
+use chrono::{DateTime, Local};
+use rusqlite::{params, Connection, OpenFlags, OptionalExtension, Transaction};
+use std::collections::HashMap;
+use std::fs;
+use std::path::PathBuf;
+use uuid::Uuid;
+
+/// Bumped whenever the table layout changes; stored in `PRAGMA user_version`
+const SCHEMA_VERSION: i32 = 4;
+
+const SCHEMA: &str = "
+CREATE TABLE IF NOT EXISTS project (
+    id INTEGER PRIMARY KEY CHECK (id = 1),
+    name TEXT NOT NULL
+);
+CREATE TABLE IF NOT EXISTS sessions (
+    id INTEGER PRIMARY KEY,
+    uid TEXT,
+    position INTEGER NOT NULL,
+    active INTEGER NOT NULL DEFAULT 0,
+    start TEXT NOT NULL,
+    end TEXT,
+    origin TEXT NOT NULL,
+    note TEXT,
+    digest TEXT
+);
+CREATE INDEX IF NOT EXISTS sessions_start ON sessions (start);
+CREATE TABLE IF NOT EXISTS breaks (
+    id INTEGER PRIMARY KEY,
+    uid TEXT,
+    session_id INTEGER REFERENCES sessions (id) ON DELETE CASCADE,
+    position INTEGER NOT NULL,
+    current INTEGER NOT NULL DEFAULT 0,
+    start TEXT NOT NULL,
+    end TEXT,
+    note TEXT
+);
+CREATE INDEX IF NOT EXISTS breaks_session ON breaks (session_id);
+CREATE TABLE IF NOT EXISTS session_tags (
+    session_id INTEGER NOT NULL REFERENCES sessions (id) ON DELETE CASCADE,
+    position INTEGER NOT NULL,
+    tag TEXT NOT NULL,
+    PRIMARY KEY (session_id, position)
+);
+CREATE TABLE IF NOT EXISTS session_commits (
+    session_id INTEGER NOT NULL REFERENCES sessions (id) ON DELETE CASCADE,
+    position INTEGER NOT NULL,
+    hash TEXT NOT NULL,
+    subject TEXT NOT NULL,
+    branch TEXT,
+    at TEXT NOT NULL,
+    PRIMARY KEY (session_id, position)
+);
+CREATE TABLE IF NOT EXISTS session_checkouts (
+    session_id INTEGER NOT NULL REFERENCES sessions (id) ON DELETE CASCADE,
+    position INTEGER NOT NULL,
+    branch TEXT NOT NULL,
+    at TEXT NOT NULL,
+    PRIMARY KEY (session_id, position)
+);
+";
+
+/// What `save` needs to know about a session row already in the database
+struct StoredSession {
+    id: i64,
+    digest: Option<String>,
+    position: i64,
+    active: bool,
+}
+
+/// Tables holding the parts of a session, rewritten when the session changes
+const SESSION_DETAILS: [&str; 4] = [
+    "breaks",
+    "session_tags",
+    "session_commits",
+    "session_checkouts",
+];
+
+/// Stores the project in `.clockme/data.db` with sessions, breaks, tags,
+/// commits and branch switches in their own tables. Saving only writes the
+/// sessions that changed.
+pub struct SqliteRepository {
+    data_dir: PathBuf,
+    db_file: PathBuf,
+}
+
+impl SqliteRepository {
+    pub fn new(data_dir: PathBuf) -> Self {
+        let db_file = data_dir.join("data.db");
+        Self { data_dir, db_file }
+    }
+
+    fn open(&self) -> Result<Connection> {
+        let conn = Connection::open(&self.db_file).context("Failed to open project database")?;
+        conn.execute_batch("PRAGMA foreign_keys = ON;")?;
+
+        let version: i32 = conn.query_row("PRAGMA user_version", [], |row| row.get(0))?;
+        if version > SCHEMA_VERSION {
+            return Err(anyhow!(
+                "Project database was written by a newer version of clock-me (schema {}). Please upgrade.",
+                version
+            ));
+        }
+
+        if version == 1 {
+            // Version 1 had no record IDs; they are backfilled on load
+            conn.execute_batch(
+                "ALTER TABLE sessions ADD COLUMN uid TEXT;
+                 ALTER TABLE breaks ADD COLUMN uid TEXT;",
+            )
+            .context("Failed to upgrade project database")?;
+        }
+        if (1..4).contains(&version) {
+            conn.execute_batch("ALTER TABLE sessions ADD COLUMN digest TEXT;")
+                .context("Failed to upgrade project database")?;
+        }
+        conn.execute_batch(SCHEMA)
+            .context("Failed to create database tables")?;
+        conn.pragma_update(None, "user_version", SCHEMA_VERSION)?;
+        Ok(conn)
+    }
+
+    fn to_text(time: DateTime<Local>) -> String {
+        time.to_rfc3339()
+    }
+
+    fn from_text(text: &str) -> Result<DateTime<Local>> {
+        DateTime::parse_from_rfc3339(text)
+            .map(|t| t.with_timezone(&Local))
+            .with_context(|| format!("Invalid timestamp '{}' in database", text))
+    }
+
+    /// Tables upgraded from schema 1 have no cascading deletes, so the
+    /// details of a session are removed explicitly
+    fn delete_details(tx: &Transaction, session_id: i64) -> Result<()> {
+        for table in SESSION_DETAILS {
+            tx.execute(
+                &format!("DELETE FROM {} WHERE session_id = ?1", table),
+                [session_id],
+            )?;
+        }
+        Ok(())
+    }
+
+    /// Fingerprint of everything stored about a session, so unchanged
+    /// sessions can be skipped on save
+    fn digest(session: &Session) -> Result<String> {
+        let json = serde_json::to_string(session)?;
+        Ok(Uuid::new_v5(&Uuid::NAMESPACE_OID, json.as_bytes()).to_string())
+    }
+
+    /// Inserts or updates one session and returns its row ID. Its breaks,
+    /// tags, commits and branch switches are only rewritten when it differs
+    /// from the stored row.
+    fn store_session(
+        tx: &Transaction,
+        stored: &mut HashMap<String, StoredSession>,
+        session: &Session,
+        position: usize,
+        active: bool,
+    ) -> Result<i64> {
+        let digest = Self::digest(session)?;
+        let session_id = match stored.remove(&session.id.to_string()) {
+            Some(row) if row.digest.as_deref() == Some(digest.as_str()) => {
+                if row.position != position as i64 || row.active != active {
+                    tx.execute(
+                        "UPDATE sessions SET position = ?2, active = ?3 WHERE id = ?1",
+                        params![row.id, position as i64, active],
+                    )?;
+                }
+                return Ok(row.id);
+            }
+            Some(row) => {
+                tx.execute(
+                    "UPDATE sessions SET position = ?2, active = ?3, start = ?4, end = ?5,
+                         origin = ?6, note = ?7, digest = ?8
+                     WHERE id = ?1",
+                    params![
+                        row.id,
+                        position as i64,
+                        active,
+                        Self::to_text(session.start),
+                        session.end.map(Self::to_text),
+                        session.origin.as_str(),
+                        session.note,
+                        digest,
+                    ],
+                )?;
+                Self::delete_details(tx, row.id)?;
+                row.id
+            }
+            None => {
+                tx.execute(
+                    "INSERT INTO sessions (uid, position, active, start, end, origin, note, digest)
+                     VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8)",
+                    params![
+                        session.id.to_string(),
+                        position as i64,
+                        active,
+                        Self::to_text(session.start),
+                        session.end.map(Self::to_text),
+                        session.origin.as_str(),
+                        session.note,
+                        digest,
+                    ],
+                )?;
+                tx.last_insert_rowid()
+            }
+        };
+
+        for (index, break_period) in session.breaks.iter().enumerate() {
+            Self::insert_break(tx, Some(session_id), break_period, index, false)?;
+        }
+        for (index, tag) in session.tags.iter().enumerate() {
+            tx.execute(
+                "INSERT INTO session_tags (session_id, position, tag) VALUES (?1, ?2, ?3)",
+                params![session_id, index as i64, tag],
+            )?;
+        }
+        for (index, commit) in session.commits.iter().enumerate() {
+            tx.execute(
+                "INSERT INTO session_commits (session_id, position, hash, subject, branch, at)
+                 VALUES (?1, ?2, ?3, ?4, ?5, ?6)",
+                params![
+                    session_id,
+                    index as i64,
+                    commit.hash,
+                    commit.subject,
+                    commit.branch,
+                    Self::to_text(commit.at),
+                ],
+            )?;
+        }
+        for (index, checkout) in session.checkouts.iter().enumerate() {
+            tx.execute(
+                "INSERT INTO session_checkouts (session_id, position, branch, at)
+                 VALUES (?1, ?2, ?3, ?4)",
+                params![
+                    session_id,
+                    index as i64,
+                    checkout.branch,
+                    Self::to_text(checkout.at),
+                ],
+            )?;
+        }
+
+        Ok(session_id)
+    }
+
+    fn insert_break(
+        tx: &Transaction,
+        session_id: Option<i64>,
+        break_period: &Break,
+        position: usize,
+        current: bool,
+    ) -> Result<()> {
+        tx.execute(
+            "INSERT INTO breaks (uid, session_id, position, current, start, end, note)
+             VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7)",
+            params![
+                break_period.id.to_string(),
+                session_id,
+                position as i64,
+                current,
+                Self::to_text(break_period.start),
+                break_period.end.map(Self::to_text),
+                break_period.note,
+            ],
+        )?;
+        Ok(())
+    }
+
+    fn read_break(row: &rusqlite::Row) -> Result<Break> {
+        let start: String = row.get("start")?;
+        let end: Option<String> = row.get("end")?;
+        Ok(Break {
+            id: Self::read_uid(row)?,
+            start: Self::from_text(&start)?,
+            end: end.as_deref().map(Self::from_text).transpose()?,
+            note: row.get("note")?,
+        })
+    }
+
+    /// Rows written before IDs existed have none; `backfill_ids` fills them in
+    fn read_uid(row: &rusqlite::Row) -> Result<Uuid> {
+        let uid: Option<String> = row.get("uid")?;
+        uid.map(|uid| Uuid::parse_str(&uid).context("Invalid ID in database"))
+            .transpose()
+            .map(Option::unwrap_or_default)
+    }
+
+    fn read_session(conn: &Connection, row: &rusqlite::Row) -> Result<Session> {
+        let id: i64 = row.get("id")?;
+        let start: String = row.get("start")?;
+        let end: Option<String> = row.get("end")?;
+        let origin: String = row.get("origin")?;
+
+        let mut session = Session::new(Self::from_text(&start)?);
+        session.id = Self::read_uid(row)?;
+        session.end = end.as_deref().map(Self::from_text).transpose()?;
+        session.origin = origin.parse()?;
+        session.note = row.get("note")?;
+
+        let mut stmt = conn.prepare(
+            "SELECT uid, start, end, note FROM breaks
+             WHERE session_id = ?1 AND current = 0 ORDER BY position",
+        )?;
+        let mut rows = stmt.query([id])?;
+        while let Some(row) = rows.next()? {
+            session.breaks.push(Self::read_break(row)?);
+        }
+
+        let mut stmt =
+            conn.prepare("SELECT tag FROM session_tags WHERE session_id = ?1 ORDER BY position")?;
+        session.tags = stmt
+            .query_map([id], |row| row.get(0))?
+            .collect::<rusqlite::Result<Vec<String>>>()?;
+
+        let mut stmt = conn.prepare(
+            "SELECT hash, subject, branch, at FROM session_commits
+             WHERE session_id = ?1 ORDER BY position",
+        )?;
+        let mut rows = stmt.query([id])?;
+        while let Some(row) = rows.next()? {
+            let at: String = row.get("at")?;
+            session.commits.push(Commit {
+                hash: row.get("hash")?,
+                subject: row.get("subject")?,
+                branch: row.get("branch")?,
+                at: Self::from_text(&at)?,
+            });
+        }
+
+        let mut stmt = conn.prepare(
+            "SELECT branch, at FROM session_checkouts WHERE session_id = ?1 ORDER BY position",
+        )?;
+        let mut rows = stmt.query([id])?;
+        while let Some(row) = rows.next()? {
+            let at: String = row.get("at")?;
+            session.checkouts.push(Checkout {
+                branch: row.get("branch")?,
+                at: Self::from_text(&at)?,
+            });
+        }
+
+        Ok(session)
+    }
+
+    /// Loads the project without creating or upgrading tables and without
+    /// writing to the database, for callers that only glance at it. A
+    /// database from an older schema is refused until a regular command has
+    /// upgraded it.
+    pub fn read_only(&self) -> Result<Project> {
+        let conn = Connection::open_with_flags(
+            &self.db_file,
+            OpenFlags::SQLITE_OPEN_READ_ONLY | OpenFlags::SQLITE_OPEN_NO_MUTEX,
+        )
+        .context("Failed to open project database")?;
+        let version: i32 = conn.query_row("PRAGMA user_version", [], |row| row.get(0))?;
+        if version != SCHEMA_VERSION {
+            return Err(anyhow!(
+                "Project database has schema {}, expected {}",
+                version,
+                SCHEMA_VERSION
+            ));
+        }
+        Self::read(&conn)
+    }
+
+    fn read(conn: &Connection) -> Result<Project> {
+        let name: String = conn
+            .query_row("SELECT name FROM project WHERE id = 1", [], |row| {
+                row.get(0)
+            })
+            .optional()?
+            .ok_or_else(|| anyhow!("Project database is empty"))?;
+        let mut project = Project::new(name);
+
+        let mut stmt = conn.prepare(
+            "SELECT id, uid, active, start, end, origin, note FROM sessions
+             ORDER BY active, position",
+        )?;
+        let mut rows = stmt.query([])?;
+        while let Some(row) = rows.next()? {
+            let session = Self::read_session(conn, row)?;
+            if row.get::<_, bool>("active")? {
+                project.current_session = Some(session);
+            } else {
+                project.sessions.push(session);
+            }
+        }
+
+        project.current_break = conn
+            .query_row(
+                "SELECT uid, start, end, note FROM breaks WHERE current = 1",
+                [],
+                |row| Ok(Self::read_break(row)),
+            )
+            .optional()?
+            .transpose()?;
+
+        project.backfill_ids();
+        Ok(project)
+    }
+}
+
+impl Repository for SqliteRepository {
+    fn load(&self) -> Result<Project> {
+        if !self.db_file.exists() {
+            return Err(anyhow!(
+                "Failed to read project data. Has the project been initialized?"
+            ));
+        }
+        Self::read(&self.open()?)
+    }
+
+    fn save(&self, project: &Project) -> Result<()> {
+        if !self.data_dir.exists() {
+            fs::create_dir_all(&self.data_dir).context("Failed to create .clockme directory")?;
+        }
+
+        let mut conn = self.open()?;
+        let tx = conn.transaction()?;
+
+        tx.execute(
+            "INSERT INTO project (id, name) VALUES (1, ?1)
+             ON CONFLICT (id) DO UPDATE SET name = excluded.name",
+            [&project.name],
+        )?;
+
+        let mut stored = HashMap::new();
+        let mut stale = Vec::new();
+        {
+            let mut stmt = tx.prepare("SELECT id, uid, digest, position, active FROM sessions")?;
+            let mut rows = stmt.query([])?;
+            while let Some(row) = rows.next()? {
+                // Rows without an ID were stored before IDs existed; they are
+                // written again with the ID given on load
+                let Some(uid) = row.get::<_, Option<String>>("uid")? else {
+                    stale.push(row.get("id")?);
+                    continue;
+                };
+                stored.insert(
+                    uid,
+                    StoredSession {
+                        id: row.get("id")?,
+                        digest: row.get("digest")?,
+                        position: row.get("position")?,
+                        active: row.get("active")?,
+                    },
+                );
+            }
+        }
+        tx.execute("DELETE FROM breaks WHERE current = 1", [])?;
+
+        for (index, session) in project.sessions.iter().enumerate() {
+            Self::store_session(&tx, &mut stored, session, index, false)?;
+        }
+        let active_id = match project.current_session {
+            Some(ref session) => Some(Self::store_session(&tx, &mut stored, session, 0, true)?),
+            None => None,
+        };
+        if let Some(ref break_period) = project.current_break {
+            Self::insert_break(&tx, active_id, break_period, 0, true)?;
+        }
+
+        // Whatever is left was deleted or merged away
+        stale.extend(stored.values().map(|row| row.id));
+        for session_id in stale {
+            Self::delete_details(&tx, session_id)?;
+            tx.execute("DELETE FROM sessions WHERE id = ?1", [session_id])?;
+        }
+
+        tx.commit().context("Failed to write project data")?;
+        Ok(())
+    }
+
+    fn lock(&self) -> Result<RepositoryLock> {
+        RepositoryLock::acquire(&self.data_dir, LOCK_TIMEOUT)
+    }
+
+    fn create_and_lock(&self) -> Result<RepositoryLock> {
+        RepositoryLock::create(&self.data_dir, LOCK_TIMEOUT)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::{Duration, TimeZone};
+    use tempfile::TempDir;
+
+    fn sample_project() -> Project {
+        let at = |d, h, m| Local.with_ymd_and_hms(2025, 10, d, h, m, 0).unwrap();
+        let mut project = Project::new("test-project".to_string());
+
+        let mut session = Session::new(at(13, 9, 0));
+        let mut break_period = Break::new(at(13, 12, 0));
+        break_period.finish(at(13, 12, 30));
+        break_period.note = Some("lunch".to_string());
+        session.add_break(break_period);
+        session.finish(at(13, 17, 0));
+        session.note = Some("API work".to_string());
+        session.tags = vec!["backend".to_string(), "review".to_string()];
+        session.add_commit(Commit {
+            hash: "3f9c2a1e".repeat(5),
+            subject: "Add login endpoint".to_string(),
+            branch: Some("feature/login".to_string()),
+            at: at(13, 11, 42),
+        });
+        session.add_checkout(Checkout {
+            branch: "main".to_string(),
+            at: at(13, 15, 10),
+        });
+        project.sessions.push(session);
+
+        project
+            .sessions
+            .push(Session::manual(at(14, 9, 0), at(14, 10, 0)));
+
+        project.start_session(at(15, 9, 0) + Duration::milliseconds(250));
+        project.start_break(at(15, 11, 0)).unwrap();
+        project.current_break.as_mut().unwrap().note = Some("coffee".to_string());
+
+        project
+    }
+
+    #[test]
+    fn test_save_and_load() {
+        let temp_dir = TempDir::new().unwrap();
+        let repo = SqliteRepository::new(temp_dir.path().join(".clockme"));
+
+        let project = sample_project();
+        repo.save(&project).unwrap();
+
+        let loaded = repo.load().unwrap();
+        assert_eq!(
+            serde_json::to_value(&loaded).unwrap(),
+            serde_json::to_value(&project).unwrap()
+        );
+    }
+
+    #[test]
+    fn test_save_replaces_previous_state() {
+        let temp_dir = TempDir::new().unwrap();
+        let repo = SqliteRepository::new(temp_dir.path().join(".clockme"));
+
+        repo.save(&sample_project()).unwrap();
+        let mut project = Project::new("renamed".to_string());
+        project.sessions.push(sample_project().sessions[1].clone());
+        repo.save(&project).unwrap();
+
+        let loaded = repo.load().unwrap();
+        assert_eq!(loaded.name, "renamed");
+        assert_eq!(loaded.sessions.len(), 1);
+        assert!(loaded.current_session.is_none());
+        assert!(loaded.current_break.is_none());
+    }
+
+    #[test]
+    fn test_save_only_rewrites_changed_sessions() {
+        let temp_dir = TempDir::new().unwrap();
+        let data_dir = temp_dir.path().join(".clockme");
+        let repo = SqliteRepository::new(data_dir.clone());
+        let mut project = sample_project();
+        repo.save(&project).unwrap();
+
+        // Edit the stored rows behind the repository's back: an unchanged
+        // session keeps them, a changed one is written again
+        let conn = Connection::open(data_dir.join("data.db")).unwrap();
+        conn.execute_batch(
+            "UPDATE breaks SET note = 'untouched' WHERE current = 0;
+             UPDATE sessions SET note = 'stale' WHERE note IS NULL AND active = 0;",
+        )
+        .unwrap();
+
+        project.sessions[1].note = Some("Planning".to_string());
+        repo.save(&project).unwrap();
+        let loaded = repo.load().unwrap();
+        assert_eq!(
+            loaded.sessions[0].breaks[0].note.as_deref(),
+            Some("untouched")
+        );
+        assert_eq!(loaded.sessions[1].note.as_deref(), Some("Planning"));
+
+        // A removed session takes its details with it
+        project.sessions.remove(0);
+        repo.save(&project).unwrap();
+        for table in SESSION_DETAILS {
+            let orphans: i64 = conn
+                .query_row(
+                    &format!(
+                        "SELECT COUNT(*) FROM {} WHERE session_id NOT IN (SELECT id FROM sessions)",
+                        table
+                    ),
+                    [],
+                    |row| row.get(0),
+                )
+                .unwrap();
+            assert_eq!(orphans, 0, "{}", table);
+        }
+        let loaded = repo.load().unwrap();
+        assert_eq!(loaded.sessions.len(), 1);
+        assert_eq!(loaded.sessions[0].id, project.sessions[0].id);
+    }
+
+    #[test]
+    fn test_load_without_database() {
+        let temp_dir = TempDir::new().unwrap();
+        let repo = SqliteRepository::new(temp_dir.path().join(".clockme"));
+
+        assert!(repo.load().is_err());
+        assert!(!temp_dir.path().join(".clockme").exists());
+    }
+
+    #[test]
+    fn test_upgrade_database_without_ids() {
+        let temp_dir = TempDir::new().unwrap();
+        let data_dir = temp_dir.path().join(".clockme");
+        fs::create_dir_all(&data_dir).unwrap();
+
+        let conn = Connection::open(data_dir.join("data.db")).unwrap();
+        conn.execute_batch(
+            "CREATE TABLE project (id INTEGER PRIMARY KEY CHECK (id = 1), name TEXT NOT NULL);
+             CREATE TABLE sessions (id INTEGER PRIMARY KEY, position INTEGER NOT NULL,
+                 active INTEGER NOT NULL DEFAULT 0, start TEXT NOT NULL, end TEXT,
+                 origin TEXT NOT NULL, note TEXT);
+             CREATE TABLE breaks (id INTEGER PRIMARY KEY, session_id INTEGER, position INTEGER NOT NULL,
+                 current INTEGER NOT NULL DEFAULT 0, start TEXT NOT NULL, end TEXT, note TEXT);
+             CREATE TABLE session_tags (session_id INTEGER NOT NULL, position INTEGER NOT NULL,
+                 tag TEXT NOT NULL, PRIMARY KEY (session_id, position));
+             INSERT INTO project VALUES (1, 'old-project');
+             INSERT INTO sessions VALUES (1, 0, 0, '2025-10-13T09:00:00+00:00',
+                 '2025-10-13T17:00:00+00:00', 'clocked', NULL);
+             PRAGMA user_version = 1;",
+        )
+        .unwrap();
+        drop(conn);
+
+        let repo = SqliteRepository::new(data_dir);
+        let first = repo.load().unwrap();
+        let second = repo.load().unwrap();
+
+        assert!(!first.sessions[0].id.is_nil());
+        assert_eq!(first.sessions[0].id, second.sessions[0].id);
+
+        repo.save(&first).unwrap();
+        assert_eq!(repo.load().unwrap().sessions[0].id, first.sessions[0].id);
+    }
+}
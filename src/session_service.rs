@@ -1,19 +1,33 @@
 use crate::clock::Clock;
+use crate::models::commit::{Checkout, Commit};
+use crate::models::id;
+use crate::models::integrity::{self, Issue};
 use crate::models::project::Project;
+use crate::models::r#break::Break;
 use crate::models::session::Session;
-use crate::repository::Repository;
-use anyhow::{anyhow, Result};
-use chrono::{DateTime, Duration, Local, Timelike};
+use crate::parsers::TimeSpec;
+use crate::repository::history::{History, HistoryEntry};
+use crate::repository::{self, Repository};
+use crate::validators::TimestampValidator;
+use anyhow::{anyhow, Context, Result};
+use chrono::{DateTime, Datelike, Duration, Local, NaiveDate, NaiveTime};
+use std::collections::BTreeMap;
+use std::ops::AddAssign;
+use std::path::{Path, PathBuf};
+use uuid::Uuid;
 
 pub struct SessionService {
     repository: Box<dyn Repository>,
     clock: Box<dyn Clock>,
+    history: Option<History>,
 }
 
 pub struct StatusInfo {
     pub project_name: String,
     pub current_session: Option<Session>,
     pub current_break_start: Option<DateTime<Local>>,
+    /// The running break as stored, with its ID and reason
+    pub current_break: Option<Break>,
     pub last_session: Option<Session>,
     pub total_sessions: usize,
     pub current_time: DateTime<Local>,
@@ -23,15 +37,254 @@ pub struct StatusInfo {
     pub today_breaks: usize,
     pub total_work_time: Duration,
     pub total_break_time: Duration,
+    pub total_breaks: usize,
+}
+
+/// Work and break totals for a group of sessions
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct TimeStats {
+    pub work_time: Duration,
+    pub break_time: Duration,
+    pub sessions: usize,
+    pub breaks: usize,
+    /// Sessions entered with `clock-me add` rather than clocked live
+    pub manual_sessions: usize,
+}
+
+impl TimeStats {
+    pub fn zero() -> Self {
+        Self {
+            work_time: Duration::zero(),
+            break_time: Duration::zero(),
+            sessions: 0,
+            breaks: 0,
+            manual_sessions: 0,
+        }
+    }
+}
+
+impl AddAssign for TimeStats {
+    fn add_assign(&mut self, other: Self) {
+        self.work_time += other.work_time;
+        self.break_time += other.break_time;
+        self.sessions += other.sessions;
+        self.breaks += other.breaks;
+        self.manual_sessions += other.manual_sessions;
+    }
+}
+
+/// How sessions are grouped in a report
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ReportPeriod {
+    Day,
+    Week,
+    Month,
+}
+
+impl ReportPeriod {
+    /// Returns the first day of the bucket that contains `date`
+    pub fn bucket_start(&self, date: NaiveDate) -> NaiveDate {
+        match self {
+            ReportPeriod::Day => date,
+            ReportPeriod::Week => {
+                date - Duration::days(date.weekday().num_days_from_monday() as i64)
+            }
+            ReportPeriod::Month => date.with_day(1).unwrap(),
+        }
+    }
+}
+
+impl std::str::FromStr for ReportPeriod {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self> {
+        match s.trim().to_lowercase().as_str() {
+            "day" => Ok(ReportPeriod::Day),
+            "week" => Ok(ReportPeriod::Week),
+            "month" => Ok(ReportPeriod::Month),
+            _ => Err(anyhow!(
+                "Invalid report period '{}'. Use 'day', 'week' or 'month'",
+                s
+            )),
+        }
+    }
+}
+
+/// A single session as seen by status and reports
+#[derive(Debug, Clone)]
+pub struct SessionEntry {
+    pub id: Uuid,
+    pub start: DateTime<Local>,
+    /// `None` for the active session
+    pub end: Option<DateTime<Local>>,
+    pub stats: TimeStats,
+    pub note: Option<String>,
+    /// Notes of the session's breaks, including the one running now
+    pub break_notes: Vec<String>,
+    pub tags: Vec<String>,
+    pub commits: Vec<Commit>,
+    pub checkouts: Vec<Checkout>,
+}
+
+/// Time spent on one tag; `tag` is `None` for untagged sessions
+pub struct TagTotal {
+    pub tag: Option<String>,
+    pub stats: TimeStats,
+}
+
+pub struct ReportBucket {
+    /// First day of the bucket (the day itself, Monday of the ISO week, or the 1st of the month)
+    pub start: NaiveDate,
+    pub stats: TimeStats,
+    /// Notes of the sessions in this bucket and of their breaks, marked
+    /// "(break)", in chronological order
+    pub notes: Vec<String>,
+    /// Sessions in this bucket with commits or branch switches, in
+    /// chronological order
+    pub commits: Vec<SessionCommits>,
+}
+
+/// Commits and branch switches recorded during one session
+pub struct SessionCommits {
+    pub id: Uuid,
+    pub start: DateTime<Local>,
+    /// `None` for the active session
+    pub end: Option<DateTime<Local>>,
+    pub commits: Vec<Commit>,
+    pub checkouts: Vec<Checkout>,
+}
+
+pub struct Report {
+    pub project_name: String,
+    pub period: ReportPeriod,
+    pub from: NaiveDate,
+    pub to: NaiveDate,
+    pub buckets: Vec<ReportBucket>,
+    /// Per-tag breakdown. A session with several tags counts towards each of them,
+    /// so these may add up to more than `total`.
+    pub tags: Vec<TagTotal>,
+    pub total: TimeStats,
+}
+
+/// Outcome of `start`: a new session, or the active one continued after a break
+#[derive(Debug, Clone)]
+pub struct ClockIn {
+    pub project_name: String,
+    pub session: Session,
+    /// The break that was ended, when resuming
+    pub ended_break: Option<Break>,
+}
+
+/// Outcome of `stop`
+#[derive(Debug, Clone)]
+pub struct ClockOut {
+    pub project_name: String,
+    pub session: Session,
+    pub work_time: Duration,
+}
+
+/// Outcome of `break`
+#[derive(Debug, Clone)]
+pub struct BreakStarted {
+    pub project_name: String,
+    /// The session being paused
+    pub session: Session,
+    pub break_period: Break,
+    /// Work time of the session up to the break
+    pub work_time: Duration,
+}
+
+/// What the post-commit hook did with a commit
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CommitOutcome {
+    /// Attached to the active session
+    Recorded,
+    /// Attached to a session started for it
+    Started,
+    /// Clocked out without auto-start, or already recorded
+    Ignored,
+}
+
+/// Outcome of merging imported sessions into the project
+pub struct ImportSummary {
+    pub imported: usize,
+    /// Sessions already present with the same start and end
+    pub skipped: usize,
+}
+
+/// Outcome of `clock-me doctor --fix`
+pub struct RepairSummary {
+    /// Copy of the project taken before any change, if one was needed
+    pub backup: Option<PathBuf>,
+    pub repairs: Vec<String>,
+    /// Issues that need a decision and were left as they are
+    pub remaining: Vec<Issue>,
 }
 
 impl SessionService {
     pub fn new(repository: Box<dyn Repository>, clock: Box<dyn Clock>) -> Self {
-        Self { repository, clock }
+        Self {
+            repository,
+            clock,
+            history: None,
+        }
+    }
+
+    /// Records every change so it can be undone
+    pub fn with_history(mut self, history: History) -> Self {
+        self.history = Some(history);
+        self
+    }
+
+    /// Saves the project, remembering `before`, the state it was loaded in,
+    /// for undo
+    fn save(&self, before: &Project, project: &Project) -> Result<()> {
+        self.repository.save(project)?;
+        if let Some(ref history) = self.history {
+            history
+                .record(before, project, self.clock.now())
+                .context("Saved, but failed to update the undo history")?;
+        }
+        Ok(())
+    }
+
+    /// Reverts the last `steps` changes; returns them newest first
+    pub fn undo(&self, steps: usize) -> Result<Vec<HistoryEntry>> {
+        self.travel(steps, true)
+    }
+
+    /// Reapplies the last `steps` undone changes; returns them oldest first
+    pub fn redo(&self, steps: usize) -> Result<Vec<HistoryEntry>> {
+        self.travel(steps, false)
+    }
+
+    fn travel(&self, steps: usize, undo: bool) -> Result<Vec<HistoryEntry>> {
+        let history = self
+            .history
+            .as_ref()
+            .ok_or_else(|| anyhow!("Undo history is not available"))?;
+        let _lock = self.repository.lock()?;
+        let mut project = self
+            .repository
+            .load()
+            .map_err(|_| anyhow!("No project found. Run 'clock-me init' first."))?;
+
+        let mut log = history.load()?;
+        let entries = if undo {
+            log.undo(&mut project, steps)?
+        } else {
+            log.redo(&mut project, steps)?
+        };
+
+        self.repository.save(&project)?;
+        history.save(&log)?;
+        Ok(entries)
     }
 
     pub fn init_project(&self, name: String) -> Result<()> {
-        // Check if project already exists
+        // Locked before the check, so of two concurrent inits only one creates
+        // the project
+        let _lock = self.repository.create_and_lock()?;
         if self.repository.load().is_ok() {
             return Err(anyhow!("Project already initialized in this directory. Use a different directory or delete .clockme folder."));
         }
@@ -41,208 +294,1990 @@ impl SessionService {
         Ok(())
     }
 
-    pub fn start_session(&self) -> Result<Project> {
+    pub fn start_session(
+        &self,
+        at: Option<TimeSpec>,
+        note: Option<String>,
+        tags: Vec<String>,
+    ) -> Result<ClockIn> {
+        let _lock = self.repository.lock()?;
         let mut project = self
             .repository
             .load()
             .map_err(|_| anyhow!("No project found. Run 'clock-me init' first."))?;
+        let before = project.clone();
+
+        let time = self.resolve_time(at)?;
+
+        if let Some(ref break_period) = project.current_break {
+            TimestampValidator::validate(
+                time,
+                self.clock.now(),
+                Some((break_period.start, "the break start")),
+            )?;
+            project.end_break(time)?;
+            let session = project
+                .current_session
+                .as_mut()
+                .ok_or_else(|| anyhow!("No active session"))?;
+            if let Some(note) = note {
+                session.append_note(&note);
+            }
+            session.add_tags(&tags);
+            let session = session.clone();
+            self.save(&before, &project)?;
 
-        if project.is_on_break() {
-            let now = self.clock.now();
-            project.end_break(now)?;
-            self.repository.save(&project)?;
-            return Ok(project);
+            return Ok(ClockIn {
+                project_name: project.name,
+                ended_break: session.breaks.iter().max_by_key(|b| b.end).cloned(),
+                session,
+            });
         }
 
         if project.current_session.is_some() {
             return Err(anyhow!("Already clocked in. Use 'clock-me stop' first."));
         }
 
-        let now = self.clock.now();
-        project.start_session(now);
-        self.repository.save(&project)?;
+        TimestampValidator::validate(
+            time,
+            self.clock.now(),
+            project
+                .last_session_end()
+                .map(|end| (end, "the end of the previous session")),
+        )?;
+        project.start_session(time);
+        let session = project
+            .current_session
+            .as_mut()
+            .ok_or_else(|| anyhow!("No active session"))?;
+        session.note = note;
+        session.add_tags(&tags);
+        let session = session.clone();
+        self.save(&before, &project)?;
 
-        Ok(project)
+        Ok(ClockIn {
+            project_name: project.name,
+            session,
+            ended_break: None,
+        })
     }
 
-    pub fn end_session(&self) -> Result<(Project, Duration)> {
+    pub fn end_session(&self, at: Option<TimeSpec>, note: Option<String>) -> Result<ClockOut> {
+        let _lock = self.repository.lock()?;
         let mut project = self
             .repository
             .load()
             .map_err(|_| anyhow!("No project found. Run 'clock-me init' first."))?;
+        let before = project.clone();
 
         if project.current_session.is_none() {
             return Err(anyhow!("Not clocked in. Use 'clock-me now' first."));
         }
 
-        let now = self.clock.now();
-        let duration = project.end_session(now)?;
-        self.repository.save(&project)?;
+        let time = self.resolve_time(at)?;
+        TimestampValidator::validate(time, self.clock.now(), Self::active_since(&project))?;
+
+        if let (Some(note), Some(session)) = (note, project.current_session.as_mut()) {
+            session.append_note(&note);
+        }
+        let work_time = project.end_session(time)?;
+        self.save(&before, &project)?;
 
-        Ok((project, duration))
+        let session = project
+            .sessions
+            .pop()
+            .ok_or_else(|| anyhow!("No finished session"))?;
+        Ok(ClockOut {
+            project_name: project.name,
+            session,
+            work_time,
+        })
     }
 
-    pub fn start_break(&self) -> Result<(Project, Duration)> {
+    pub fn start_break(&self, at: Option<TimeSpec>, note: Option<String>) -> Result<BreakStarted> {
+        let _lock = self.repository.lock()?;
         let mut project = self
             .repository
             .load()
             .map_err(|_| anyhow!("No project found. Run 'clock-me init' first."))?;
+        let before = project.clone();
 
-        let now = self.clock.now();
-        project.start_break(now)?;
+        let time = self.resolve_time(at)?;
+        if !project.is_on_break() {
+            TimestampValidator::validate(time, self.clock.now(), Self::active_since(&project))?;
+        }
+        project.start_break(time)?;
+        if let Some(break_period) = project.current_break.as_mut() {
+            break_period.note = note;
+        }
+        self.save(&before, &project)?;
+
+        let (Some(session), Some(break_period)) = (project.current_session, project.current_break)
+        else {
+            return Err(anyhow!("No active break"));
+        };
+        // Work time so far, up to the break
+        let work_time = time.signed_duration_since(session.start) - session.total_break_time();
+
+        Ok(BreakStarted {
+            project_name: project.name,
+            session,
+            break_period,
+            work_time,
+        })
+    }
+
+    /// Sets the note of the active session, or of the last finished one when clocked out.
+    /// With `append`, the text is added to the existing note instead of replacing it.
+    pub fn set_note(&self, note: String, append: bool) -> Result<Session> {
+        let _lock = self.repository.lock()?;
+        let mut project = self
+            .repository
+            .load()
+            .map_err(|_| anyhow!("No project found. Run 'clock-me init' first."))?;
+        let before = project.clone();
+
+        let session = match project.current_session.as_mut() {
+            Some(session) => session,
+            None => project
+                .sessions
+                .last_mut()
+                .ok_or_else(|| anyhow!("No sessions yet. Use 'clock-me start' first."))?,
+        };
 
-        // Calculate session work time so far (before break)
-        let session_work_time = if let Some(ref session) = project.current_session {
-            let elapsed = now.signed_duration_since(session.start);
-            let break_time = session.total_break_time();
-            elapsed - break_time
+        if append {
+            session.append_note(&note);
         } else {
-            Duration::zero()
+            session.note = Some(note);
+        }
+        let session = session.clone();
+
+        self.save(&before, &project)?;
+        Ok(session)
+    }
+
+    /// Adds or removes tags on a session.
+    ///
+    /// `session` is a finished session's ID, or a number counting back from the
+    /// most recent one (1 = last); when omitted the active session is used, or
+    /// the last one when clocked out.
+    pub fn tag_session(
+        &self,
+        tags: Vec<String>,
+        session: Option<String>,
+        remove: bool,
+    ) -> Result<Session> {
+        let _lock = self.repository.lock()?;
+        let mut project = self
+            .repository
+            .load()
+            .map_err(|_| anyhow!("No project found. Run 'clock-me init' first."))?;
+        let before = project.clone();
+
+        let count = project.sessions.len();
+        let counted_back = session
+            .as_deref()
+            .filter(|s| s.len() < id::MIN_PREFIX_LEN)
+            .and_then(|s| s.parse::<usize>().ok());
+        let target = match (counted_back, session) {
+            (Some(0), _) => return Err(anyhow!("Session numbers start at 1 (the last session)")),
+            (Some(n), _) if n > count => {
+                return Err(anyhow!("Only {} finished sessions recorded", count));
+            }
+            (Some(n), _) => &mut project.sessions[count - n],
+            (None, Some(query)) => {
+                let index = project.find_session(&query)?;
+                &mut project.sessions[index]
+            }
+            (None, None) => match project.current_session.as_mut() {
+                Some(active) => active,
+                None => project
+                    .sessions
+                    .last_mut()
+                    .ok_or_else(|| anyhow!("No sessions yet. Use 'clock-me start' first."))?,
+            },
         };
 
-        self.repository.save(&project)?;
+        if remove {
+            target.remove_tags(&tags);
+        } else {
+            target.add_tags(&tags);
+        }
+        let target = target.clone();
 
-        Ok((project, session_work_time))
+        self.save(&before, &project)?;
+        Ok(target)
     }
 
-    pub fn get_status(&self) -> Result<StatusInfo> {
-        let project = self
+    /// Lists the last `limit` finished sessions that started between `from`
+    /// and `to` (inclusive), oldest first
+    pub fn get_log(
+        &self,
+        from: Option<NaiveDate>,
+        to: Option<NaiveDate>,
+        limit: usize,
+    ) -> Result<(String, Vec<Session>)> {
+        let (name, mut sessions) = self.get_sessions(from, to)?;
+        sessions.drain(..sessions.len().saturating_sub(limit));
+        Ok((name, sessions))
+    }
+
+    /// Moves the start and/or end of a finished session. A bare time of day
+    /// falls on the date the session started.
+    pub fn edit_session(
+        &self,
+        id: &str,
+        start: Option<TimeSpec>,
+        end: Option<TimeSpec>,
+    ) -> Result<Session> {
+        self.update_session(id, |session, resolve| {
+            if let Some(ref start) = start {
+                session.start = resolve(start)?;
+            }
+            if let Some(ref end) = end {
+                session.end = Some(resolve(end)?);
+            }
+            Ok(())
+        })
+    }
+
+    /// Moves the start and/or end of a break, given by number (from 1) or ID,
+    /// of a finished session
+    pub fn edit_break(
+        &self,
+        id: &str,
+        break_id: &str,
+        start: Option<TimeSpec>,
+        end: Option<TimeSpec>,
+    ) -> Result<Session> {
+        self.update_session(id, |session, resolve| {
+            let index = session.find_break(break_id)?;
+            let break_period = &mut session.breaks[index];
+            if let Some(ref start) = start {
+                break_period.start = resolve(start)?;
+            }
+            if let Some(ref end) = end {
+                break_period.end = Some(resolve(end)?);
+            }
+            session.breaks.sort_by_key(|b| b.start);
+            Ok(())
+        })
+    }
+
+    /// Removes a finished session from the history
+    pub fn delete_session(&self, id: &str) -> Result<Session> {
+        let _lock = self.repository.lock()?;
+        let mut project = self
             .repository
             .load()
             .map_err(|_| anyhow!("No project found. Run 'clock-me init' first."))?;
+        let before = project.clone();
 
-        let current_time = self.clock.now();
-        let last_session = project.sessions.last().cloned();
+        let index = project.find_session(id)?;
+        let session = project.sessions.remove(index);
 
-        // Calculate today's stats
-        let today_start = current_time
-            .date_naive()
-            .and_hms_opt(0, 0, 0)
-            .unwrap()
-            .and_local_timezone(Local)
-            .unwrap();
+        self.save(&before, &project)?;
+        Ok(session)
+    }
 
-        let (today_work_time, today_break_time, today_sessions, today_breaks) =
-            self.calculate_today_stats(&project, today_start, current_time);
+    /// Removes a break, given by number (from 1) or ID, from a finished session
+    pub fn delete_break(&self, id: &str, break_id: &str) -> Result<Break> {
+        let mut removed = None;
+        self.update_session(id, |session, _| {
+            let index = session.find_break(break_id)?;
+            removed = Some(session.breaks.remove(index));
+            Ok(())
+        })?;
+        removed.ok_or_else(|| anyhow!("Break {} not found", break_id))
+    }
+
+    /// Splits a finished session in two at `at`, resolved on the session's date
+    pub fn split_session(&self, id: &str, at: TimeSpec) -> Result<(Session, Session)> {
+        let _lock = self.repository.lock()?;
+        let mut project = self
+            .repository
+            .load()
+            .map_err(|_| anyhow!("No project found. Run 'clock-me init' first."))?;
+        let before = project.clone();
 
-        // Calculate total stats (including current session if active)
-        let mut total_work_time = project.total_work_time();
-        let mut total_break_time = project.total_break_time();
+        let index = project.find_session(id)?;
+        let at = at.resolve_on(project.sessions[index].start.date_naive(), self.clock.now())?;
+        let halves = project.split_session(index, at)?;
 
-        if let Some(ref session) = project.current_session {
-            let elapsed = current_time.signed_duration_since(session.start);
-            let session_break_time = session.total_break_time();
+        self.save(&before, &project)?;
+        Ok(halves)
+    }
 
-            // Add current break time if on break
-            let current_break_time = if let Some(ref break_period) = project.current_break {
-                current_time.signed_duration_since(break_period.start)
-            } else {
-                Duration::zero()
-            };
+    /// Merges two consecutive finished sessions, keeping the gap as a break.
+    /// `force` allows merging across days or a long gap.
+    pub fn merge_sessions(&self, first: &str, second: &str, force: bool) -> Result<Session> {
+        let _lock = self.repository.lock()?;
+        let mut project = self
+            .repository
+            .load()
+            .map_err(|_| anyhow!("No project found. Run 'clock-me init' first."))?;
+        let before = project.clone();
 
-            total_work_time = total_work_time + elapsed - session_break_time - current_break_time;
-            total_break_time = total_break_time + session_break_time + current_break_time;
+        let a = project.find_session(first)?;
+        let b = project.find_session(second)?;
+        let merged = project.merge_sessions(a, b, force)?;
+
+        self.save(&before, &project)?;
+        Ok(merged)
+    }
+
+    /// Lists every inconsistency in the stored project. Data that cannot be
+    /// read is reported as the only issue rather than checked in a backup.
+    pub fn check_integrity(&self) -> Result<Vec<Issue>> {
+        match self.repository.load_strict() {
+            Ok(project) => Ok(integrity::check(&project, self.clock.now())),
+            Err(e) => Ok(vec![Issue::unreadable(&e)]),
         }
+    }
 
-        Ok(StatusInfo {
-            project_name: project.name.clone(),
-            current_session: project.current_session.clone(),
-            current_break_start: project.current_break.as_ref().map(|b| b.start),
-            last_session,
-            total_sessions: project.sessions.len(),
-            current_time,
-            today_work_time,
-            today_break_time,
-            today_sessions,
-            today_breaks,
-            total_work_time,
-            total_break_time,
+    /// Applies the safe repairs, after copying the project to `data_dir/backups`.
+    /// Unreadable data is left alone for the user to restore.
+    pub fn repair_integrity(&self, data_dir: &Path) -> Result<RepairSummary> {
+        let _lock = self.repository.lock()?;
+        let mut project = match self.repository.load_strict() {
+            Ok(project) => project,
+            Err(e) => {
+                return Ok(RepairSummary {
+                    backup: None,
+                    repairs: Vec::new(),
+                    remaining: vec![Issue::unreadable(&e)],
+                })
+            }
+        };
+
+        let now = self.clock.now();
+        let original = project.clone();
+        let repairs = integrity::repair(&mut project, now);
+        let remaining = integrity::check(&project, now);
+        if repairs.is_empty() {
+            return Ok(RepairSummary {
+                backup: None,
+                repairs,
+                remaining,
+            });
+        }
+
+        let backup = repository::backup_project(data_dir, &original, now)?;
+        self.save(&original, &project)?;
+        Ok(RepairSummary {
+            backup: Some(backup),
+            repairs,
+            remaining,
         })
     }
 
-    fn calculate_today_stats(
+    /// Attaches a commit reported by the post-commit hook to the active
+    /// session. While clocked out the commit is dropped, unless `auto_start`
+    /// starts a session for it.
+    pub fn record_commit(
         &self,
-        project: &Project,
-        today_start: DateTime<Local>,
-        current_time: DateTime<Local>,
-    ) -> (Duration, Duration, usize, usize) {
-        let mut work_time = Duration::zero();
-        let mut break_time = Duration::zero();
-        let mut session_count = 0;
-        let mut break_count = 0;
-
-        // Process completed sessions from today
-        for session in &project.sessions {
-            if session.start >= today_start {
-                session_count += 1;
-                if let Some(wt) = session.work_time() {
-                    work_time = work_time + wt;
-                }
-                break_time = break_time + session.total_break_time();
-                break_count += session.breaks.len();
-            }
-        }
+        hash: String,
+        subject: String,
+        branch: Option<String>,
+        auto_start: bool,
+    ) -> Result<CommitOutcome> {
+        let _lock = self.repository.lock()?;
+        let mut project = self
+            .repository
+            .load()
+            .map_err(|_| anyhow!("No project found. Run 'clock-me init' first."))?;
+        let before = project.clone();
 
-        // Add current session if it started today
-        if let Some(ref session) = project.current_session {
-            if session.start >= today_start {
-                session_count += 1;
+        let now = self.clock.now();
+        let outcome = if project.current_session.is_some() {
+            CommitOutcome::Recorded
+        } else if auto_start {
+            Self::start_now(&mut project, now)?;
+            CommitOutcome::Started
+        } else {
+            return Ok(CommitOutcome::Ignored);
+        };
 
-                let elapsed = current_time.signed_duration_since(session.start);
-                let session_break_time = session.total_break_time();
+        let session = project
+            .current_session
+            .as_mut()
+            .ok_or_else(|| anyhow!("No active session"))?;
+        let commit = Commit {
+            hash,
+            subject,
+            branch,
+            at: now,
+        };
+        if !session.add_commit(commit) {
+            return Ok(CommitOutcome::Ignored);
+        }
+        self.save(&before, &project)?;
+        Ok(outcome)
+    }
 
-                // Add current break time if on break
-                let current_break_time = if let Some(ref break_period) = project.current_break {
-                    current_time.signed_duration_since(break_period.start)
-                } else {
-                    Duration::zero()
-                };
+    /// Records a branch switch reported by the post-checkout hook on the
+    /// active session. Returns false while clocked out or when the session is
+    /// already on that branch.
+    pub fn record_checkout(&self, branch: String) -> Result<bool> {
+        let _lock = self.repository.lock()?;
+        let mut project = self
+            .repository
+            .load()
+            .map_err(|_| anyhow!("No project found. Run 'clock-me init' first."))?;
+        let before = project.clone();
+        let now = self.clock.now();
+        let Some(session) = project.current_session.as_mut() else {
+            return Ok(false);
+        };
 
-                work_time = work_time + elapsed - session_break_time - current_break_time;
-                break_time = break_time + session_break_time + current_break_time;
-                break_count += session.breaks.len();
+        if !session.add_checkout(Checkout { branch, at: now }) {
+            return Ok(false);
+        }
+        self.save(&before, &project)?;
+        Ok(true)
+    }
 
-                if project.current_break.is_some() {
-                    break_count += 1;
-                }
+    /// Applies the `(old, new)` hash pairs git passes to the post-rewrite
+    /// hook after `commit --amend` or `rebase`, so rewritten commits are not
+    /// listed twice. Returns how many recorded commits were affected.
+    pub fn rewrite_commits(&self, rewrites: &[(String, String)]) -> Result<usize> {
+        let _lock = self.repository.lock()?;
+        let mut project = self
+            .repository
+            .load()
+            .map_err(|_| anyhow!("No project found. Run 'clock-me init' first."))?;
+        let before = project.clone();
+
+        let mut rewritten = 0;
+        for (old, new) in rewrites {
+            if project.rewrite_commit(old, new) {
+                rewritten += 1;
             }
         }
+        if rewritten > 0 {
+            self.save(&before, &project)?;
+        }
+        Ok(rewritten)
+    }
 
-        (work_time, break_time, session_count, break_count)
+    fn start_now(project: &mut Project, now: DateTime<Local>) -> Result<()> {
+        TimestampValidator::validate(
+            now,
+            now,
+            project
+                .last_session_end()
+                .map(|end| (end, "the end of the previous session")),
+        )?;
+        project.start_session(now);
+        Ok(())
     }
-}
 
-#[cfg(test)]
-mod tests {
-    use super::*;
-    use crate::clock::MockClock;
-    use crate::repository::MockRepository;
-    use chrono::TimeZone;
+    /// Applies `edit` to a copy of session `id` and stores it if the result is
+    /// still valid and doesn't overlap other sessions
+    fn update_session<F>(&self, id: &str, edit: F) -> Result<Session>
+    where
+        F: FnOnce(&mut Session, &dyn Fn(&TimeSpec) -> Result<DateTime<Local>>) -> Result<()>,
+    {
+        let _lock = self.repository.lock()?;
+        let mut project = self
+            .repository
+            .load()
+            .map_err(|_| anyhow!("No project found. Run 'clock-me init' first."))?;
+        let before = project.clone();
 
-    #[test]
-    fn test_init_project() {
-        let mut mock_repo = MockRepository::new();
-        mock_repo
-            .expect_load()
-            .returning(|| Err(anyhow!("Not found")));
-        mock_repo.expect_save().returning(|_| Ok(()));
+        let now = self.clock.now();
+        let index = project.find_session(id)?;
+        let mut session = project.sessions[index].clone();
+        let date = session.start.date_naive();
 
-        let mock_clock = MockClock::new();
-        let service = SessionService::new(Box::new(mock_repo), Box::new(mock_clock));
+        let resolve = |spec: &TimeSpec| -> Result<DateTime<Local>> {
+            let time = spec.resolve_on(date, now)?;
+            TimestampValidator::validate(time, now, None)?;
+            Ok(time)
+        };
+        edit(&mut session, &resolve)?;
 
-        assert!(service.init_project("test-project".to_string()).is_ok());
+        project.replace_session(index, session.clone(), now)?;
+        self.save(&before, &project)?;
+        Ok(session)
     }
 
-    #[test]
-    fn test_start_session_without_init() {
-        let mut mock_repo = MockRepository::new();
-        mock_repo
-            .expect_load()
-            .returning(|| Err(anyhow!("Not found")));
+    fn check_range(from: Option<NaiveDate>, to: Option<NaiveDate>) -> Result<()> {
+        if let (Some(from), Some(to)) = (from, to) {
+            if from > to {
+                return Err(anyhow!(
+                    "Invalid range: {} is after {}",
+                    from.format("%Y-%m-%d"),
+                    to.format("%Y-%m-%d")
+                ));
+            }
+        }
+        Ok(())
+    }
 
-        let mock_clock = MockClock::new();
+    fn resolve_time(&self, at: Option<TimeSpec>) -> Result<DateTime<Local>> {
+        let now = self.clock.now();
+        match at {
+            Some(spec) => spec.resolve(now),
+            None => Ok(now),
+        }
+    }
+
+    /// Latest point of the active session that a new stop or break must not precede:
+    /// the current break start, the end of the last break, or the session start.
+    fn active_since(project: &Project) -> Option<(DateTime<Local>, &'static str)> {
+        if let Some(ref break_period) = project.current_break {
+            return Some((break_period.start, "the break start"));
+        }
+
+        let session = project.current_session.as_ref()?;
+        match session.breaks.iter().filter_map(|b| b.end).max() {
+            Some(end) if end > session.start => Some((end, "the end of the last break")),
+            _ => Some((session.start, "the session start")),
+        }
+    }
+
+    /// Records a completed session after the fact.
+    ///
+    /// `date` defaults to today. `at` is the start time; when omitted the entry
+    /// is placed so that it ends now, which is only allowed for today.
+    pub fn add_session(
+        &self,
+        duration: Duration,
+        date: Option<NaiveDate>,
+        at: Option<NaiveTime>,
+        note: Option<String>,
+        tags: Vec<String>,
+    ) -> Result<(Project, Session)> {
+        let _lock = self.repository.lock()?;
+        let mut project = self
+            .repository
+            .load()
+            .map_err(|_| anyhow!("No project found. Run 'clock-me init' first."))?;
+        let before = project.clone();
+
+        let now = self.clock.now();
+        let today = now.date_naive();
+        let date = date.unwrap_or(today);
+
+        let start = match at {
+            Some(time) => date
+                .and_time(time)
+                .and_local_timezone(Local)
+                .earliest()
+                .ok_or_else(|| anyhow!("{} {} does not exist in the local timezone", date, time))?,
+            None if date == today => now - duration,
+            None => {
+                return Err(anyhow!(
+                    "Specify a start time with --at when adding time to another day"
+                ))
+            }
+        };
+        let end = start + duration;
+
+        if end > now {
+            return Err(anyhow!(
+                "Entry would end in the future ({})",
+                end.format("%Y-%m-%d %H:%M")
+            ));
+        }
+
+        let mut session = Session::manual(start, end);
+        session.note = note;
+        session.add_tags(&tags);
+
+        project.add_session(session.clone(), now)?;
+        self.save(&before, &project)?;
+
+        Ok((project, session))
+    }
+
+    /// Merges finished sessions from another tracker into the history.
+    ///
+    /// Sessions identical to recorded ones are skipped so the same file can be
+    /// imported twice. Any other overlap aborts the whole import without saving.
+    pub fn import_sessions(&self, sessions: Vec<Session>) -> Result<ImportSummary> {
+        let _lock = self.repository.lock()?;
+        let mut project = self
+            .repository
+            .load()
+            .map_err(|_| anyhow!("No project found. Run 'clock-me init' first."))?;
+        let before = project.clone();
+
+        let now = self.clock.now();
+        let mut summary = ImportSummary {
+            imported: 0,
+            skipped: 0,
+        };
+
+        for session in sessions {
+            let label = session.start.format("%Y-%m-%d %H:%M").to_string();
+
+            if session.end.is_some_and(|end| end > now) {
+                return Err(anyhow!("Session starting {} ends in the future", label));
+            }
+
+            if project
+                .sessions
+                .iter()
+                .any(|s| s.start == session.start && s.end == session.end)
+            {
+                summary.skipped += 1;
+                continue;
+            }
+
+            project
+                .add_session(session, now)
+                .with_context(|| format!("Cannot import session starting {}", label))?;
+            summary.imported += 1;
+        }
+
+        if summary.imported > 0 {
+            self.save(&before, &project)?;
+        }
+
+        Ok(summary)
+    }
+
+    pub fn get_status(&self) -> Result<StatusInfo> {
+        let project = self
+            .repository
+            .load()
+            .map_err(|_| anyhow!("No project found. Run 'clock-me init' first."))?;
+
+        Ok(self.status_of(&project))
+    }
+
+    /// The current time from the service's clock
+    pub fn now(&self) -> DateTime<Local> {
+        self.clock.now()
+    }
+
+    /// Status of a loaded project, possibly another one, as seen from this
+    /// service's clock
+    pub fn status_of(&self, project: &Project) -> StatusInfo {
+        Self::status_at(project, self.clock.now())
+    }
+
+    /// Status of an already loaded project at `current_time`
+    pub fn status_at(project: &Project, current_time: DateTime<Local>) -> StatusInfo {
+        let last_session = project.sessions.last().cloned();
+
+        // Calculate today's stats
+        let today_start = current_time
+            .date_naive()
+            .and_hms_opt(0, 0, 0)
+            .unwrap()
+            .and_local_timezone(Local)
+            .unwrap();
+
+        let entries = Self::collect_stats(project, current_time);
+
+        let mut today = TimeStats::zero();
+        let mut total = TimeStats::zero();
+        for entry in &entries {
+            if entry.start >= today_start {
+                today += entry.stats;
+            }
+            total += entry.stats;
+        }
+
+        StatusInfo {
+            project_name: project.name.clone(),
+            current_session: project.current_session.clone(),
+            current_break_start: project.current_break.as_ref().map(|b| b.start),
+            current_break: project.current_break.clone(),
+            last_session,
+            total_sessions: project.sessions.len(),
+            current_time,
+            today_work_time: today.work_time,
+            today_break_time: today.break_time,
+            today_sessions: today.sessions,
+            today_breaks: today.breaks,
+            total_work_time: total.work_time,
+            total_break_time: total.break_time,
+            total_breaks: total.breaks,
+        }
+    }
+
+    /// Builds a report of sessions that started between `from` and `to` (inclusive),
+    /// grouped by `period`.
+    ///
+    /// Defaults when a bound is omitted:
+    /// - `to`: today
+    /// - `from`: start of the current week (day), month (week) or year (month)
+    pub fn get_report(
+        &self,
+        period: ReportPeriod,
+        from: Option<NaiveDate>,
+        to: Option<NaiveDate>,
+    ) -> Result<Report> {
+        let project = self
+            .repository
+            .load()
+            .map_err(|_| anyhow!("No project found. Run 'clock-me init' first."))?;
+
+        let current_time = self.clock.now();
+        let to = to.unwrap_or_else(|| current_time.date_naive());
+        let from = from.unwrap_or_else(|| match period {
+            ReportPeriod::Day => ReportPeriod::Week.bucket_start(to),
+            ReportPeriod::Week => ReportPeriod::Month.bucket_start(to),
+            ReportPeriod::Month => to.with_ordinal(1).unwrap(),
+        });
+
+        Self::check_range(Some(from), Some(to))?;
+
+        let entries = Self::collect_stats(&project, current_time);
+        let (buckets, total) = Self::aggregate(&entries, period, from, to);
+        let tags = Self::aggregate_by_tag(&entries, from, to);
+
+        Ok(Report {
+            project_name: project.name,
+            period,
+            from,
+            to,
+            buckets,
+            tags,
+            total,
+        })
+    }
+
+    /// Returns the project name and the finished sessions that started between
+    /// `from` and `to` (inclusive). Missing bounds are open-ended.
+    pub fn get_sessions(
+        &self,
+        from: Option<NaiveDate>,
+        to: Option<NaiveDate>,
+    ) -> Result<(String, Vec<Session>)> {
+        let project = self
+            .repository
+            .load()
+            .map_err(|_| anyhow!("No project found. Run 'clock-me init' first."))?;
+
+        Self::check_range(from, to)?;
+
+        let sessions = project
+            .sessions
+            .into_iter()
+            .filter(|session| {
+                let date = session.start.date_naive();
+                from.is_none_or(|from| date >= from) && to.is_none_or(|to| date <= to)
+            })
+            .collect();
+
+        Ok((project.name, sessions))
+    }
+
+    /// Groups per-session stats into buckets by the local date each session started on.
+    /// Sessions outside `from..=to` are skipped; empty buckets are not returned.
+    pub fn aggregate(
+        entries: &[SessionEntry],
+        period: ReportPeriod,
+        from: NaiveDate,
+        to: NaiveDate,
+    ) -> (Vec<ReportBucket>, TimeStats) {
+        let mut grouped: BTreeMap<NaiveDate, ReportBucket> = BTreeMap::new();
+        let mut total = TimeStats::zero();
+
+        let mut entries: Vec<&SessionEntry> = entries.iter().collect();
+        entries.sort_by_key(|entry| entry.start);
+
+        for entry in entries {
+            let date = entry.start.date_naive();
+            if date < from || date > to {
+                continue;
+            }
+
+            let bucket_start = period.bucket_start(date);
+            let bucket = grouped.entry(bucket_start).or_insert_with(|| ReportBucket {
+                start: bucket_start,
+                stats: TimeStats::zero(),
+                notes: Vec::new(),
+                commits: Vec::new(),
+            });
+            bucket.stats += entry.stats;
+            if let Some(ref note) = entry.note {
+                bucket.notes.push(note.clone());
+            }
+            for note in &entry.break_notes {
+                bucket.notes.push(format!("{} (break)", note));
+            }
+            if !entry.commits.is_empty() || !entry.checkouts.is_empty() {
+                bucket.commits.push(SessionCommits {
+                    id: entry.id,
+                    start: entry.start,
+                    end: entry.end,
+                    commits: entry.commits.clone(),
+                    checkouts: entry.checkouts.clone(),
+                });
+            }
+            total += entry.stats;
+        }
+
+        (grouped.into_values().collect(), total)
+    }
+
+    /// Sums sessions that started between `from` and `to` (inclusive) per tag,
+    /// sorted by tag name with untagged sessions last.
+    pub fn aggregate_by_tag(
+        entries: &[SessionEntry],
+        from: NaiveDate,
+        to: NaiveDate,
+    ) -> Vec<TagTotal> {
+        let mut grouped: BTreeMap<String, TimeStats> = BTreeMap::new();
+        let mut untagged = TimeStats::zero();
+
+        for entry in entries {
+            let date = entry.start.date_naive();
+            if date < from || date > to {
+                continue;
+            }
+
+            if entry.tags.is_empty() {
+                untagged += entry.stats;
+            }
+            for tag in &entry.tags {
+                *grouped.entry(tag.clone()).or_insert_with(TimeStats::zero) += entry.stats;
+            }
+        }
+
+        let mut totals: Vec<TagTotal> = grouped
+            .into_iter()
+            .map(|(tag, stats)| TagTotal {
+                tag: Some(tag),
+                stats,
+            })
+            .collect();
+        if untagged.sessions > 0 {
+            totals.push(TagTotal {
+                tag: None,
+                stats: untagged,
+            });
+        }
+        totals
+    }
+
+    /// Returns an entry for every session, including the active one
+    /// measured up to `current_time`.
+    pub fn collect_stats(project: &Project, current_time: DateTime<Local>) -> Vec<SessionEntry> {
+        let mut entries: Vec<SessionEntry> = project
+            .sessions
+            .iter()
+            .map(|session| {
+                let stats = TimeStats {
+                    work_time: session.work_time().unwrap_or_else(Duration::zero),
+                    break_time: session.total_break_time(),
+                    sessions: 1,
+                    breaks: session.breaks.len(),
+                    manual_sessions: usize::from(session.is_manual()),
+                };
+                SessionEntry {
+                    id: session.id,
+                    start: session.start,
+                    end: session.end,
+                    stats,
+                    note: session.note.clone(),
+                    break_notes: Self::break_notes(session.breaks.iter()),
+                    tags: session.tags.clone(),
+                    commits: session.commits.clone(),
+                    checkouts: session.checkouts.clone(),
+                }
+            })
+            .collect();
+
+        if let Some(ref session) = project.current_session {
+            let elapsed = current_time.signed_duration_since(session.start);
+            let session_break_time = session.total_break_time();
+
+            // Add current break time if on break
+            let current_break_time = if let Some(ref break_period) = project.current_break {
+                current_time.signed_duration_since(break_period.start)
+            } else {
+                Duration::zero()
+            };
+
+            let mut breaks = session.breaks.len();
+            if project.current_break.is_some() {
+                breaks += 1;
+            }
+
+            let stats = TimeStats {
+                work_time: elapsed - session_break_time - current_break_time,
+                break_time: session_break_time + current_break_time,
+                sessions: 1,
+                breaks,
+                manual_sessions: 0,
+            };
+            entries.push(SessionEntry {
+                id: session.id,
+                start: session.start,
+                end: None,
+                stats,
+                note: session.note.clone(),
+                break_notes: Self::break_notes(
+                    session.breaks.iter().chain(project.current_break.as_ref()),
+                ),
+                tags: session.tags.clone(),
+                commits: session.commits.clone(),
+                checkouts: session.checkouts.clone(),
+            });
+        }
+
+        entries
+    }
+
+    fn break_notes<'a>(breaks: impl Iterator<Item = &'a Break>) -> Vec<String> {
+        breaks.filter_map(|b| b.note.clone()).collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::clock::{MockClock, SystemClock};
+    use crate::models::r#break::Break;
+    use crate::repository::lock::RepositoryLock;
+    use crate::repository::{FileRepository, MockRepository};
+    use chrono::TimeZone;
+
+    fn completed_session(
+        start: DateTime<Local>,
+        end: DateTime<Local>,
+        break_minutes: i64,
+    ) -> Session {
+        let mut session = Session::new(start);
+        if break_minutes > 0 {
+            let mut break_period = Break::new(start + Duration::hours(1));
+            break_period.finish(start + Duration::hours(1) + Duration::minutes(break_minutes));
+            session.add_break(break_period);
+        }
+        session.finish(end);
+        session
+    }
+
+    fn sample_project() -> Project {
+        let mut project = Project::new("test-project".to_string());
+        // Monday and Tuesday of ISO week 42, then Monday of week 43
+        project.sessions.push(completed_session(
+            Local.with_ymd_and_hms(2026, 10, 12, 9, 0, 0).unwrap(),
+            Local.with_ymd_and_hms(2026, 10, 12, 13, 0, 0).unwrap(),
+            30,
+        ));
+        project.sessions.push(completed_session(
+            Local.with_ymd_and_hms(2026, 10, 13, 9, 0, 0).unwrap(),
+            Local.with_ymd_and_hms(2026, 10, 13, 11, 0, 0).unwrap(),
+            0,
+        ));
+        project.sessions.push(completed_session(
+            Local.with_ymd_and_hms(2026, 10, 19, 10, 0, 0).unwrap(),
+            Local.with_ymd_and_hms(2026, 10, 19, 12, 0, 0).unwrap(),
+            15,
+        ));
+        project
+    }
+
+    /// A repository mock whose lock always succeeds
+    fn mock_repository() -> MockRepository {
+        let mut mock_repo = MockRepository::new();
+        mock_repo
+            .expect_lock()
+            .returning(|| Ok(RepositoryLock::none()));
+        mock_repo
+    }
+
+    fn service_with(project: Project, now: DateTime<Local>) -> SessionService {
+        let mut mock_repo = mock_repository();
+        mock_repo
+            .expect_load()
+            .returning(move || Ok(project.clone()));
+
+        let mut mock_clock = MockClock::new();
+        mock_clock.expect_now().returning(move || now);
+
+        SessionService::new(Box::new(mock_repo), Box::new(mock_clock))
+    }
+
+    #[test]
+    fn test_init_project() {
+        let mut mock_repo = MockRepository::new();
+        mock_repo
+            .expect_create_and_lock()
+            .times(1)
+            .returning(|| Ok(RepositoryLock::none()));
+        mock_repo
+            .expect_load()
+            .returning(|| Err(anyhow!("Not found")));
+        mock_repo.expect_save().returning(|_| Ok(()));
+
+        let mock_clock = MockClock::new();
+        let service = SessionService::new(Box::new(mock_repo), Box::new(mock_clock));
+
+        assert!(service.init_project("test-project".to_string()).is_ok());
+    }
+
+    /// Several `clock-me init` processes in the same directory at once. The
+    /// test binary re-runs this test as each of them; they start together
+    /// once the parent creates the `go` file.
+    #[test]
+    fn test_concurrent_init_creates_one_project() {
+        if let Some(data_dir) = std::env::var_os("CLOCKME_TEST_INIT_DIR") {
+            let go = std::path::Path::new(&data_dir).with_file_name("go");
+            while !go.exists() {
+                std::thread::sleep(std::time::Duration::from_millis(1));
+            }
+            let repository = FileRepository::with_data_dir(data_dir.into());
+            let service = SessionService::new(Box::new(repository), Box::new(SystemClock));
+            let initialized = service.init_project("test-project".to_string());
+            std::process::exit(match initialized {
+                Ok(()) => 0,
+                Err(e) if e.to_string().contains("already initialized") => 3,
+                Err(_) => 4,
+            });
+        }
+
+        let temp_dir = tempfile::TempDir::new().unwrap();
+        let data_dir = temp_dir.path().join(".clockme");
+        let children: Vec<_> = (0..8)
+            .map(|_| {
+                std::process::Command::new(std::env::current_exe().unwrap())
+                    .args([
+                        "--exact",
+                        "session_service::tests::test_concurrent_init_creates_one_project",
+                    ])
+                    .env("CLOCKME_TEST_INIT_DIR", &data_dir)
+                    .stdout(std::process::Stdio::null())
+                    .stderr(std::process::Stdio::null())
+                    .spawn()
+                    .unwrap()
+            })
+            .collect();
+        std::thread::sleep(std::time::Duration::from_millis(200));
+        std::fs::write(temp_dir.path().join("go"), "").unwrap();
+        let codes: Vec<_> = children
+            .into_iter()
+            .map(|mut child| child.wait().unwrap().code())
+            .collect();
+
+        assert_eq!(
+            codes.iter().filter(|&&code| code == Some(0)).count(),
+            1,
+            "{:?}",
+            codes
+        );
+        assert_eq!(
+            codes.iter().filter(|&&code| code == Some(3)).count(),
+            7,
+            "{:?}",
+            codes
+        );
+        let project = FileRepository::with_data_dir(data_dir).load().unwrap();
+        assert_eq!(project.name, "test-project");
+    }
+
+    #[test]
+    fn test_busy_lock_aborts_before_loading() {
+        let mut mock_repo = MockRepository::new();
+        mock_repo
+            .expect_lock()
+            .returning(|| Err(anyhow!("Another clock-me is writing to this project.")));
+        mock_repo.expect_load().never();
+        mock_repo.expect_save().never();
+
+        let mock_clock = MockClock::new();
+        let service = SessionService::new(Box::new(mock_repo), Box::new(mock_clock));
+
+        let err = service.start_session(None, None, Vec::new()).unwrap_err();
+        assert!(err.to_string().contains("Another clock-me"));
+    }
+
+    #[test]
+    fn test_start_session_without_init() {
+        let mut mock_repo = mock_repository();
+        mock_repo
+            .expect_load()
+            .returning(|| Err(anyhow!("Not found")));
+
+        let mock_clock = MockClock::new();
+        let service = SessionService::new(Box::new(mock_repo), Box::new(mock_clock));
+
+        assert!(service.start_session(None, None, Vec::new()).is_err());
+    }
+
+    #[test]
+    fn test_add_session() {
+        let now = Local.with_ymd_and_hms(2026, 10, 20, 18, 0, 0).unwrap();
+        let mut mock_repo = mock_repository();
+        mock_repo.expect_load().returning(|| Ok(sample_project()));
+        mock_repo
+            .expect_save()
+            .withf(|p| p.sessions.len() == 4 && p.sessions[2].is_manual())
+            .returning(|_| Ok(()));
+        let mut mock_clock = MockClock::new();
+        mock_clock.expect_now().returning(move || now);
+        let service = SessionService::new(Box::new(mock_repo), Box::new(mock_clock));
+
+        let (_, session) = service
+            .add_session(
+                Duration::minutes(90),
+                NaiveDate::from_ymd_opt(2026, 10, 13),
+                NaiveTime::from_hms_opt(14, 0, 0),
+                Some("code review".to_string()),
+                vec!["review".to_string()],
+            )
+            .unwrap();
+
+        assert_eq!(
+            session.start,
+            Local.with_ymd_and_hms(2026, 10, 13, 14, 0, 0).unwrap()
+        );
+        assert_eq!(session.note.as_deref(), Some("code review"));
+        assert_eq!(session.tags, vec!["review"]);
+    }
+
+    #[test]
+    fn test_add_session_rejects_overlap_and_future() {
+        let now = Local.with_ymd_and_hms(2026, 10, 20, 18, 0, 0).unwrap();
+        let service = service_with(sample_project(), now);
+
+        // Overlaps 09:00 – 13:00 on the 12th
+        assert!(service
+            .add_session(
+                Duration::hours(1),
+                NaiveDate::from_ymd_opt(2026, 10, 12),
+                NaiveTime::from_hms_opt(12, 30, 0),
+                None,
+                Vec::new(),
+            )
+            .is_err());
+
+        // Ends after now
+        assert!(service
+            .add_session(
+                Duration::hours(2),
+                None,
+                NaiveTime::from_hms_opt(17, 0, 0),
+                None,
+                Vec::new(),
+            )
+            .is_err());
+
+        // Another day without a start time
+        assert!(service
+            .add_session(
+                Duration::hours(1),
+                NaiveDate::from_ymd_opt(2026, 10, 14),
+                None,
+                None,
+                Vec::new(),
+            )
+            .is_err());
+    }
+
+    #[test]
+    fn test_start_and_stop_at_past_time() {
+        let now = Local.with_ymd_and_hms(2026, 10, 20, 12, 0, 0).unwrap();
+        let mut mock_repo = mock_repository();
+        mock_repo.expect_load().returning(|| Ok(sample_project()));
+        mock_repo.expect_save().returning(|_| Ok(()));
+        let mut mock_clock = MockClock::new();
+        mock_clock.expect_now().returning(move || now);
+        let service = SessionService::new(Box::new(mock_repo), Box::new(mock_clock));
+
+        let clock_in = service
+            .start_session(Some(TimeSpec::parse_at("09:15").unwrap()), None, Vec::new())
+            .unwrap();
+        assert_eq!(
+            clock_in.session.start,
+            Local.with_ymd_and_hms(2026, 10, 20, 9, 15, 0).unwrap()
+        );
+
+        // In the future
+        assert!(service
+            .start_session(Some(TimeSpec::parse_at("12:30").unwrap()), None, Vec::new())
+            .is_err());
+
+        // Before the previous session ended (2026-10-19 12:00)
+        assert!(service
+            .start_session(
+                Some(TimeSpec::parse_at("2026-10-19 11:00").unwrap()),
+                None,
+                Vec::new()
+            )
+            .is_err());
+    }
+
+    #[test]
+    fn test_stop_and_break_before_start_rejected() {
+        let now = Local.with_ymd_and_hms(2026, 10, 20, 12, 0, 0).unwrap();
+        let mut project = sample_project();
+        project.start_session(Local.with_ymd_and_hms(2026, 10, 20, 10, 0, 0).unwrap());
+        let service = service_with(project, now);
+
+        assert!(service
+            .end_session(Some(TimeSpec::parse_at("09:30").unwrap()), None)
+            .is_err());
+        assert!(service
+            .start_break(Some(TimeSpec::parse_at("09:59").unwrap()), None)
+            .is_err());
+    }
+
+    #[test]
+    fn test_break_ago() {
+        let now = Local.with_ymd_and_hms(2026, 10, 20, 12, 0, 0).unwrap();
+        let mut project = sample_project();
+        project.start_session(Local.with_ymd_and_hms(2026, 10, 20, 10, 0, 0).unwrap());
+        let mut mock_repo = mock_repository();
+        mock_repo
+            .expect_load()
+            .returning(move || Ok(project.clone()));
+        mock_repo.expect_save().returning(|_| Ok(()));
+        let mut mock_clock = MockClock::new();
+        mock_clock.expect_now().returning(move || now);
+        let service = SessionService::new(Box::new(mock_repo), Box::new(mock_clock));
+
+        let started = service
+            .start_break(
+                Some(TimeSpec::parse_ago("20m").unwrap()),
+                Some("lunch".to_string()),
+            )
+            .unwrap();
+
+        assert_eq!(started.work_time.num_minutes(), 100);
+        let break_period = started.break_period;
+        assert_eq!(break_period.note.as_deref(), Some("lunch"));
+        assert_eq!(
+            break_period.start,
+            Local.with_ymd_and_hms(2026, 10, 20, 11, 40, 0).unwrap()
+        );
+    }
+
+    #[test]
+    fn test_report_by_day() {
+        let now = Local.with_ymd_and_hms(2026, 10, 20, 9, 0, 0).unwrap();
+        let service = service_with(sample_project(), now);
+
+        let report = service
+            .get_report(
+                ReportPeriod::Day,
+                NaiveDate::from_ymd_opt(2026, 10, 12),
+                NaiveDate::from_ymd_opt(2026, 10, 18),
+            )
+            .unwrap();
+
+        assert_eq!(report.buckets.len(), 2);
+        assert_eq!(report.buckets[0].stats.work_time.num_minutes(), 4 * 60 - 30);
+        assert_eq!(report.buckets[0].stats.break_time.num_minutes(), 30);
+        assert_eq!(report.buckets[1].stats.work_time.num_minutes(), 120);
+        assert_eq!(report.total.sessions, 2);
+        assert_eq!(report.total.breaks, 1);
+    }
+
+    #[test]
+    fn test_report_by_week() {
+        let now = Local.with_ymd_and_hms(2026, 10, 20, 9, 0, 0).unwrap();
+        let service = service_with(sample_project(), now);
+
+        let report = service
+            .get_report(
+                ReportPeriod::Week,
+                NaiveDate::from_ymd_opt(2026, 10, 1),
+                None,
+            )
+            .unwrap();
+
+        assert_eq!(report.buckets.len(), 2);
+        assert_eq!(
+            report.buckets[0].start,
+            NaiveDate::from_ymd_opt(2026, 10, 12).unwrap()
+        );
+        assert_eq!(report.buckets[0].stats.sessions, 2);
+        assert_eq!(report.buckets[1].stats.work_time.num_minutes(), 120 - 15);
+        assert_eq!(report.total.work_time.num_minutes(), 210 + 120 + 105);
+    }
+
+    #[test]
+    fn test_set_note_on_last_session() {
+        let now = Local.with_ymd_and_hms(2026, 10, 20, 12, 0, 0).unwrap();
+        let mut mock_repo = mock_repository();
+        mock_repo.expect_load().returning(|| Ok(sample_project()));
+        mock_repo
+            .expect_save()
+            .withf(|p| p.sessions[2].note.as_deref() == Some("release prep"))
+            .returning(|_| Ok(()));
+        let mut mock_clock = MockClock::new();
+        mock_clock.expect_now().returning(move || now);
+        let service = SessionService::new(Box::new(mock_repo), Box::new(mock_clock));
+
+        let session = service.set_note("release prep".to_string(), false).unwrap();
+        assert_eq!(session.note.as_deref(), Some("release prep"));
+    }
+
+    #[test]
+    fn test_report_includes_notes() {
+        let now = Local.with_ymd_and_hms(2026, 10, 20, 9, 0, 0).unwrap();
+        let mut project = sample_project();
+        project.sessions[0].note = Some("planning".to_string());
+        project.sessions[1].note = Some("bugfix".to_string());
+        let mut lunch = Break::new(project.sessions[1].start + Duration::hours(2));
+        lunch.end = Some(lunch.start + Duration::minutes(30));
+        lunch.note = Some("lunch".to_string());
+        project.sessions[1].breaks.push(lunch);
+        let service = service_with(project, now);
+
+        let report = service
+            .get_report(
+                ReportPeriod::Week,
+                NaiveDate::from_ymd_opt(2026, 10, 1),
+                None,
+            )
+            .unwrap();
+
+        assert_eq!(
+            report.buckets[0].notes,
+            vec!["planning", "bugfix", "lunch (break)"]
+        );
+        assert!(report.buckets[1].notes.is_empty());
+    }
+
+    #[test]
+    fn test_report_lists_commits_per_session() {
+        let now = Local.with_ymd_and_hms(2026, 10, 20, 9, 0, 0).unwrap();
+        let mut project = sample_project();
+        let commit = |hash: &str, hour| Commit {
+            hash: hash.to_string(),
+            subject: "Work".to_string(),
+            branch: Some("main".to_string()),
+            at: Local.with_ymd_and_hms(2026, 10, 12, hour, 0, 0).unwrap(),
+        };
+        project.sessions[0].add_commit(commit("aaa", 10));
+        project.sessions[0].add_commit(commit("bbb", 12));
+        project.start_session(now - Duration::hours(1));
+        project
+            .current_session
+            .as_mut()
+            .unwrap()
+            .add_commit(commit("ccc", 8));
+        let service = service_with(project, now);
+
+        let report = service
+            .get_report(
+                ReportPeriod::Week,
+                NaiveDate::from_ymd_opt(2026, 10, 1),
+                None,
+            )
+            .unwrap();
+
+        let first_week = &report.buckets[0].commits;
+        assert_eq!(first_week.len(), 1);
+        assert_eq!(
+            first_week[0].start,
+            Local.with_ymd_and_hms(2026, 10, 12, 9, 0, 0).unwrap()
+        );
+        assert_eq!(first_week[0].commits.len(), 2);
+        let active = &report.buckets[1].commits;
+        assert_eq!(active.len(), 1);
+        assert!(active[0].end.is_none());
+        assert_eq!(active[0].commits[0].hash, "ccc");
+    }
+
+    #[test]
+    fn test_tag_past_session() {
+        let now = Local.with_ymd_and_hms(2026, 10, 20, 12, 0, 0).unwrap();
+        let mut mock_repo = mock_repository();
+        mock_repo.expect_load().returning(|| Ok(sample_project()));
+        mock_repo
+            .expect_save()
+            .withf(|p| p.sessions[1].tags == vec!["meeting"] && p.sessions[2].tags.is_empty())
+            .returning(|_| Ok(()));
+        let mut mock_clock = MockClock::new();
+        mock_clock.expect_now().returning(move || now);
+        let service = SessionService::new(Box::new(mock_repo), Box::new(mock_clock));
+
+        let session = service
+            .tag_session(vec!["meeting".to_string()], Some("2".to_string()), false)
+            .unwrap();
+        assert_eq!(
+            session.start,
+            Local.with_ymd_and_hms(2026, 10, 13, 9, 0, 0).unwrap()
+        );
+
+        assert!(service
+            .tag_session(vec!["meeting".to_string()], Some("4".to_string()), false)
+            .is_err());
+    }
+
+    #[test]
+    fn test_tag_session_by_id() {
+        let (service, ids) = editing_service(true);
+
+        let session = service
+            .tag_session(vec!["meeting".to_string()], Some(ids[0].clone()), false)
+            .unwrap();
+        assert_eq!(session.short_id(), ids[0]);
+        assert_eq!(session.tags, vec!["meeting"]);
+    }
+
+    #[test]
+    fn test_report_tag_breakdown() {
+        let now = Local.with_ymd_and_hms(2026, 10, 20, 9, 0, 0).unwrap();
+        let mut project = sample_project();
+        project.sessions[0].tags = vec!["review".to_string(), "backend".to_string()];
+        project.sessions[1].tags = vec!["review".to_string()];
+        let service = service_with(project, now);
+
+        let report = service
+            .get_report(
+                ReportPeriod::Week,
+                NaiveDate::from_ymd_opt(2026, 10, 1),
+                None,
+            )
+            .unwrap();
+
+        let tags: Vec<(Option<&str>, i64)> = report
+            .tags
+            .iter()
+            .map(|t| (t.tag.as_deref(), t.stats.work_time.num_minutes()))
+            .collect();
+        assert_eq!(
+            tags,
+            vec![
+                (Some("backend"), 210),
+                (Some("review"), 210 + 120),
+                (None, 105),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_get_sessions_in_range() {
+        let now = Local.with_ymd_and_hms(2026, 10, 20, 9, 0, 0).unwrap();
+        let mut project = sample_project();
+        project.start_session(Local.with_ymd_and_hms(2026, 10, 20, 8, 0, 0).unwrap());
+        let service = service_with(project, now);
+
+        let (name, sessions) = service
+            .get_sessions(NaiveDate::from_ymd_opt(2026, 10, 13), None)
+            .unwrap();
+        assert_eq!(name, "test-project");
+        // The active session is not exported
+        assert_eq!(sessions.len(), 2);
+
+        let (_, sessions) = service
+            .get_sessions(None, NaiveDate::from_ymd_opt(2026, 10, 12))
+            .unwrap();
+        assert_eq!(sessions.len(), 1);
+    }
+
+    #[test]
+    fn test_import_sessions_merges_and_skips_duplicates() {
+        let now = Local.with_ymd_and_hms(2026, 10, 20, 18, 0, 0).unwrap();
+        let mut mock_repo = mock_repository();
+        mock_repo.expect_load().returning(|| Ok(sample_project()));
+        mock_repo
+            .expect_save()
+            .times(1)
+            .withf(|p| p.sessions.len() == 4)
+            .returning(|_| Ok(()));
+        let mut mock_clock = MockClock::new();
+        mock_clock.expect_now().returning(move || now);
+        let service = SessionService::new(Box::new(mock_repo), Box::new(mock_clock));
+
+        let existing = sample_project().sessions[0].clone();
+        let new = Session::imported(
+            Local.with_ymd_and_hms(2026, 10, 14, 9, 0, 0).unwrap(),
+            Local.with_ymd_and_hms(2026, 10, 14, 10, 0, 0).unwrap(),
+        );
+
+        let summary = service.import_sessions(vec![existing, new]).unwrap();
+        assert_eq!(summary.imported, 1);
+        assert_eq!(summary.skipped, 1);
+    }
+
+    #[test]
+    fn test_import_sessions_rejects_overlap() {
+        let now = Local.with_ymd_and_hms(2026, 10, 20, 18, 0, 0).unwrap();
+        let service = service_with(sample_project(), now);
+
+        let overlapping = Session::imported(
+            Local.with_ymd_and_hms(2026, 10, 12, 12, 0, 0).unwrap(),
+            Local.with_ymd_and_hms(2026, 10, 12, 14, 0, 0).unwrap(),
+        );
+
+        let result = service.import_sessions(vec![overlapping]);
+        assert!(result.is_err());
+    }
+
+    #[test]
+    fn test_report_invalid_range() {
+        let now = Local.with_ymd_and_hms(2026, 10, 20, 9, 0, 0).unwrap();
+        let service = service_with(sample_project(), now);
+
+        let result = service.get_report(
+            ReportPeriod::Month,
+            NaiveDate::from_ymd_opt(2026, 10, 20),
+            NaiveDate::from_ymd_opt(2026, 10, 1),
+        );
+        assert!(result.is_err());
+    }
+
+    #[test]
+    fn test_status_includes_active_session() {
+        let now = Local.with_ymd_and_hms(2026, 10, 19, 15, 0, 0).unwrap();
+        let mut project = sample_project();
+        project.start_session(Local.with_ymd_and_hms(2026, 10, 19, 13, 0, 0).unwrap());
+        project
+            .start_break(Local.with_ymd_and_hms(2026, 10, 19, 14, 30, 0).unwrap())
+            .unwrap();
+        let service = service_with(project, now);
+
+        let status = service.get_status().unwrap();
+
+        assert_eq!(status.today_sessions, 2);
+        assert_eq!(status.today_breaks, 2);
+        assert_eq!(status.today_work_time.num_minutes(), 105 + 90);
+        assert_eq!(status.today_break_time.num_minutes(), 15 + 30);
+        assert_eq!(status.total_sessions, 3);
+    }
+
+    /// A service over `sample_project()` and the short IDs of its sessions
+    fn editing_service(expect_save: bool) -> (SessionService, Vec<String>) {
+        let now = Local.with_ymd_and_hms(2026, 10, 20, 18, 0, 0).unwrap();
+        let project = sample_project();
+        let ids = project.sessions.iter().map(|s| s.short_id()).collect();
+
+        let mut mock_repo = mock_repository();
+        let stored = project.clone();
+        mock_repo
+            .expect_load_strict()
+            .returning(move || Ok(stored.clone()));
+        mock_repo
+            .expect_load()
+            .returning(move || Ok(project.clone()));
+        mock_repo
+            .expect_save()
+            .times(if expect_save { 1 } else { 0 })
+            .returning(|_| Ok(()));
+        let mut mock_clock = MockClock::new();
+        mock_clock.expect_now().returning(move || now);
+
+        let service = SessionService::new(Box::new(mock_repo), Box::new(mock_clock));
+        (service, ids)
+    }
+
+    #[test]
+    fn test_get_log_keeps_most_recent() {
+        let now = Local.with_ymd_and_hms(2026, 10, 20, 18, 0, 0).unwrap();
+        let service = service_with(sample_project(), now);
+
+        let (_, sessions) = service.get_log(None, None, 2).unwrap();
+        let days: Vec<u32> = sessions.iter().map(|s| s.start.day()).collect();
+        assert_eq!(days, [13, 19]);
+
+        let (_, sessions) = service
+            .get_log(NaiveDate::from_ymd_opt(2026, 10, 13), None, 20)
+            .unwrap();
+        assert_eq!(sessions.len(), 2);
+    }
+
+    #[test]
+    fn test_edit_session_on_its_own_date() {
+        let (service, ids) = editing_service(true);
+
+        let session = service
+            .edit_session(&ids[0], None, Some(TimeSpec::parse_at("14:00").unwrap()))
+            .unwrap();
+
+        assert_eq!(
+            session.end,
+            Some(Local.with_ymd_and_hms(2026, 10, 12, 14, 0, 0).unwrap())
+        );
+    }
+
+    #[test]
+    fn test_edit_session_rejects_overlap() {
+        let (service, ids) = editing_service(false);
+
+        let err = service
+            .edit_session(
+                &ids[0],
+                None,
+                Some(TimeSpec::parse_at("2026-10-13 10:00").unwrap()),
+            )
+            .unwrap_err();
+        assert!(err.to_string().contains("Overlaps"));
+    }
+
+    #[test]
+    fn test_edit_unknown_session() {
+        let (service, _) = editing_service(false);
+
+        assert!(service
+            .edit_session("ffffffff", None, Some(TimeSpec::parse_at("14:00").unwrap()))
+            .is_err());
+    }
+
+    #[test]
+    fn test_edit_break_must_stay_within_session() {
+        let (service, ids) = editing_service(false);
+
+        assert!(service
+            .edit_break(
+                &ids[0],
+                "1",
+                None,
+                Some(TimeSpec::parse_at("13:30").unwrap())
+            )
+            .is_err());
+        assert!(service
+            .edit_break(
+                &ids[1],
+                "1",
+                None,
+                Some(TimeSpec::parse_at("10:00").unwrap())
+            )
+            .is_err());
+    }
+
+    #[test]
+    fn test_edit_break_end() {
+        let (service, ids) = editing_service(true);
+
+        let session = service
+            .edit_break(
+                &ids[0],
+                "1",
+                None,
+                Some(TimeSpec::parse_at("10:40").unwrap()),
+            )
+            .unwrap();
+        assert_eq!(session.total_break_time(), Duration::minutes(40));
+    }
+
+    #[test]
+    fn test_delete_session_and_break() {
+        let (service, ids) = editing_service(true);
+        let session = service.delete_session(&ids[1]).unwrap();
+        assert_eq!(
+            session.start,
+            Local.with_ymd_and_hms(2026, 10, 13, 9, 0, 0).unwrap()
+        );
+
+        let (service, ids) = editing_service(true);
+        let break_period = service.delete_break(&ids[2], "1").unwrap();
+        assert_eq!(
+            break_period.start,
+            Local.with_ymd_and_hms(2026, 10, 19, 11, 0, 0).unwrap()
+        );
+    }
+
+    #[test]
+    fn test_split_session_on_its_own_date() {
+        let (service, ids) = editing_service(true);
+        let (first, second) = service
+            .split_session(&ids[0], TimeSpec::parse_at("10:15").unwrap())
+            .unwrap();
+
+        let at = Local.with_ymd_and_hms(2026, 10, 12, 10, 15, 0).unwrap();
+        assert_eq!((first.end, second.start), (Some(at), at));
+        assert_eq!(first.total_break_time(), Duration::minutes(15));
+        assert_eq!(second.total_break_time(), Duration::minutes(15));
+
+        let (service, ids) = editing_service(false);
+        assert!(service
+            .split_session(&ids[0], TimeSpec::parse_at("14:00").unwrap())
+            .is_err());
+    }
+
+    #[test]
+    fn test_merge_sessions_requires_neighbours() {
+        let (service, ids) = editing_service(true);
+        let merged = service.merge_sessions(&ids[1], &ids[0], true).unwrap();
+        assert_eq!(merged.start.day(), 12);
+        assert_eq!(merged.end.unwrap().day(), 13);
+        assert_eq!(merged.breaks.len(), 2);
+
+        let (service, ids) = editing_service(false);
+        assert!(service.merge_sessions(&ids[0], &ids[2], true).is_err());
+    }
+
+    #[test]
+    fn test_merge_sessions_across_days_needs_force() {
+        let (service, ids) = editing_service(false);
+        let err = service.merge_sessions(&ids[0], &ids[1], false).unwrap_err();
+        assert!(err.to_string().contains("different days"));
+    }
+
+    #[test]
+    fn test_repair_integrity_backs_up_first() {
+        let temp_dir = tempfile::TempDir::new().unwrap();
+        let now = Local.with_ymd_and_hms(2026, 10, 20, 18, 0, 0).unwrap();
+        let mut project = sample_project();
+        project.sessions.swap(0, 2);
+        project.current_break = Some(Break::new(now - Duration::hours(1)));
+
+        let saved = project.clone();
+        let mut mock_repo = mock_repository();
+        mock_repo
+            .expect_load_strict()
+            .returning(move || Ok(saved.clone()));
+        mock_repo
+            .expect_save()
+            .times(1)
+            .withf(|p| p.current_break.is_none() && p.sessions.is_sorted_by_key(|s| s.start))
+            .returning(|_| Ok(()));
+        let mut mock_clock = MockClock::new();
+        mock_clock.expect_now().returning(move || now);
+        let service = SessionService::new(Box::new(mock_repo), Box::new(mock_clock));
+
+        assert_eq!(service.check_integrity().unwrap().len(), 3);
+        let summary = service.repair_integrity(temp_dir.path()).unwrap();
+        assert_eq!(summary.repairs.len(), 2);
+        assert!(summary.remaining.is_empty());
+
+        let backup = std::fs::read_to_string(summary.backup.unwrap()).unwrap();
+        let (restored, _) = crate::repository::migrations::load(&backup).unwrap();
+        assert!(restored.current_break.is_some());
+        assert_eq!(restored.sessions[0].start, project.sessions[0].start);
+    }
+
+    #[test]
+    fn test_repair_integrity_without_issues_saves_nothing() {
+        let temp_dir = tempfile::TempDir::new().unwrap();
+        let (service, _) = editing_service(false);
+
+        let summary = service.repair_integrity(temp_dir.path()).unwrap();
+        assert!(summary.repairs.is_empty() && summary.backup.is_none());
+        assert!(!temp_dir.path().join("backups").exists());
+    }
+
+    #[test]
+    fn test_integrity_of_unreadable_data() {
+        let temp_dir = tempfile::TempDir::new().unwrap();
+        let mut mock_repo = mock_repository();
+        mock_repo
+            .expect_load_strict()
+            .returning(|| Err(anyhow!("expected value at line 3 column 5")));
+        mock_repo.expect_save().times(0);
+        let service = SessionService::new(Box::new(mock_repo), Box::new(MockClock::new()));
+
+        let issues = service.check_integrity().unwrap();
+        assert_eq!(issues.len(), 1);
+        assert!(issues[0].is_unreadable() && !issues[0].fixable);
+        assert!(issues[0].problem.contains("line 3 column 5"));
+
+        let summary = service.repair_integrity(temp_dir.path()).unwrap();
+        assert!(summary.repairs.is_empty() && summary.backup.is_none());
+        assert_eq!(summary.remaining, issues);
+    }
+
+    #[test]
+    fn test_change_loads_the_project_once() {
+        let temp_dir = tempfile::TempDir::new().unwrap();
+        let now = Local.with_ymd_and_hms(2026, 10, 20, 9, 0, 0).unwrap();
+        let project = sample_project();
+        let stored = project.clone();
+        let mut mock_repo = mock_repository();
+        mock_repo
+            .expect_load()
+            .times(1)
+            .returning(move || Ok(stored.clone()));
+        mock_repo.expect_save().times(1).returning(|_| Ok(()));
+        let mut mock_clock = MockClock::new();
+        mock_clock.expect_now().returning(move || now);
+        let history = History::new(temp_dir.path());
+        let service = SessionService::new(Box::new(mock_repo), Box::new(mock_clock))
+            .with_history(History::new(temp_dir.path()));
+
+        service.start_session(None, None, Vec::new()).unwrap();
+
+        let mut restored = project.clone();
+        restored.start_session(now);
+        let undone = history.load().unwrap().undo(&mut restored, 1).unwrap();
+        assert_eq!(undone[0].action, "start");
+        assert!(restored.current_session.is_none());
+    }
+
+    #[test]
+    fn test_undo_and_redo_stop() {
+        let temp_dir = tempfile::TempDir::new().unwrap();
+        let data_dir = temp_dir.path().join(".clockme");
+        let repository = FileRepository::with_data_dir(data_dir.clone());
+        let mut project = Project::new("test-project".to_string());
+        project.start_session(Local.with_ymd_and_hms(2026, 10, 17, 9, 0, 0).unwrap());
+        repository.save(&project).unwrap();
+
+        let now = Local.with_ymd_and_hms(2026, 10, 17, 17, 0, 0).unwrap();
+        let mut mock_clock = MockClock::new();
+        mock_clock.expect_now().returning(move || now);
+        let service = SessionService::new(Box::new(repository), Box::new(mock_clock))
+            .with_history(History::new(&data_dir));
+
+        service.end_session(None, None).unwrap();
+        let load = || {
+            FileRepository::with_data_dir(data_dir.clone())
+                .load()
+                .unwrap()
+        };
+        assert!(load().current_session.is_none());
+
+        let undone = service.undo(1).unwrap();
+        assert_eq!(undone[0].action, "stop");
+        let restored = load();
+        assert!(restored.current_session.is_some());
+        assert!(restored.sessions.is_empty());
+
+        service.redo(1).unwrap();
+        assert_eq!(load().sessions.len(), 1);
+        assert!(service.redo(1).is_err());
+    }
+
+    #[test]
+    fn test_record_commit() {
+        let now = Local.with_ymd_and_hms(2026, 10, 20, 11, 0, 0).unwrap();
+        let record = |working: bool, auto_start: bool, saves: usize| {
+            let mut project = sample_project();
+            if working {
+                project.start_session(now - Duration::hours(2));
+            }
+            let mut mock_repo = mock_repository();
+            mock_repo
+                .expect_load()
+                .returning(move || Ok(project.clone()));
+            mock_repo
+                .expect_save()
+                .times(saves)
+                .withf(move |p| {
+                    let session = p.current_session.as_ref().unwrap();
+                    session.commits.len() == 1
+                        && session.commits[0].branch.as_deref() == Some("main")
+                        && session.commits[0].at == now
+                        && p.sessions.len() == 3
+                })
+                .returning(|_| Ok(()));
+            let mut mock_clock = MockClock::new();
+            mock_clock.expect_now().returning(move || now);
+            let service = SessionService::new(Box::new(mock_repo), Box::new(mock_clock));
+
+            service
+                .record_commit(
+                    "a1b2c3d4".to_string(),
+                    "Fix parser".to_string(),
+                    Some("main".to_string()),
+                    auto_start,
+                )
+                .unwrap()
+        };
+
+        assert_eq!(record(true, false, 1), CommitOutcome::Recorded);
+        assert_eq!(record(false, false, 0), CommitOutcome::Ignored);
+        assert_eq!(record(false, true, 1), CommitOutcome::Started);
+    }
+
+    #[test]
+    fn test_record_commit_once() {
+        let now = Local.with_ymd_and_hms(2026, 10, 20, 11, 0, 0).unwrap();
+        let mut project = sample_project();
+        project.start_session(now - Duration::hours(2));
+        project
+            .current_session
+            .as_mut()
+            .unwrap()
+            .add_commit(Commit {
+                hash: "a1b2c3d4".to_string(),
+                subject: "Fix parser".to_string(),
+                branch: None,
+                at: now - Duration::minutes(5),
+            });
+        let mut mock_repo = mock_repository();
+        mock_repo
+            .expect_load()
+            .returning(move || Ok(project.clone()));
+        mock_repo.expect_save().never();
+        let mut mock_clock = MockClock::new();
+        mock_clock.expect_now().returning(move || now);
+        let service = SessionService::new(Box::new(mock_repo), Box::new(mock_clock));
+
+        let outcome = service
+            .record_commit(
+                "a1b2c3d4".to_string(),
+                "Fix parser".to_string(),
+                None,
+                false,
+            )
+            .unwrap();
+        assert_eq!(outcome, CommitOutcome::Ignored);
+    }
+
+    #[test]
+    fn test_record_checkout() {
+        let now = Local.with_ymd_and_hms(2026, 10, 20, 11, 0, 0).unwrap();
+        let mut working = sample_project();
+        working.start_session(now - Duration::hours(1));
+        let mut mock_repo = mock_repository();
+        mock_repo
+            .expect_load()
+            .returning(move || Ok(working.clone()));
+        mock_repo
+            .expect_save()
+            .times(1)
+            .withf(move |p| {
+                p.current_session.as_ref().unwrap().checkouts
+                    == vec![Checkout {
+                        branch: "feature".to_string(),
+                        at: now,
+                    }]
+            })
+            .returning(|_| Ok(()));
+        let mut mock_clock = MockClock::new();
+        mock_clock.expect_now().returning(move || now);
+        let service = SessionService::new(Box::new(mock_repo), Box::new(mock_clock));
+        assert!(service.record_checkout("feature".to_string()).unwrap());
+
+        // Clocked out: nothing to record, and no session is started
+        let service = service_with(sample_project(), now);
+        assert!(!service.record_checkout("feature".to_string()).unwrap());
+    }
+
+    #[test]
+    fn test_rewrite_commits() {
+        let now = Local.with_ymd_and_hms(2026, 10, 20, 11, 0, 0).unwrap();
+        let mut project = sample_project();
+        project.start_session(now - Duration::hours(1));
+        let commit = |hash: &str| Commit {
+            hash: hash.to_string(),
+            subject: "Fix parser".to_string(),
+            branch: Some("main".to_string()),
+            at: now - Duration::minutes(30),
+        };
+        project.sessions[0].add_commit(commit("aaa"));
+        let session = project.current_session.as_mut().unwrap();
+        session.add_commit(commit("bbb"));
+        session.add_commit(commit("ccc"));
+
+        let mut mock_repo = mock_repository();
+        mock_repo
+            .expect_load()
+            .returning(move || Ok(project.clone()));
+        mock_repo
+            .expect_save()
+            .times(1)
+            .withf(|p| {
+                let hashes = |session: &Session| -> Vec<String> {
+                    session.commits.iter().map(|c| c.hash.clone()).collect()
+                };
+                hashes(&p.sessions[0]) == ["ddd"]
+                    && hashes(p.current_session.as_ref().unwrap()) == ["ccc"]
+            })
+            .returning(|_| Ok(()));
+        let mut mock_clock = MockClock::new();
+        mock_clock.expect_now().returning(move || now);
         let service = SessionService::new(Box::new(mock_repo), Box::new(mock_clock));
 
-        assert!(service.start_session().is_err());
+        let rewrites = [
+            ("aaa".to_string(), "ddd".to_string()),
+            ("bbb".to_string(), "ccc".to_string()),
+            ("eee".to_string(), "fff".to_string()),
+        ];
+        assert_eq!(service.rewrite_commits(&rewrites).unwrap(), 2);
     }
 }
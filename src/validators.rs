@@ -1,4 +1,5 @@
 use anyhow::{anyhow, Result};
+use chrono::{DateTime, Local};
 use regex::Regex;
 
 pub struct ProjectValidator;
@@ -33,6 +34,107 @@ impl ProjectValidator {
     }
 }
 
+pub struct TagValidator;
+
+impl TagValidator {
+    pub const MAX_LEN: usize = 30;
+
+    /// Validates that a tag name is alphanumeric with hyphens/underscores
+    ///
+    /// Rules:
+    /// - Must not be empty
+    /// - Must be 30 characters or less
+    /// - Can only contain letters, numbers, hyphens, and underscores
+    /// - Must start with a letter or number
+    pub fn validate_name(tag: &str) -> Result<()> {
+        if tag.is_empty() {
+            return Err(anyhow!("Tag cannot be empty"));
+        }
+
+        if tag.len() > Self::MAX_LEN {
+            return Err(anyhow!(
+                "Tag '{}' must be {} characters or less",
+                tag,
+                Self::MAX_LEN
+            ));
+        }
+
+        let re = Regex::new(r"^[a-zA-Z0-9][a-zA-Z0-9_-]*$").unwrap();
+
+        if !re.is_match(tag) {
+            return Err(anyhow!(
+                "Tag '{}' must start with a letter or number and can only contain letters, numbers, hyphens, and underscores",
+                tag
+            ));
+        }
+
+        Ok(())
+    }
+}
+
+pub struct NoteValidator;
+
+impl NoteValidator {
+    pub const MAX_LEN: usize = 500;
+
+    /// Validates a session or break note
+    ///
+    /// Rules:
+    /// - Must not be empty or whitespace only
+    /// - Must be 500 characters or less
+    /// - Must be a single line
+    pub fn validate(note: &str) -> Result<()> {
+        if note.trim().is_empty() {
+            return Err(anyhow!("Note cannot be empty"));
+        }
+
+        if note.chars().count() > Self::MAX_LEN {
+            return Err(anyhow!("Note must be {} characters or less", Self::MAX_LEN));
+        }
+
+        if note.contains('\n') || note.contains('\r') {
+            return Err(anyhow!("Note must be a single line"));
+        }
+
+        Ok(())
+    }
+}
+
+pub struct TimestampValidator;
+
+impl TimestampValidator {
+    /// Validates a user-supplied timestamp for start/stop/break
+    ///
+    /// Rules:
+    /// - Must not be in the future
+    /// - Must not be before `earliest`, described by `what` in the error
+    pub fn validate(
+        time: DateTime<Local>,
+        now: DateTime<Local>,
+        earliest: Option<(DateTime<Local>, &str)>,
+    ) -> Result<()> {
+        if time > now {
+            return Err(anyhow!(
+                "Time {} is in the future",
+                time.format("%Y-%m-%d %H:%M")
+            ));
+        }
+
+        if let Some((earliest, what)) = earliest {
+            if time < earliest {
+                return Err(anyhow!(
+                    "Time {} is before {} ({})",
+                    time.format("%Y-%m-%d %H:%M"),
+                    what,
+                    earliest.format("%Y-%m-%d %H:%M")
+                ));
+            }
+        }
+
+        Ok(())
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -87,4 +189,55 @@ mod tests {
         assert!(result.is_err());
         assert!(result.unwrap_err().to_string().contains("50 characters"));
     }
+
+    #[test]
+    fn test_valid_tags() {
+        assert!(TagValidator::validate_name("review").is_ok());
+        assert!(TagValidator::validate_name("backend_api").is_ok());
+        assert!(TagValidator::validate_name("on-call").is_ok());
+        assert!(TagValidator::validate_name("Q4").is_ok());
+    }
+
+    #[test]
+    fn test_invalid_tags() {
+        assert!(TagValidator::validate_name("").is_err());
+        assert!(TagValidator::validate_name("code review").is_err());
+        assert!(TagValidator::validate_name("#review").is_err());
+        assert!(TagValidator::validate_name("-review").is_err());
+        assert!(TagValidator::validate_name(&"a".repeat(31)).is_err());
+
+        let result = TagValidator::validate_name("a,b");
+        assert!(result.unwrap_err().to_string().contains("Tag 'a,b'"));
+    }
+
+    #[test]
+    fn test_note_validation() {
+        assert!(NoteValidator::validate("fixed login bug").is_ok());
+        assert!(NoteValidator::validate("review: PR #42, \"quoted\"").is_ok());
+
+        assert!(NoteValidator::validate("").is_err());
+        assert!(NoteValidator::validate("   ").is_err());
+        assert!(NoteValidator::validate("two\nlines").is_err());
+        assert!(NoteValidator::validate(&"a".repeat(501)).is_err());
+    }
+
+    #[test]
+    fn test_timestamp_validation() {
+        use chrono::TimeZone;
+        let now = Local.with_ymd_and_hms(2025, 10, 13, 12, 0, 0).unwrap();
+        let earlier = Local.with_ymd_and_hms(2025, 10, 13, 9, 0, 0).unwrap();
+        let later = Local.with_ymd_and_hms(2025, 10, 13, 13, 0, 0).unwrap();
+
+        assert!(TimestampValidator::validate(now, now, None).is_ok());
+        assert!(TimestampValidator::validate(earlier, now, Some((earlier, "start"))).is_ok());
+
+        let result = TimestampValidator::validate(later, now, None);
+        assert!(result.unwrap_err().to_string().contains("in the future"));
+
+        let result = TimestampValidator::validate(earlier, now, Some((now, "the session start")));
+        assert!(result
+            .unwrap_err()
+            .to_string()
+            .contains("before the session start"));
+    }
 }